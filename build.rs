@@ -4,4 +4,4 @@ extern crate lalrpop;
 
 fn main() {
     lalrpop::process_root().unwrap();
-}
\ No newline at end of file
+}
@@ -0,0 +1,91 @@
+#ifndef TWOC_H
+#define TWOC_H
+
+/* Generated from src/ffi.rs by `cargo run -p xtask -- header`, so don't edit this file by hand */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// twoc_run's result when the word is accepted
+#define TWOC_ACCEPTED 1
+
+// twoc_run's result when the word is rejected
+#define TWOC_REJECTED 0
+
+// The result of a call that failed, which twoc_last_error describes.
+// Calls also fail this way if twoc panics, rather than unwinding into the caller.
+#define TWOC_ERROR -1
+
+// A compiled program, made by twoc_compile and freed by twoc_free.
+// twoc_stats and twoc_run only read it, so one automaton can be used from several threads at once,
+// as long as none of them frees it while the others are still using it.
+typedef struct TwocAutomaton TwocAutomaton;
+
+// The size and kind of a compiled program's automaton
+typedef struct TwocStats {
+  // The number of states in the automaton
+  uint32_t states;
+  // The number of transitions in the automaton
+  uint64_t transitions;
+  // The number of letters in the program's alphabet
+  uint32_t alphabet_size;
+  // Whether the program has no branch or while-choose statements
+  bool deterministic;
+  // Whether the automaton never moves left
+  bool one_way;
+} TwocStats;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Compile a program from its source code.
+// Returns NULL if the program doesn't compile, and twoc_last_error then lists what's wrong with it.
+//
+// # Safety
+// source has to be NULL or a nul-terminated string.
+struct TwocAutomaton *twoc_compile(const char *source);
+
+// Free a compiled program. Does nothing if automaton is NULL.
+//
+// # Safety
+// automaton has to be NULL or have come from twoc_compile, and can't be used after it's freed.
+void twoc_free(struct TwocAutomaton *automaton);
+
+// Fill in the statistics of a compiled program.
+// Returns 0, or TWOC_ERROR if either pointer is NULL.
+//
+// # Safety
+// automaton has to be NULL or have come from twoc_compile, and stats has to be NULL or point to a TwocStats.
+int twoc_stats(const struct TwocAutomaton *automaton,
+               struct TwocStats *stats);
+
+// Run a compiled program on a word.
+// algorithm names the simulator to use (e.g. "glueck_array" or "rytter"), or is NULL to pick one from the program:
+// "streaming" for one-way programs, "glueck_array" for deterministic ones and "rytter" otherwise.
+// The simulator gives up after max_steps steps, unless max_steps is 0.
+// threads is the number of threads "rytter_parallel" uses, or 0 to use one per CPU; the other simulators ignore it.
+// Returns TWOC_ACCEPTED or TWOC_REJECTED, or TWOC_ERROR if the simulator can't run the program,
+// the word has letters outside of the alphabet or the simulator gave up.
+//
+// # Safety
+// automaton has to be NULL or have come from twoc_compile, and word and algorithm have to be NULL or
+// nul-terminated strings.
+int twoc_run(const struct TwocAutomaton *automaton,
+             const char *word,
+             const char *algorithm,
+             uint64_t max_steps,
+             uint32_t threads);
+
+// The message of the last call on this thread that failed, or NULL if none has.
+// Each thread has its own last error, so a call failing on one thread doesn't change what another sees.
+// The string belongs to twoc, and stays valid until the next call on this thread fails.
+const char *twoc_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* TWOC_H */
@@ -0,0 +1,254 @@
+// Frozen form of an automaton that the simulators run on
+
+use hashbrown::{HashMap, HashSet};
+
+use crate::automaton::autom::Autom;
+use crate::automaton::generic_autom::State;
+use crate::parser::ast::{Cond, Readable};
+use crate::simulation::budget::Fault;
+
+// Tape symbols are indices into the automaton's symbol table
+pub type Symbol = u16;
+
+// An input word, with endmarkers, as a list of symbols
+pub type Tape = Vec<Symbol>;
+
+// The effect of a transition once its condition has been checked
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Action {
+    // The state this goes to
+    pub goto : State,
+
+    // How much the counter changes by
+    pub incr_by : i32,
+
+    // How far the read head moves
+    pub move_by : i32,
+}
+
+// Bit sets over states
+type StateSet = Vec<u64>;
+
+// An automaton with all of its conditions evaluated ahead of time
+// For every state, tape symbol and counter_zero flag, the actions that can fire are stored
+// next to each other in one array, so simulators never have to look at a Cond tree or a HashMap
+#[derive(Debug, Clone)]
+pub struct CompiledAutom {
+    // The number of states in the automaton
+    pub state_total : State,
+
+    // The tape alphabet (excluding the endmarkers)
+    pub alpha : HashSet<char>,
+
+    // Flag to store whether or not decrementing the counter past zero should be allowed
+    pub decr_zero : bool,
+
+    // The tape symbols the automaton can tell apart
+    // lend and rend come first, and the last one stands for every character the program never mentions
+    symbols : Vec<Readable>,
+
+    // The symbol each character the program mentions is read as
+    symbol_indices : HashMap<char, Symbol>,
+
+    // The range of actions for each (state, symbol, counter_zero), indexed by guard_index
+    guards : Vec<(u32, u32)>,
+
+    // The actions of every guard, one after the other
+    actions : Vec<Action>,
+
+    // Accepting and rejecting states
+    accepting : StateSet,
+    rejecting : StateSet,
+
+    // The statement each state comes from, if it's known
+    sources : Vec<Option<String>>,
+}
+
+impl CompiledAutom {
+    // Compile an automaton, evaluating every condition on every symbol and counter_zero flag
+    pub fn new(autom : &Autom) -> Self {
+        // Every character the automaton can tell apart from the others
+        let mut chars : HashSet<char> = autom.alpha.iter().copied().collect();
+        for transitions in autom.state_map.values() {
+            for trans in transitions {
+                if let Some(ref cond) = trans.condition {
+                    cond_chars(cond, &mut chars);
+                }
+            }
+        }
+
+        let mut sorted_chars : Vec<char> = chars.into_iter().collect();
+        sorted_chars.sort();
+
+        // A character that doesn't appear in any condition, to stand in for all of those characters
+        let other = (0..).filter_map(char::from_u32).find(|c| !sorted_chars.contains(c)).unwrap();
+
+        let mut symbols = vec![Readable::LEnd(), Readable::REnd()];
+        symbols.extend(sorted_chars.iter().map(|c| Readable::Char(*c)));
+        symbols.push(Readable::Char(other));
+
+        let symbol_indices = sorted_chars.iter()
+            .enumerate()
+            .map(|(i, c)| (*c, (i + 2) as Symbol))
+            .collect();
+
+        // Evaluate the guards
+        let mut guards = Vec::new();
+        let mut actions = Vec::new();
+
+        for state in 0..autom.state_total {
+            let transitions = autom.state_map.get(&state).expect("State doesn't exist in automaton!");
+
+            for symbol in &symbols {
+                for counter_zero in [true, false] {
+                    let start = actions.len();
+
+                    for trans in transitions {
+                        let enabled = match trans.condition {
+                            None => true,
+                            Some(ref cond) => cond.check(*symbol, if counter_zero {0} else {1}),
+                        };
+
+                        let action = Action { goto : trans.goto, incr_by : trans.incr_by, move_by : trans.move_by };
+
+                        // Transitions that do exactly the same thing are only stored once
+                        if enabled && !actions[start..].contains(&action) {
+                            actions.push(action);
+                        }
+                    }
+
+                    guards.push((start as u32, actions.len() as u32));
+                }
+            }
+        }
+
+        // Find the halting states
+        let words = (autom.state_total as usize).div_ceil(64);
+        let mut accepting = vec![0; words];
+        let mut rejecting = vec![0; words];
+
+        for state in 0..autom.state_total {
+            match autom.check_if_halting(state) {
+                Some(true)  => accepting[state as usize / 64] |= 1 << (state % 64),
+                Some(false) => rejecting[state as usize / 64] |= 1 << (state % 64),
+                None => (),
+            }
+        }
+
+        Self {
+            state_total : autom.state_total,
+            alpha : autom.alpha.iter().copied().collect(),
+            decr_zero : autom.decr_zero,
+            symbols,
+            symbol_indices,
+            guards,
+            actions,
+            accepting,
+            rejecting,
+            sources : (0..autom.state_total).map(|state| autom.sources.get(&state).cloned()).collect(),
+        }
+    }
+
+    // Turn a string into a tape, adding the endmarkers
+    pub fn encode(&self, input : &str) -> Tape {
+        let other = (self.symbols.len() - 1) as Symbol;
+
+        let mut tape = Vec::with_capacity(input.len() + 2);
+
+        tape.push(0);
+        for c in input.chars() {
+            tape.push(*self.symbol_indices.get(&c).unwrap_or(&other));
+        }
+        tape.push(1);
+
+        tape
+    }
+
+    // The number of symbols the automaton can tell apart
+    pub fn num_symbols(&self) -> usize {
+        self.symbols.len()
+    }
+
+    // The Readable that a symbol stands for
+    pub fn readable(&self, symbol : Symbol) -> Readable {
+        self.symbols[symbol as usize]
+    }
+
+    // Get the actions that can fire from a state on a given symbol
+    pub fn transitions(&self, state : State, symbol : Symbol, counter_zero : bool) -> &[Action] {
+        let (start, end) = self.guards[self.guard_index(state, symbol, counter_zero)];
+        &self.actions[start as usize..end as usize]
+    }
+
+    // Get the action that fires from a state on a given symbol, if one exists
+    // Fails if there's more than one, since then the automaton is nondeterministic
+    pub fn transition(&self, state : State, symbol : Symbol, counter_zero : bool) -> Result<Option<Action>, Fault> {
+        match self.transitions(state, symbol, counter_zero) {
+            [] => Ok(None),
+            [action] => Ok(Some(*action)),
+            _ => Err(Fault::Nondeterministic(state)),
+        }
+    }
+
+    // Whether at most one action can fire from every state on every symbol
+    pub fn is_deterministic(&self) -> bool {
+        self.guards.iter().all(|(start, end)| end - start <= 1)
+    }
+
+    // Get every action that can fire from a state, on any symbol with any counter value
+    pub fn all_transitions(&self, state : State) -> Vec<Action> {
+        let first = self.guard_index(state, 0, true);
+        let (start, _) = self.guards[first];
+        let (_, end) = self.guards[first + self.num_symbols() * 2 - 1];
+
+        let mut out : Vec<Action> = Vec::new();
+        for action in &self.actions[start as usize..end as usize] {
+            if !out.contains(action) {
+                out.push(*action);
+            }
+        }
+
+        out
+    }
+
+    // Find out if a state is accepting or rejecting
+    // Some(true) if accepting, Some(false) if rejecting
+    pub fn check_if_halting(&self, state : State) -> Option<bool> {
+        let (word, bit) = (state as usize / 64, 1 << (state % 64));
+
+        if self.accepting[word] & bit != 0 {
+            Some(true)
+        } else if self.rejecting[word] & bit != 0 {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    // The statement a state was introduced for, or that made it halt
+    pub fn source(&self, state : State) -> Option<&str> {
+        self.sources.get(state as usize)?.as_deref()
+    }
+
+    // Where the actions for (state, symbol, counter_zero) are in guards
+    fn guard_index(&self, state : State, symbol : Symbol, counter_zero : bool) -> usize {
+        let counter_offset = if counter_zero {0} else {1};
+        (state as usize * self.num_symbols() + symbol as usize) * 2 + counter_offset
+    }
+}
+
+// Collect all the characters a condition compares the read head to
+fn cond_chars(cond : &Cond, chars : &mut HashSet<char>) {
+    match cond {
+        Cond::Read(Readable::Char(c)) | Cond::NotRead(Readable::Char(c)) => { chars.insert(*c); },
+
+        Cond::And(x, y) | Cond::Or(x, y) => {
+            cond_chars(x, chars);
+            cond_chars(y, chars);
+        },
+
+        Cond::Not(x) => cond_chars(x, chars),
+
+        _ => (),
+    }
+}
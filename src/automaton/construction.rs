@@ -67,10 +67,20 @@ pub fn construct_from_prog(prog : Program) -> Autom {
 
 // Introduce the neccesary states and transitions to represent a single statement
 fn construct_stmt(autom : &mut Autom, state : &mut State, stmt : ast::Stmt) {
+    // Remember which statement the states introduced from here on come from
+    let first_state = autom.state_total;
+    let source = stmt.summary();
+
     match stmt {
         // Turn the current state into an accept/reject state
-        ast::Stmt::Accept() => autom.make_accept_state(*state),
-        ast::Stmt::Reject() => autom.make_reject_state(*state),
+        ast::Stmt::Accept() => {
+            autom.make_accept_state(*state);
+            autom.sources.insert(*state, source.clone());
+        },
+        ast::Stmt::Reject() => {
+            autom.make_reject_state(*state);
+            autom.sources.insert(*state, source.clone());
+        },
 
         // Add a new state/transition for a basic block
         ast::Stmt::BasicBlock(move_by, incr_by) => {
@@ -240,4 +250,6 @@ fn construct_stmt(autom : &mut Autom, state : &mut State, stmt : ast::Stmt) {
 
         _ => panic!("Move or Incr statement in deterministic program!"),
     }
-}
\ No newline at end of file
+
+    autom.record_source(first_state, &source);
+}
@@ -0,0 +1,151 @@
+// Static check that an automaton is deterministic, by looking for transitions whose guards overlap
+
+use std::fmt;
+
+use crate::automaton::autom::{Autom, Transition};
+use crate::automaton::generic_autom::State;
+use crate::parser::ast::{Cond, Readable};
+
+// Two transitions off of the same state that can both fire on the same symbol and counter
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    // The state the transitions leave from, and the statement it came from
+    pub state : State,
+    pub source : Option<String>,
+
+    // The transitions, each with the statement of the state it goes to
+    pub first : (Transition, Option<String>),
+    pub second : (Transition, Option<String>),
+
+    // Every (symbol, counter == 0) pair both transitions are enabled on
+    pub overlap : Vec<(Readable, bool)>,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "State {}", self.state)?;
+        if let Some(source) = &self.source {
+            write!(f, " (from {})", source)?;
+        }
+        writeln!(f, " has two transitions that are both enabled when")?;
+
+        let overlap : Vec<String> = self.overlap.iter()
+            .map(|(read, zero)| format!("reading {} with {}", readable_name(*read), if *zero {"c == 0"} else {"c != 0"}))
+            .collect();
+        writeln!(f, "  {}", overlap.join(", or "))?;
+
+        for (transition, source) in [&self.first, &self.second] {
+            write!(f, "  {}", describe_transition(transition))?;
+            if let Some(source) = source {
+                write!(f, " (for {})", source)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+// A transition as a line of text
+fn describe_transition(transition : &Transition) -> String {
+    let mut out = format!("goto {}", transition.goto);
+
+    if transition.move_by != 0 {
+        out.push_str(&format!(" move({})", transition.move_by));
+    }
+
+    if transition.incr_by != 0 {
+        out.push_str(&format!(" c += {}", transition.incr_by));
+    }
+
+    if let Some(cond) = &transition.condition {
+        out.push_str(&format!(" if {:?}", cond));
+    }
+
+    out
+}
+
+fn readable_name(read : Readable) -> String {
+    match read {
+        Readable::LEnd() => "lend".to_string(),
+        Readable::REnd() => "rend".to_string(),
+        Readable::Char(c) => format!("{:?}", c),
+    }
+}
+
+// Find every pair of transitions off of the same state whose guards can both be true at once.
+// Transitions that do exactly the same thing don't count, since taking either gives the same run
+pub fn find_conflicts(autom : &Autom) -> Vec<Conflict> {
+    let readables = readables(autom);
+    let mut conflicts = Vec::new();
+
+    for state in 0..autom.state_total {
+        let transitions = autom.get_transitions(state);
+
+        for (i, first) in transitions.iter().enumerate() {
+            for second in &transitions[i + 1..] {
+                if (first.goto, first.move_by, first.incr_by) == (second.goto, second.move_by, second.incr_by) {
+                    continue;
+                }
+
+                let overlap : Vec<(Readable, bool)> = readables.iter()
+                    .flat_map(|read| [(*read, true), (*read, false)])
+                    .filter(|(read, zero)| enabled(first, *read, *zero) && enabled(second, *read, *zero))
+                    .collect();
+
+                if !overlap.is_empty() {
+                    conflicts.push(Conflict {
+                        state,
+                        source : autom.sources.get(&state).cloned(),
+                        first : (first.clone(), autom.sources.get(&first.goto).cloned()),
+                        second : (second.clone(), autom.sources.get(&second.goto).cloned()),
+                        overlap,
+                    });
+                }
+            }
+        }
+    }
+
+    conflicts
+}
+
+// Check if a transition's guard is true for a symbol and counter
+fn enabled(transition : &Transition, read : Readable, zero : bool) -> bool {
+    match &transition.condition {
+        None => true,
+        Some(cond) => cond.check(read, if zero {0} else {1}),
+    }
+}
+
+// The endmarkers, the alphabet and every character a condition mentions
+fn readables(autom : &Autom) -> Vec<Readable> {
+    let mut chars : Vec<char> = autom.alpha.iter().copied().collect();
+
+    for transitions in autom.state_map.values() {
+        for cond in transitions.iter().filter_map(|transition| transition.condition.as_ref()) {
+            cond_chars(cond, &mut chars);
+        }
+    }
+
+    chars.sort();
+    chars.dedup();
+
+    let mut readables = vec![Readable::LEnd(), Readable::REnd()];
+    readables.extend(chars.into_iter().map(Readable::Char));
+    readables
+}
+
+fn cond_chars(cond : &Cond, chars : &mut Vec<char>) {
+    match cond {
+        Cond::Read(Readable::Char(c)) | Cond::NotRead(Readable::Char(c)) => chars.push(*c),
+
+        Cond::And(x, y) | Cond::Or(x, y) => {
+            cond_chars(x, chars);
+            cond_chars(y, chars);
+        },
+
+        Cond::Not(x) => cond_chars(x, chars),
+
+        _ => (),
+    }
+}
@@ -37,6 +37,9 @@ pub struct GenericAutom<Transition> {
 
     // Flag to store whether or not decrementing the counter past zero should be allowed
     pub decr_zero : bool,
+
+    // The statement each state was introduced for (or that made it halt), for explaining runs
+    pub sources : HashMap<State, String>,
 }
 
 // Implementation for above struct
@@ -50,7 +53,8 @@ impl<Transition : std::clone::Clone + TransitionTrait> GenericAutom<Transition>
             accepting : Vec::new(),
             rejecting : Vec::new(), 
             alpha : char_set,
-            decr_zero
+            decr_zero,
+            sources : HashMap::new(),
         }
     }
 
@@ -82,6 +86,14 @@ impl<Transition : std::clone::Clone + TransitionTrait> GenericAutom<Transition>
         }
     }
 
+    // Record the statement that every state from first onwards was introduced for,
+    // unless a statement nested inside it already claimed the state
+    pub fn record_source(&mut self, first : State, source : &str) {
+        for state in first..self.state_total {
+            self.sources.entry(state).or_insert_with(|| source.to_string());
+        }
+    }
+
     // Turn a given state into an accept state
     pub fn make_accept_state(&mut self, state : State) {
         self.accepting.push(state);
@@ -100,7 +112,7 @@ impl<Transition : std::clone::Clone + TransitionTrait> GenericAutom<Transition>
         // Display each of the transitions off of each state
         println!("Transitions:");
         for state in 0..self.state_total {
-            print!("  From {:?}:\n", state);
+            println!("  From {:?}:", state);
 
             for trans in self.state_map.get(&state).unwrap() {
                 print!("    ");
@@ -111,7 +123,7 @@ impl<Transition : std::clone::Clone + TransitionTrait> GenericAutom<Transition>
         println!();
 
         // Display the accepting states
-        if self.accepting.len() > 0 {
+        if !self.accepting.is_empty() {
             println!("Accepting:");
             for state in &self.accepting {
                 println!("  {:?}", *state);
@@ -119,7 +131,7 @@ impl<Transition : std::clone::Clone + TransitionTrait> GenericAutom<Transition>
         }
 
         // Display the rejecting states
-        if self.rejecting.len() > 0 {
+        if !self.rejecting.is_empty() {
             println!("Rejecting:");
             for state in &self.rejecting {
                 println!("  {:?}", *state);
@@ -6,3 +6,8 @@ pub mod autom;
 pub mod determ_construction;
 pub mod construction;
 
+pub mod one_way;
+
+pub mod determinism;
+
+pub mod compiled;
@@ -0,0 +1,60 @@
+// Analysis to find automata that only ever move the read head rightwards
+
+use crate::automaton::autom::{Autom, Transition};
+use crate::automaton::generic_autom::State;
+use crate::parser::ast::Readable;
+
+// Check if an automaton can be simulated in a single sweep over the input
+// This is the case if no transition moves left, and the counter can't be pumped up
+// without moving, i.e. there's no cycle of stationary transitions that increments the counter overall.
+// Moving right off of rend doesn't move the read head, so every transition that can fire on rend
+// counts as stationary there
+pub fn is_one_way(autom : &Autom) -> bool {
+    // Every transition has to move right or not at all
+    let transitions = || autom.state_map.iter().flat_map(|(state, ts)| ts.iter().map(move |t| (*state, t)));
+
+    if transitions().any(|(_, trans)| trans.move_by < 0) {
+        return false;
+    }
+
+    // Transitions that don't move the read head on any tape cell
+    let stationary : Vec<_> = transitions().filter(|(_, trans)| trans.move_by == 0).collect();
+
+    // Transitions that don't move the read head on rend
+    let stationary_on_rend : Vec<_> = transitions().filter(|(_, trans)| can_fire_on_rend(trans)).collect();
+
+    !has_incrementing_cycle(autom, &stationary) && !has_incrementing_cycle(autom, &stationary_on_rend)
+}
+
+// Check if a transition's condition can hold with the read head on rend
+fn can_fire_on_rend(trans : &Transition) -> bool {
+    match trans.condition {
+        None => true,
+        Some(ref cond) => cond.check(Readable::REnd(), 0) || cond.check(Readable::REnd(), 1),
+    }
+}
+
+// Check if a set of transitions contains a cycle whose increments add up to more than zero
+// Finds the largest counter increase along a path into each state with Bellman-Ford,
+// which still has something to improve after |states| rounds iff such a cycle exists
+fn has_incrementing_cycle(autom : &Autom, transitions : &[(State, &Transition)]) -> bool {
+    let num_states = autom.state_total as usize;
+    let mut gain = vec![0; num_states];
+
+    for _ in 0..=num_states {
+        let mut changed = false;
+
+        for (source, trans) in transitions {
+            let new_gain = gain[*source as usize] + trans.incr_by;
+
+            if new_gain > gain[trans.goto as usize] {
+                gain[trans.goto as usize] = new_gain;
+                changed = true;
+            }
+        }
+
+        if !changed { return false; }
+    }
+
+    true
+}
@@ -0,0 +1,44 @@
+// Fitting empirical growth rates to benchmark results
+
+// The fit of t = c * n^exponent to a set of points
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GrowthFit {
+    // The power of n that t grows with
+    pub exponent : f64,
+
+    // The constant factor c
+    pub constant : f64,
+
+    // How well the line fits the points on a log-log scale (1 is a perfect fit)
+    pub r_squared : f64,
+}
+
+// Fit t = c * n^exponent to points (n, t) by least squares on log t = log c + exponent * log n
+// Points with n or t not above zero are left out, since they have no logarithm.
+// Returns None if fewer than two distinct values of n are left
+pub fn fit_growth(points : &[(f64, f64)]) -> Option<GrowthFit> {
+    let logs : Vec<(f64, f64)> = points.iter()
+        .filter(|(n, t)| *n > 0.0 && *t > 0.0)
+        .map(|(n, t)| (n.ln(), t.ln()))
+        .collect();
+
+    let count = logs.len() as f64;
+    if logs.len() < 2 { return None; }
+
+    let mean_x = logs.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = logs.iter().map(|(_, y)| y).sum::<f64>() / count;
+
+    let sxx : f64 = logs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let sxy : f64 = logs.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let syy : f64 = logs.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+
+    if sxx == 0.0 { return None; }
+
+    let exponent = sxy / sxx;
+    let intercept = mean_y - exponent * mean_x;
+
+    // Every point is on the line if the times don't vary at all
+    let r_squared = if syy == 0.0 { 1.0 } else { (sxy * sxy) / (sxx * syy) };
+
+    Some(GrowthFit { exponent, constant : intercept.exp(), r_squared })
+}
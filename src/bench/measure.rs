@@ -0,0 +1,48 @@
+// Timing simulators with warm-up runs and repetitions
+
+use std::time::Instant;
+
+// Summary of the times taken by the repetitions of a run, in seconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timing {
+    pub median : f64,
+    pub min : f64,
+    pub max : f64,
+}
+
+// Time a function, running it warmup times without timing it first and then timing reps runs of it
+// Returns the summary of the timed runs, along with the result of the last one
+pub fn measure<T>(mut f : impl FnMut() -> T, warmup : usize, reps : usize) -> (Timing, T) {
+    if reps == 0 {
+        panic!("Can't time a function without running it at least once!");
+    }
+
+    for _ in 0..warmup {
+        f();
+    }
+
+    let mut times = Vec::with_capacity(reps);
+    let mut result = None;
+
+    for _ in 0..reps {
+        let start = Instant::now();
+        result = Some(f());
+        times.push(start.elapsed().as_secs_f64());
+    }
+
+    (summarise(&times), result.unwrap())
+}
+
+// The median, minimum and maximum of a non-empty list of times
+pub fn summarise(times : &[f64]) -> Timing {
+    let mut sorted = times.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+
+    let mid = sorted.len() / 2;
+    let median = match sorted.len() % 2 {
+        0 => (sorted[mid - 1] + sorted[mid]) / 2.0,
+        _ => sorted[mid],
+    };
+
+    Timing { median, min : sorted[0], max : sorted[sorted.len() - 1] }
+}
@@ -0,0 +1,5 @@
+pub mod measure;
+
+pub mod fit;
+
+pub mod plot;
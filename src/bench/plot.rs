@@ -0,0 +1,107 @@
+// Plotting benchmark results as SVG line charts
+
+use std::fmt::Write;
+
+// Size of the whole image
+const WIDTH : f64 = 800.0;
+const HEIGHT : f64 = 500.0;
+
+// Space around the plotting area for the axis labels
+const MARGIN_LEFT : f64 = 80.0;
+const MARGIN_RIGHT : f64 = 20.0;
+const MARGIN_TOP : f64 = 40.0;
+const MARGIN_BOTTOM : f64 = 60.0;
+
+// Number of gaps between the ticks on each axis
+const TICKS : usize = 5;
+
+// The colours of successive series
+const COLOURS : [&str; 6] = ["#1f77b4", "#d62728", "#2ca02c", "#ff7f0e", "#9467bd", "#8c564b"];
+
+// A line to plot, e.g. the times taken by one simulator
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    pub name : String,
+    pub points : Vec<(f64, f64)>,
+}
+
+// Draw a line chart of some series, with both axes starting from zero
+pub fn render_svg(title : &str, x_label : &str, y_label : &str, series : &[Series]) -> String {
+    let points = series.iter().flat_map(|s| s.points.iter());
+
+    // Leave room for the largest values, and avoid dividing by zero if there aren't any
+    let (x_max, y_max) = points.fold((0.0f64, 0.0f64), |(x_max, y_max), (x, y)| (x_max.max(*x), y_max.max(*y)));
+    let x_max = if x_max > 0.0 { x_max } else { 1.0 };
+    let y_max = if y_max > 0.0 { y_max } else { 1.0 };
+
+    let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_height = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+
+    // Convert a point to the image's coordinates
+    let to_x = |x : f64| MARGIN_LEFT + x / x_max * plot_width;
+    let to_y = |y : f64| MARGIN_TOP + plot_height - y / y_max * plot_height;
+
+    let mut svg = String::new();
+
+    // Writing to a String can't fail
+    writeln!(svg, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\" font-size=\"12\">", WIDTH, HEIGHT).unwrap();
+    writeln!(svg, "<rect width=\"100%\" height=\"100%\" fill=\"white\"/>").unwrap();
+    writeln!(svg, "<text x=\"{}\" y=\"24\" text-anchor=\"middle\" font-size=\"16\">{}</text>", WIDTH / 2.0, escape(title)).unwrap();
+
+    // Axes
+    writeln!(
+        svg, "<path d=\"M {} {} V {} H {}\" fill=\"none\" stroke=\"black\"/>",
+        MARGIN_LEFT, MARGIN_TOP, MARGIN_TOP + plot_height, MARGIN_LEFT + plot_width
+    ).unwrap();
+
+    // Ticks and grid lines
+    for i in 0..=TICKS {
+        let fraction = i as f64 / TICKS as f64;
+
+        let x = to_x(fraction * x_max);
+        writeln!(svg, "<line x1=\"{0}\" y1=\"{1}\" x2=\"{0}\" y2=\"{2}\" stroke=\"#ddd\"/>", x, MARGIN_TOP, MARGIN_TOP + plot_height).unwrap();
+        writeln!(svg, "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>", x, MARGIN_TOP + plot_height + 18.0, format_tick(fraction * x_max)).unwrap();
+
+        let y = to_y(fraction * y_max);
+        writeln!(svg, "<line x1=\"{0}\" y1=\"{1}\" x2=\"{2}\" y2=\"{1}\" stroke=\"#ddd\"/>", MARGIN_LEFT, y, MARGIN_LEFT + plot_width).unwrap();
+        writeln!(svg, "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>", MARGIN_LEFT - 6.0, y + 4.0, format_tick(fraction * y_max)).unwrap();
+    }
+
+    // Axis labels
+    writeln!(svg, "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>", MARGIN_LEFT + plot_width / 2.0, HEIGHT - 16.0, escape(x_label)).unwrap();
+    writeln!(
+        svg, "<text x=\"18\" y=\"{0}\" text-anchor=\"middle\" transform=\"rotate(-90 18 {0})\">{1}</text>",
+        MARGIN_TOP + plot_height / 2.0, escape(y_label)
+    ).unwrap();
+
+    // The lines themselves, with a legend in the top left corner
+    for (i, s) in series.iter().enumerate() {
+        let colour = COLOURS[i % COLOURS.len()];
+
+        let coords : Vec<String> = s.points.iter().map(|(x, y)| format!("{:.2},{:.2}", to_x(*x), to_y(*y))).collect();
+        writeln!(svg, "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>", coords.join(" "), colour).unwrap();
+
+        let legend_y = MARGIN_TOP + 16.0 + 18.0 * i as f64;
+        writeln!(svg, "<line x1=\"{0}\" y1=\"{1}\" x2=\"{2}\" y2=\"{1}\" stroke=\"{3}\" stroke-width=\"2\"/>", MARGIN_LEFT + 12.0, legend_y, MARGIN_LEFT + 36.0, colour).unwrap();
+        writeln!(svg, "<text x=\"{}\" y=\"{}\">{}</text>", MARGIN_LEFT + 42.0, legend_y + 4.0, escape(&s.name)).unwrap();
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// Write a tick value without too many digits
+fn format_tick(value : f64) -> String {
+    if value == 0.0 || value.abs() >= 100.0 {
+        format!("{:.0}", value)
+    } else if value.abs() >= 1.0 {
+        format!("{:.2}", value)
+    } else {
+        format!("{:.2e}", value)
+    }
+}
+
+// Escape the characters that mean something in XML
+fn escape(text : &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,38 @@
+// The twoc language server, which reads messages from stdin and writes responses to stdout
+
+use std::io::{self, BufReader};
+use std::process::exit;
+
+use twoc::lsp::protocol::{read_message, write_message};
+use twoc::lsp::server::Server;
+
+fn main() {
+    let mut input = BufReader::new(io::stdin().lock());
+    let mut output = io::stdout().lock();
+    let mut server = Server::new();
+
+    loop {
+        let message = match read_message(&mut input) {
+            Ok(Some(message)) => message,
+
+            // The editor went away without saying so
+            Ok(None) => exit(1),
+
+            Err(err) => {
+                eprintln!("{}", err);
+                continue;
+            },
+        };
+
+        if let Some(code) = server.exit_code(&message) {
+            exit(code);
+        }
+
+        for response in server.handle(&message) {
+            if let Err(err) = write_message(&mut output, &response) {
+                eprintln!("Couldn't write a response: {}", err);
+                exit(1);
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+// Timing simulators on a family of words
+
+use std::fs;
+
+use clap::Args;
+
+use twoc::json::Json;
+use twoc::simulation::algorithm::Algorithm;
+use twoc::simulation::budget::Budget;
+use twoc::words::pattern::Pattern;
+use twoc::words::families::{pattern_family, parse_range};
+use twoc::bench::measure::{Timing, measure};
+use twoc::bench::fit::fit_growth;
+use twoc::bench::plot::{Series, render_svg};
+
+use crate::{Failure, Format, EXIT_ACCEPT, algorithm_parser, load, verdict_name};
+
+#[derive(Args, Debug)]
+pub struct BenchArgs {
+    #[arg(short, long)]
+    file : String,
+
+    /// The family of words to time the simulators on, e.g. 0^{2n}1^{2n}
+    #[arg(long)]
+    pattern : String,
+
+    /// The values of n to instantiate the pattern at, e.g. 100..2000
+    #[arg(long)]
+    n : String,
+
+    /// The step between values of n
+    #[arg(long, default_value_t = 1)]
+    step : usize,
+
+    /// Seed for the random letters in the pattern
+    #[arg(long, default_value_t = 0)]
+    seed : u64,
+
+    /// The simulators to time, separated by commas
+    #[arg(long, value_parser = algorithm_parser(), value_delimiter = ',', required = true)]
+    algorithms : Vec<Algorithm>,
+
+    /// Untimed runs on each word before the timed ones
+    #[arg(long, default_value_t = 1)]
+    warmup : usize,
+
+    /// Timed runs on each word
+    #[arg(long, default_value_t = 5, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    reps : usize,
+
+    /// Threads for rytter_parallel
+    #[arg(long, default_value_t = 1)]
+    threads : usize,
+
+    /// Write the timings to this CSV file
+    #[arg(long)]
+    csv : Option<String>,
+
+    /// Plot the median timings to this SVG file
+    #[arg(long)]
+    svg : Option<String>,
+}
+
+// Time simulators on a family of words
+pub fn bench(args : BenchArgs, format : Format) -> Result<u8, Failure> {
+    let compiled = load(&args.file, format)?;
+
+    // Generate the words to time on
+    let mut alphabet : Vec<char> = compiled.desugared.alpha.iter().copied().collect();
+    alphabet.sort();
+
+    let pattern = Pattern::parse(&args.pattern)?;
+    let family = pattern_family(&pattern, &parse_range(&args.n, args.step)?, &alphabet, args.seed);
+
+    for (_, word) in &family {
+        if !compiled.desugared.check_if_input_in_alphabet(word) {
+            return Err(format!("{:?} contains characters that aren't in the program's alphabet!", word).into());
+        }
+    }
+
+    // Check the simulators can handle the program before timing anything
+    for algorithm in &args.algorithms {
+        algorithm.check(&compiled)?;
+    }
+
+    let general = compiled.general();
+
+    let compiled_for = |algorithm : &Algorithm| match algorithm.general() {
+        true  => &general,
+        false => &compiled.frozen,
+    };
+
+    // (algorithm, n, word length, timing) for every run
+    let mut results : Vec<(Algorithm, usize, usize, Timing)> = Vec::new();
+
+    if format == Format::Text {
+        println!("{:<18} {:>8} {:>10} {:>12} {:>12} {:>12}", "algorithm", "n", "length", "median (s)", "min (s)", "max (s)");
+    }
+
+    for (n, word) in &family {
+        let mut outcomes = Vec::new();
+
+        for algorithm in &args.algorithms {
+            let (timing, (outcome, _)) = measure(
+                || algorithm.run(compiled_for(algorithm), word, &Budget::default(), args.threads, false),
+                args.warmup,
+                args.reps,
+            );
+
+            if format == Format::Text {
+                println!(
+                    "{:<18} {:>8} {:>10} {:>12.6} {:>12.6} {:>12.6}",
+                    algorithm.name(), n, word.len(), timing.median, timing.min, timing.max
+                );
+            }
+
+            outcomes.push((algorithm, outcome));
+            results.push((*algorithm, *n, word.len(), timing));
+        }
+
+        // Timings of simulators that disagree aren't worth much
+        if outcomes.iter().any(|(_, outcome)| *outcome != outcomes[0].1) {
+            eprintln!("Warning: the simulators disagree on the word for n = {}:", n);
+            for (algorithm, outcome) in &outcomes {
+                eprintln!("  {}: {}", algorithm.name(), verdict_name(*outcome));
+            }
+        }
+    }
+
+    // The median times of each algorithm against the word length
+    let series : Vec<Series> = args.algorithms.iter()
+        .map(|algorithm| Series {
+            name : algorithm.name().to_string(),
+            points : results.iter()
+                .filter(|(a, _, _, _)| a == algorithm)
+                .map(|(_, _, length, timing)| (*length as f64, timing.median))
+                .collect(),
+        })
+        .collect();
+
+    match format {
+        Format::Text => {
+            println!("\nGrowth exponents (time ~ c * length^k):");
+            for s in &series {
+                match fit_growth(&s.points) {
+                    Some(fit) => println!("  {:<18} k = {:.2} (c = {:.3e}, r^2 = {:.3})", s.name, fit.exponent, fit.constant, fit.r_squared),
+                    None => println!("  {:<18} not enough distinct word lengths to fit", s.name),
+                }
+            }
+        },
+
+        Format::Json => {
+            let timings = results.iter()
+                .map(|(algorithm, n, length, timing)| Json::object(vec![
+                    ("algorithm", algorithm.name().into()),
+                    ("n", (*n).into()),
+                    ("length", (*length).into()),
+                    ("median_s", timing.median.into()),
+                    ("min_s", timing.min.into()),
+                    ("max_s", timing.max.into()),
+                ]))
+                .collect();
+
+            let growth = series.iter()
+                .map(|s| Json::object(vec![
+                    ("algorithm", s.name.as_str().into()),
+                    ("fit", match fit_growth(&s.points) {
+                        Some(fit) => Json::object(vec![
+                            ("exponent", fit.exponent.into()),
+                            ("constant", fit.constant.into()),
+                            ("r_squared", fit.r_squared.into()),
+                        ]),
+                        None => Json::Null,
+                    }),
+                ]))
+                .collect();
+
+            println!("{}", Json::object(vec![("timings", Json::Array(timings)), ("growth", Json::Array(growth))]));
+        },
+    }
+
+    if let Some(path) = &args.csv {
+        let mut csv = String::from("algorithm,n,length,median_s,min_s,max_s\n");
+        for (algorithm, n, length, timing) in &results {
+            csv.push_str(&format!("{},{},{},{},{},{}\n", algorithm.name(), n, length, timing.median, timing.min, timing.max));
+        }
+
+        fs::write(path, csv).map_err(|err| format!("Couldn't write {:?}: {}", path, err))?;
+    }
+
+    if let Some(path) = &args.svg {
+        let title = format!("{} on {}", args.file, args.pattern);
+        let svg = render_svg(&title, "Input length", "Median runtime (s)", &series);
+
+        fs::write(path, svg).map_err(|err| format!("Couldn't write {:?}: {}", path, err))?;
+    }
+
+    Ok(EXIT_ACCEPT)
+}
@@ -0,0 +1,140 @@
+// Checking, summarising and printing what a program compiles to
+
+use std::fs;
+
+use clap::{Args, ValueEnum};
+
+use twoc::codegen::rust_recognizer;
+use twoc::json::{Json, ToJson};
+
+use crate::{Failure, FileArgs, Format, EXIT_ACCEPT, automaton_stats, load, read_source};
+
+#[derive(Args, Debug)]
+pub struct PrintAstArgs {
+    #[arg(short, long)]
+    file : String,
+
+    /// The stage of the pipeline to print the program at
+    #[arg(long, value_enum, default_value_t = Stage::Sugared)]
+    stage : Stage,
+}
+
+#[derive(Args, Debug)]
+pub struct CodegenArgs {
+    #[arg(short, long)]
+    file : String,
+
+    /// The file to write the module to (printed if not given)
+    #[arg(short, long)]
+    output : Option<String>,
+}
+
+// The stages of the pipeline a program's syntax tree can be printed at
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    // As parsed
+    Sugared,
+
+    // With the syntactic sugar removed
+    Desugared,
+
+    // With moves and increments contracted into basic blocks
+    Contracted,
+}
+
+// Check that a program compiles
+pub fn check(args : FileArgs, format : Format) -> Result<u8, Failure> {
+    let compiled = load(&args.file, format)?;
+    let warnings = compiled.warnings.len();
+
+    match format {
+        Format::Text => println!("{} compiles with {} warning{}", args.file, warnings, if warnings == 1 {""} else {"s"}),
+
+        Format::Json => {
+            let source = read_source(&args.file)?;
+
+            println!("{}", Json::object(vec![
+                ("file", args.file.as_str().into()),
+                ("ok", true.into()),
+                ("warnings", Json::Array(compiled.warnings.iter().map(|warning| warning.to_json(&source)).collect())),
+            ]));
+        },
+    }
+
+    Ok(EXIT_ACCEPT)
+}
+
+// Summarise what a program compiles to
+pub fn compile_summary(args : FileArgs, format : Format) -> Result<u8, Failure> {
+    let compiled = load(&args.file, format)?;
+    let stats = automaton_stats(&compiled);
+
+    match format {
+        Format::Text => {
+            println!("Deterministic: {}", compiled.deterministic);
+            println!("One-way: {}", compiled.one_way);
+            println!("States: {}", stats.get("states").unwrap());
+            println!("Transitions: {}", stats.get("transitions").unwrap());
+        },
+
+        Format::Json => println!("{}", Json::object(vec![
+            ("deterministic", compiled.deterministic.into()),
+            ("one_way", compiled.one_way.into()),
+            ("automaton", stats),
+        ])),
+    }
+
+    Ok(EXIT_ACCEPT)
+}
+
+// Print a program's syntax tree
+pub fn print_ast(args : PrintAstArgs, format : Format) -> Result<u8, Failure> {
+    let compiled = load(&args.file, format)?;
+
+    match (args.stage, format) {
+        (Stage::Sugared, Format::Text)    => compiled.sugared.print(),
+        (Stage::Desugared, Format::Text)  => compiled.desugared.print(),
+        (Stage::Contracted, Format::Text) => compiled.contracted.print(),
+
+        (Stage::Sugared, Format::Json)    => println!("{}", compiled.sugared.to_json()),
+        (Stage::Desugared, Format::Json)  => println!("{}", compiled.desugared.to_json()),
+        (Stage::Contracted, Format::Json) => println!("{}", compiled.contracted.to_json()),
+    }
+
+    Ok(EXIT_ACCEPT)
+}
+
+// Print a program's automaton
+pub fn print_automaton(args : FileArgs, format : Format) -> Result<u8, Failure> {
+    let compiled = load(&args.file, format)?;
+
+    match format {
+        Format::Text => compiled.autom.print(),
+        Format::Json => println!("{}", compiled.autom.to_json()),
+    }
+
+    Ok(EXIT_ACCEPT)
+}
+
+// Generate a Rust recognizer for a program
+pub fn codegen(args : CodegenArgs, format : Format) -> Result<u8, Failure> {
+    let compiled = load(&args.file, format)?;
+
+    if !compiled.deterministic {
+        return Err("Only deterministic programs can be turned into Rust!".to_string().into());
+    }
+
+    let module = rust_recognizer(&compiled.autom)?;
+
+    match (&args.output, format) {
+        (Some(output), _) => fs::write(output, &module).map_err(|err| format!("Couldn't write {}: {}", output, err))?,
+        (None, Format::Text) => print!("{}", module),
+        (None, Format::Json) => println!("{}", Json::object(vec![("module", module.into())])),
+    }
+
+    if let (Some(output), Format::Json) = (&args.output, format) {
+        println!("{}", Json::object(vec![("output", output.as_str().into())]));
+    }
+
+    Ok(EXIT_ACCEPT)
+}
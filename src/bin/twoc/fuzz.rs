@@ -0,0 +1,100 @@
+// Checking the simulators against each other on random programs
+
+use clap::Args;
+
+use twoc::json::{Json, ToJson};
+use twoc::fuzz::generate::GenConfig;
+use twoc::fuzz::differential::differential_test;
+
+use crate::{Failure, Format, EXIT_ACCEPT, EXIT_REJECT};
+
+#[derive(Args, Debug)]
+pub struct FuzzArgs {
+    /// How many random programs to check
+    #[arg(long, default_value_t = 1000)]
+    programs : usize,
+
+    /// How many random words to run each program on
+    #[arg(long, default_value_t = 8)]
+    words : usize,
+
+    /// The length of the longest random words
+    #[arg(long, default_value_t = 6)]
+    max_word_len : usize,
+
+    /// Seed for the random programs and words
+    #[arg(long, default_value_t = 0)]
+    seed : u64,
+
+    /// The letters of the programs' alphabet
+    #[arg(long, default_value = "ab")]
+    alphabet : String,
+
+    /// How deeply blocks can be nested
+    #[arg(long, default_value_t = 3)]
+    max_depth : usize,
+
+    /// The most statements in a single block
+    #[arg(long, default_value_t = 4)]
+    max_block_len : usize,
+
+    /// The largest distance a single move goes
+    #[arg(long, default_value_t = 1)]
+    max_move : i32,
+
+    /// The largest amount a single statement changes the counter by
+    #[arg(long, default_value_t = 2)]
+    max_incr : i32,
+
+    /// Only generate deterministic programs
+    #[arg(long)]
+    deterministic : bool,
+
+    /// Generate desugared programs rather than sugared ones
+    #[arg(long)]
+    core : bool,
+}
+
+// Run every simulator that applies on random programs and words, reporting the first disagreement shrunk down
+pub fn fuzz(args : FuzzArgs, format : Format) -> Result<u8, Failure> {
+    let config = GenConfig {
+        alphabet : args.alphabet.chars().collect(),
+        max_depth : args.max_depth,
+        max_block_len : args.max_block_len,
+        max_move : args.max_move,
+        max_incr : args.max_incr,
+        loops : true,
+        nondeterminism : !args.deterministic,
+        sugar : !args.core,
+        decr_zero : None,
+    };
+
+    if config.alphabet.is_empty() {
+        return Err("The alphabet needs at least one letter!".to_string().into());
+    }
+
+    let disagreement = differential_test(&config, args.programs, args.words, args.max_word_len, args.seed);
+
+    match (&disagreement, format) {
+        (None, Format::Text) => println!("The simulators agree on all {} programs", args.programs),
+
+        (Some(disagreement), Format::Text) => print!("{}", disagreement),
+
+        (_, Format::Json) => println!("{}", Json::object(vec![
+            ("programs", args.programs.into()),
+            ("agree", disagreement.is_none().into()),
+            ("disagreement", match &disagreement {
+                Some(disagreement) => Json::object(vec![
+                    ("word", disagreement.word.as_str().into()),
+                    ("program", disagreement.program.to_json()),
+                    ("verdicts", Json::Array(disagreement.verdicts.iter()
+                        .map(|(name, outcome)| Json::object(vec![("simulator", name.as_str().into()), ("verdict", outcome.to_json())]))
+                        .collect())),
+                ]),
+                None => Json::Null,
+            }),
+        ])),
+    }
+
+    Ok(if disagreement.is_some() {EXIT_REJECT} else {EXIT_ACCEPT})
+}
@@ -0,0 +1,168 @@
+// Listing the words a program accepts and comparing the languages of two programs
+
+use clap::Args;
+
+use twoc::json::Json;
+use twoc::simulation::algorithm::procedure_for;
+use twoc::words::families::{random_words, all_words, parse_range};
+use twoc::words::word_list::EMPTY_WORD;
+use twoc::words::equivalence::{check_alphabets, find_distinction};
+use twoc::words::language;
+
+use crate::{Failure, Format, EXIT_ACCEPT, EXIT_REJECT, load};
+
+#[derive(Args, Debug)]
+pub struct EnumerateArgs {
+    #[arg(short, long)]
+    file : String,
+
+    /// The length of the longest words to try
+    #[arg(long)]
+    max_len : usize,
+
+    /// Simulate this many words at once
+    #[arg(long, default_value_t = 1)]
+    threads : usize,
+}
+
+#[derive(Args, Debug)]
+pub struct EquivArgs {
+    first : String,
+
+    second : String,
+
+    /// Compare the programs on every word of at most this length
+    #[arg(long, default_value_t = 0)]
+    max_len : usize,
+
+    /// Also compare them on this many random words of each length in --sample-len
+    #[arg(long, default_value_t = 0)]
+    samples : usize,
+
+    /// The lengths of the random words, e.g. 10..=50
+    #[arg(long, default_value = "0")]
+    sample_len : String,
+
+    /// Seed for the random words
+    #[arg(long, default_value_t = 0)]
+    seed : u64,
+
+    /// Simulate this many words at once
+    #[arg(long, default_value_t = 1)]
+    threads : usize,
+}
+
+// List the accepted words of a program in length-lexicographic order, followed by how many there are of each length
+pub fn enumerate(args : EnumerateArgs, format : Format) -> Result<u8, Failure> {
+    let compiled = load(&args.file, format)?;
+
+    let mut alphabet : Vec<char> = compiled.desugared.alpha.iter().copied().collect();
+    alphabet.sort();
+
+    // One-way automata can share the runs on each prefix between the words that start with it
+    let language = match compiled.one_way {
+        true  => language::enumerate_one_way(&compiled.frozen, &alphabet, args.max_len)
+            .map_err(|fault| format!("Couldn't enumerate the language: {}", fault))?,
+        false => language::enumerate(&compiled.frozen, &alphabet, args.max_len, procedure_for(&compiled), args.threads),
+    };
+
+    if format == Format::Json {
+        let counts = language.counts.iter()
+            .map(|count| Json::object(vec![
+                ("length", count.length.into()),
+                ("accepted", count.accepted.into()),
+                ("words", count.words.into()),
+            ]))
+            .collect();
+
+        println!("{}", Json::object(vec![("accepted", language.accepted.into()), ("counts", Json::Array(counts))]));
+        return Ok(EXIT_ACCEPT);
+    }
+
+    for word in &language.accepted {
+        match word.is_empty() {
+            true  => println!("{}", EMPTY_WORD),
+            false => println!("{}", word),
+        }
+    }
+
+    println!("\n{:>8} {:>12} {:>12}", "length", "accepted", "words");
+    for count in &language.counts {
+        println!("{:>8} {:>12} {:>12}", count.length, count.accepted, count.words);
+    }
+
+    Ok(EXIT_ACCEPT)
+}
+
+// Compare the verdicts of two programs on every word up to a length bound and on some random longer ones,
+// reporting the shortest word they disagree on
+pub fn equiv(args : EquivArgs, format : Format) -> Result<u8, Failure> {
+    let first = load(&args.first, format)?;
+    let second = load(&args.second, format)?;
+
+    check_alphabets(&first.desugared.alpha, &second.desugared.alpha)?;
+
+    let mut alphabet : Vec<char> = first.desugared.alpha.iter().copied().collect();
+    alphabet.sort();
+
+    // All the short words, followed by the random ones, each length sorted so the first distinction is the shortest
+    let mut words = all_words(&alphabet, args.max_len);
+    let exhaustive = words.len();
+
+    for length in parse_range(&args.sample_len, 1)? {
+        if args.samples == 0 || length <= args.max_len { continue; }
+
+        let mut sample = random_words(&alphabet, length, args.samples, args.seed.wrapping_add(length as u64));
+        sample.sort();
+        sample.dedup();
+        words.extend(sample);
+    }
+
+    let distinction = find_distinction(
+        (&first.frozen, procedure_for(&first)),
+        (&second.frozen, procedure_for(&second)),
+        &words,
+        args.threads,
+    );
+
+    // The word they disagree on, and which program accepts it
+    let disagreement = distinction.map(|distinction| match distinction.first_accepts {
+        true  => (distinction.word, &args.first, &args.second),
+        false => (distinction.word, &args.second, &args.first),
+    });
+
+    match (&disagreement, format) {
+        (None, Format::Text) => {
+            println!("The programs agree on all {} words of length at most {}", exhaustive, args.max_len);
+            if words.len() > exhaustive {
+                println!("and on {} random longer words", words.len() - exhaustive);
+            }
+        },
+
+        (Some((word, accepter, rejecter)), Format::Text) => {
+            let word = match word.is_empty() {
+                true  => EMPTY_WORD,
+                false => word,
+            };
+
+            println!("The programs disagree on {}: {} accepts it but {} doesn't", word, accepter, rejecter);
+        },
+
+        (_, Format::Json) => println!("{}", Json::object(vec![
+            ("equivalent", disagreement.is_none().into()),
+            ("exhaustive_words", exhaustive.into()),
+            ("random_words", (words.len() - exhaustive).into()),
+            ("max_len", args.max_len.into()),
+            ("distinction", match &disagreement {
+                Some((word, accepter, rejecter)) => Json::object(vec![
+                    ("word", word.as_str().into()),
+                    ("accepted_by", accepter.as_str().into()),
+                    ("rejected_by", rejecter.as_str().into()),
+                ]),
+                None => Json::Null,
+            }),
+        ])),
+    }
+
+    Ok(if disagreement.is_some() {EXIT_REJECT} else {EXIT_ACCEPT})
+}
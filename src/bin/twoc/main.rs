@@ -0,0 +1,226 @@
+// The command line interface, with a module for each group of subcommands
+
+// IMPORTS
+use std::fs;
+use std::process::ExitCode;
+
+// Import the compiler pipeline
+use twoc::compiler::{Compiled, Diagnostics, Severity, compile};
+use twoc::json::Json;
+use twoc::simulation::algorithm::Algorithm;
+use twoc::simulation::budget::Outcome;
+
+// Clap import
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+
+mod bench;
+mod compile;
+mod fuzz;
+mod language;
+mod run;
+mod suite;
+
+use bench::{BenchArgs, bench};
+use compile::{CodegenArgs, PrintAstArgs, check, codegen, compile_summary, print_ast, print_automaton};
+use fuzz::{FuzzArgs, fuzz};
+use language::{EnumerateArgs, EquivArgs, enumerate, equiv};
+use run::{RunArgs, run};
+use suite::{TestArgs, test};
+
+// Exit codes, so that scripts can tell what happened without reading the output
+// (2 is left for clap's usage errors)
+const EXIT_ACCEPT : u8 = 0;
+const EXIT_REJECT : u8 = 1;
+const EXIT_COMPILE_ERROR : u8 = 3;
+const EXIT_RUNTIME_ERROR : u8 = 4;
+
+// Cli arguments
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command : Command,
+
+    /// How to print the results
+    #[arg(long, global = true, value_enum, default_value_t = Format::Text)]
+    format : Format,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run a program on a word, or on every word in a list
+    Run(RunArgs),
+
+    /// Check that a program compiles, printing any warnings
+    Check(FileArgs),
+
+    /// Compile a program and summarise the automaton it becomes
+    Compile(FileArgs),
+
+    /// Print a program's syntax tree at a stage of the pipeline
+    PrintAst(PrintAstArgs),
+
+    /// Print the automaton a program is constructed into
+    PrintAutomaton(FileArgs),
+
+    /// Generate a standalone Rust module with a function accepts(input : &str) -> bool for a deterministic program
+    Codegen(CodegenArgs),
+
+    /// Time simulators on a family of words
+    Bench(BenchArgs),
+
+    /// List the words a program accepts, up to a length bound
+    Enumerate(EnumerateArgs),
+
+    /// Check that two programs accept the same words, up to a length bound
+    Equiv(EquivArgs),
+
+    /// Check the simulators against each other on random programs
+    Fuzz(FuzzArgs),
+
+    /// Check every program in a directory against the // @accept and // @reject examples in its comments
+    Test(TestArgs),
+}
+
+#[derive(Args, Debug)]
+struct FileArgs {
+    #[arg(short, long)]
+    file : String,
+}
+
+// The ways results can be printed
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    // For people to read (results for a list of words are comma-separated lines after a header line)
+    Text,
+
+    // A JSON object (or one per line, for a list of words)
+    Json,
+}
+
+// The ways a command can fail
+enum Failure {
+    // The program didn't compile
+    Compile { file : String, source : String, diagnostics : Diagnostics },
+
+    // Anything else that went wrong
+    Runtime(String),
+}
+
+impl From<String> for Failure {
+    fn from(message : String) -> Self {
+        Failure::Runtime(message)
+    }
+}
+
+impl Failure {
+    fn exit_code(&self) -> u8 {
+        match self {
+            Failure::Compile { .. } => EXIT_COMPILE_ERROR,
+            Failure::Runtime(_) => EXIT_RUNTIME_ERROR,
+        }
+    }
+
+    // Tell the user what went wrong
+    fn report(&self, format : Format) {
+        match (self, format) {
+            (Failure::Compile { file, source, diagnostics }, Format::Text) => eprintln!("{}", diagnostics.render(source, file)),
+            (Failure::Runtime(message), Format::Text) => eprintln!("Error: {}", message),
+
+            (Failure::Compile { file, source, diagnostics }, Format::Json) => println!("{}", Json::object(vec![
+                ("error", "compile".into()),
+                ("file", file.as_str().into()),
+                ("diagnostics", diagnostics.to_json(source)),
+            ])),
+
+            (Failure::Runtime(message), Format::Json) => println!("{}", Json::object(vec![
+                ("error", "runtime".into()),
+                ("message", message.as_str().into()),
+            ])),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    // Parse command line arguments
+    let cli = Cli::parse();
+    let format = cli.format;
+
+    let result = match cli.command {
+        Command::Run(args) => run(args, format),
+        Command::Check(args) => check(args, format),
+        Command::Compile(args) => compile_summary(args, format),
+        Command::PrintAst(args) => print_ast(args, format),
+        Command::PrintAutomaton(args) => print_automaton(args, format),
+        Command::Codegen(args) => codegen(args, format),
+        Command::Bench(args) => bench(args, format),
+        Command::Enumerate(args) => enumerate(args, format),
+        Command::Equiv(args) => equiv(args, format),
+        Command::Fuzz(args) => fuzz(args, format),
+        Command::Test(args) => test(args, format),
+    };
+
+    match result {
+        Ok(code) => ExitCode::from(code),
+
+        Err(failure) => {
+            failure.report(format);
+            ExitCode::from(failure.exit_code())
+        },
+    }
+}
+
+// Parse the name of a simulator, listing every name in --help
+fn algorithm_parser() -> impl TypedValueParser<Value = Algorithm> {
+    PossibleValuesParser::new(Algorithm::ALL.map(|algorithm| algorithm.name()))
+        .map(|name| Algorithm::from_name(&name).unwrap())
+}
+
+// The size of a program's automaton
+fn automaton_stats(compiled : &Compiled) -> Json {
+    let transitions : usize = compiled.autom.state_map.values().map(|transitions| transitions.len()).sum();
+
+    Json::object(vec![
+        ("states", compiled.autom.state_total.into()),
+        ("transitions", transitions.into()),
+    ])
+}
+
+// Read a program's source code
+fn read_source(file_path : &str) -> Result<String, String> {
+    match fs::read_to_string(file_path) {
+        Ok(str) => Ok(str),
+        Err(_) => Err(format!("Couldn't find {:?}!", file_path)),
+    }
+}
+
+// Load and compile a program, printing any warnings to stderr
+fn load(file_path : &str, format : Format) -> Result<Compiled, Failure> {
+    let source = read_source(file_path)?;
+
+    let compiled = compile(&source).map_err(|diagnostics| Failure::Compile {
+        file : file_path.to_string(),
+        source : source.clone(),
+        diagnostics,
+    })?;
+
+    for warning in compiled.warnings.iter().filter(|diagnostic| diagnostic.severity == Severity::Warning) {
+        match format {
+            Format::Text => eprintln!("{}", warning.render(&source, file_path)),
+            Format::Json => eprintln!("{}", warning.to_json(&source)),
+        }
+    }
+
+    Ok(compiled)
+}
+
+// The name of an outcome, as printed in results
+fn verdict_name(outcome : Outcome) -> &'static str {
+    match outcome {
+        Outcome::Accepted          => "accept",
+        Outcome::Rejected          => "reject",
+        Outcome::BudgetExceeded(_) => "unknown",
+        Outcome::Failed(_)         => "error",
+    }
+}
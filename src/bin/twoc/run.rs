@@ -0,0 +1,336 @@
+// Running a program on a word, or on every word in a list
+
+use std::fs;
+use std::collections::HashSet;
+use std::io::{self, Read};
+use std::time::{Duration, Instant};
+
+use clap::{ArgGroup, Args};
+
+use twoc::json::{Json, ToJson};
+use twoc::simulation::algorithm::Algorithm;
+use twoc::simulation::budget::{Budget, Outcome, Stop};
+use twoc::simulation::explain::explain_with_budget;
+use twoc::words::word_list::{ListedWord, EMPTY_WORD, parse_word_list};
+use twoc::words::pattern::Pattern;
+use twoc::words::families::{pattern_family, all_words, parse_range};
+
+use crate::{Failure, Format, EXIT_ACCEPT, EXIT_REJECT, EXIT_RUNTIME_ERROR, algorithm_parser, automaton_stats, load, verdict_name};
+
+// How many steps leading up to a rejection --explain shows
+const EXPLAIN_HISTORY : usize = 8;
+
+#[derive(Args, Debug)]
+#[command(group(ArgGroup::new("input").required(true).args(["word", "words", "pattern", "all_up_to"])))]
+pub struct RunArgs {
+    #[arg(short, long)]
+    file : String,
+
+    /// The word to run the program on ("" for the empty word)
+    #[arg(short, long)]
+    word : Option<String>,
+
+    /// A file with one word per line (- for stdin), each optionally followed by "accept" or "reject"
+    #[arg(long)]
+    words : Option<String>,
+
+    /// Run on the family of words a pattern describes, e.g. 0^{2n}1^{2n} or (01)^{n+1}, where "." is a random letter
+    #[arg(long)]
+    pattern : Option<String>,
+
+    /// The values of n to instantiate the pattern at, e.g. 1..1000 or 0..=10
+    #[arg(long, default_value = "0..=10")]
+    n : String,
+
+    /// The step between values of n
+    #[arg(long, default_value_t = 1)]
+    step : usize,
+
+    /// Seed for the random letters in the pattern
+    #[arg(long, default_value_t = 0)]
+    seed : u64,
+
+    /// Run on every word over the alphabet with at most this many letters
+    #[arg(long)]
+    all_up_to : Option<usize>,
+
+    #[arg(short, long, default_value_t = false)]
+    verbose : bool,
+
+    /// The simulator to run (picked from the program if not given)
+    #[arg(long, value_parser = algorithm_parser())]
+    algorithm : Option<Algorithm>,
+
+    /// Threads for rytter_parallel, which is picked for nondeterministic programs if this is more than 1
+    #[arg(long, default_value_t = 1)]
+    threads : usize,
+
+    #[arg(long, default_value_t = false)]
+    stats : bool,
+
+    /// Explain why the word was rejected (deterministic programs only)
+    #[arg(long, default_value_t = false)]
+    explain : bool,
+
+    #[arg(long)]
+    max_steps : Option<u64>,
+
+    #[arg(long)]
+    max_configs : Option<usize>,
+
+    /// Time limit for each word
+    #[arg(long)]
+    timeout_ms : Option<u64>,
+}
+
+// Run a program on the words given on the command line
+// A single word exits with whether it was accepted, and a list of words exits with whether they all got
+// the verdicts the list expects
+pub fn run(args : RunArgs, format : Format) -> Result<u8, Failure> {
+    let verbose = args.verbose && format == Format::Text;
+    let threads = args.threads;
+
+    let compiled = load(&args.file, format)?;
+
+    // The words to run the program on
+    let words = get_words(&args, &compiled.desugared.alpha)?;
+
+    // Crash if an input string isn't consistent with the parsed alphabet
+    for listed in &words {
+        if !compiled.desugared.check_if_input_in_alphabet(&listed.word) {
+            return Err(format!("{:?} contains characters that aren't in the program's alphabet!", listed.word).into());
+        }
+    }
+
+    if args.explain && (!compiled.deterministic || args.word.is_none()) {
+        return Err("--explain only works on a single word with a deterministic program!".to_string().into());
+    }
+
+    // Pick the procedure to simulate with
+    let algorithm = match args.algorithm {
+        Some(algorithm) => {
+            algorithm.check(&compiled)?;
+            algorithm
+        },
+
+        None => Algorithm::pick(&compiled, threads),
+    };
+
+    if verbose {
+        println!("{} program, simulating with {}", if compiled.deterministic {"Deterministic"} else {"Nondeterministic"}, algorithm.name());
+    }
+
+    // Freeze the automaton the algorithm simulates
+    // This only happens once, however many words there are
+    let autom = algorithm.automaton(&compiled);
+
+    // A single word on the command line gets a human readable answer
+    if args.word.is_some() {
+        let word = &words[0].word;
+
+        let start = Instant::now();
+        let (outcome, stats) = algorithm.run(&autom, word, &make_budget(&args), threads, args.stats || format == Format::Json);
+        let time_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        let explanation = match args.explain && outcome == Outcome::Rejected {
+            true  => match explain_with_budget(&compiled.frozen, word, EXPLAIN_HISTORY, &make_budget(&args)) {
+                Ok(Some(explanation)) => Ok(Some(explanation)),
+                Ok(None) => return Err("The step by step run accepted, but the simulator rejected!".to_string().into()),
+                Err(Stop::Exceeded(exceeded)) => Err(exceeded),
+                Err(Stop::Fault(fault)) => return Err(format!("Couldn't explain the run: {}", fault).into()),
+            },
+            false => Ok(None),
+        };
+
+        match format {
+            Format::Text => {
+                print_outcome(word, outcome);
+
+                match &explanation {
+                    Ok(Some(explanation)) => print!("\n{}", explanation),
+                    Ok(None) => (),
+                    Err(exceeded) => println!("\nThe run couldn't be explained before the {} budget ran out", exceeded),
+                }
+
+                if args.stats {
+                    println!();
+                    stats.print();
+                }
+            },
+
+            Format::Json => {
+                let mut result = vec![
+                    ("word", word.as_str().into()),
+                    ("verdict", outcome.to_json()),
+                    ("algorithm", algorithm.name().into()),
+                    ("time_ms", time_ms.into()),
+                    ("deterministic", compiled.deterministic.into()),
+                    ("one_way", compiled.one_way.into()),
+                    ("automaton", automaton_stats(&compiled)),
+                    ("stats", stats.to_json()),
+                ];
+
+                match outcome {
+                    Outcome::BudgetExceeded(exceeded) => result.push(("budget_exceeded", exceeded.to_string().into())),
+                    Outcome::Failed(fault) => result.push(("fault", fault.to_string().into())),
+                    _ => (),
+                }
+
+                if args.explain {
+                    result.push(("explanation", match &explanation {
+                        Ok(explanation) => explanation.as_ref().map(|explanation| explanation.to_json()).into(),
+                        Err(_) => Json::Null,
+                    }));
+                }
+
+                println!("{}", Json::object(result));
+            },
+        }
+
+        return Ok(match outcome {
+            Outcome::Accepted => EXIT_ACCEPT,
+            Outcome::Rejected => EXIT_REJECT,
+            Outcome::BudgetExceeded(_) | Outcome::Failed(_) => EXIT_RUNTIME_ERROR,
+        });
+    }
+
+    // Otherwise print a line of results per word
+    if format == Format::Text {
+        println!("word,verdict,algorithm,time_ms");
+    }
+
+    let mut mismatches = 0;
+    let mut undecided = 0;
+
+    for listed in &words {
+        let start = Instant::now();
+        let (outcome, _) = algorithm.run(&autom, &listed.word, &make_budget(&args), threads, false);
+        let time_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        let verdict = verdict_name(outcome);
+
+        match format {
+            Format::Text => println!("{},{},{},{:.3}", csv_field(&listed.word), verdict, algorithm.name(), time_ms),
+            Format::Json => println!("{}", Json::object(vec![
+                ("word", listed.word.as_str().into()),
+                ("verdict", verdict.into()),
+                ("expected", listed.expected.map(|expected| verdict_name(Outcome::decided(expected))).into()),
+                ("algorithm", algorithm.name().into()),
+                ("time_ms", time_ms.into()),
+            ])),
+        }
+
+        if let Outcome::BudgetExceeded(_) | Outcome::Failed(_) = outcome {
+            undecided += 1;
+        }
+
+        // Check the verdict against the list, if it gives one
+        if let Some(expected) = listed.expected {
+            if outcome != Outcome::decided(expected) {
+                eprintln!(
+                    "Line {}: expected {:?} to get {}, but it got {}",
+                    listed.line, listed.word, verdict_name(Outcome::decided(expected)), verdict
+                );
+                mismatches += 1;
+            }
+        }
+    }
+
+    if mismatches > 0 {
+        eprintln!("{} of {} words didn't get the expected verdict", mismatches, words.len());
+    }
+
+    // A word that couldn't be decided is a runtime error, as it is when a single word is run
+    Ok(match (undecided, mismatches) {
+        (0, 0) => EXIT_ACCEPT,
+        (0, _) => EXIT_REJECT,
+        _      => EXIT_RUNTIME_ERROR,
+    })
+}
+
+// Get the words to run the program on from whichever of the options were given
+fn get_words(args : &RunArgs, alpha : &HashSet<char>) -> Result<Vec<ListedWord>, String> {
+    // Words that don't come from a list aren't expected to get any particular verdict
+    let unlisted = |words : Vec<String>| words.into_iter()
+        .enumerate()
+        .map(|(i, word)| ListedWord { word, expected : None, line : i + 1 })
+        .collect();
+
+    // Letters are picked from the alphabet in a fixed order, so that seeds are reproducible
+    let mut alphabet : Vec<char> = alpha.iter().copied().collect();
+    alphabet.sort();
+
+    if let Some(word) = &args.word {
+        let word = match word.as_str() {
+            "//EMPTY//" | EMPTY_WORD => "",
+            _ => word,
+        };
+
+        Ok(unlisted(vec![word.to_string()]))
+    }
+
+    else if let Some(words_path) = &args.words {
+        parse_word_list(&read_word_list(words_path)?)
+    }
+
+    else if let Some(pattern) = &args.pattern {
+        let pattern = Pattern::parse(pattern)?;
+        let ns = parse_range(&args.n, args.step)?;
+
+        Ok(unlisted(pattern_family(&pattern, &ns, &alphabet, args.seed).into_iter().map(|(_, word)| word).collect()))
+    }
+
+    else if let Some(max_len) = args.all_up_to {
+        Ok(unlisted(all_words(&alphabet, max_len)))
+    }
+
+    else {
+        Err("No words were given to run the program on!".to_string())
+    }
+}
+
+// Read a word list from a file, or from stdin if the path is -
+fn read_word_list(path : &str) -> Result<String, String> {
+    if path == "-" {
+        let mut text = String::new();
+
+        return match io::stdin().read_to_string(&mut text) {
+            Ok(_) => Ok(text),
+            Err(err) => Err(format!("Couldn't read the words from stdin: {}", err)),
+        };
+    }
+
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(text),
+        Err(_) => Err(format!("Couldn't find {:?}!", path)),
+    }
+}
+
+// Give up on a word if the simulator runs past any of these
+// The time limit starts when this is called, so each word gets a fresh one
+fn make_budget(args : &RunArgs) -> Budget {
+    Budget {
+        max_steps : args.max_steps,
+        max_configs : args.max_configs,
+        deadline : args.timeout_ms.map(|ms| Instant::now() + Duration::from_millis(ms)),
+    }
+}
+
+// A word as a CSV field, quoted if it has a comma, quote or line break in it
+fn csv_field(word : &str) -> String {
+    match word.contains([',', '"', '\n', '\r']) {
+        true  => format!("\"{}\"", word.replace('"', "\"\"")),
+        false => word.to_string(),
+    }
+}
+
+// Tell the user what happened to the word
+fn print_outcome(word : &str, outcome : Outcome) {
+    match outcome {
+        Outcome::Accepted => println!("\n{:?} is accepted", word),
+        Outcome::Rejected => println!("\n{:?} is rejected", word),
+        Outcome::BudgetExceeded(exceeded)
+            => println!("\n{:?} couldn't be decided before the {} budget ran out", word, exceeded),
+        Outcome::Failed(fault) => println!("\n{:?} couldn't be decided: {}", word, fault),
+    }
+}
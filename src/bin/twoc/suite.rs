@@ -0,0 +1,91 @@
+// Checking programs against the examples in their comments
+
+use std::path::Path;
+
+use clap::Args;
+
+use twoc::json::{Json, ToJson};
+use twoc::words::suite::check_dir;
+
+use crate::{Failure, Format, EXIT_ACCEPT, EXIT_REJECT};
+
+#[derive(Args, Debug)]
+pub struct TestArgs {
+    /// The directory to look for programs in (subdirectories included)
+    dir : String,
+}
+
+// Check the programs in a directory against their examples, printing a line for each failure and then a summary
+pub fn test(args : TestArgs, format : Format) -> Result<u8, Failure> {
+    let (reports, skipped) = check_dir(Path::new(&args.dir))?;
+
+    let examples : usize = reports.iter().map(|report| report.examples).sum();
+    let failed : usize = reports.iter().map(|report| report.mismatches.len()).sum();
+    let undecided : usize = reports.iter().map(|report| report.undecided).sum();
+    let errors = reports.iter().filter(|report| report.error.is_some()).count();
+
+    // The examples of the programs that could be run at all
+    let checked : usize = reports.iter().filter(|report| report.error.is_none()).map(|report| report.examples).sum();
+
+    match format {
+        Format::Text => {
+            for report in &reports {
+                if let Some(error) = &report.error {
+                    println!("ERROR {}: {}", report.file, error);
+                }
+
+                for mismatch in &report.mismatches {
+                    println!("FAIL {}: {}", report.file, mismatch);
+                }
+            }
+
+            let mut summary = format!("{} examples in {} programs: {} passed, {} failed", examples, reports.len(), checked - failed, failed);
+
+            if errors > 0 {
+                summary.push_str(&format!(", {} program{} couldn't be checked", errors, if errors == 1 {""} else {"s"}));
+            }
+
+            if undecided > 0 {
+                summary.push_str(&format!(", {} simulator run{} ran out of budget", undecided, if undecided == 1 {""} else {"s"}));
+            }
+
+            if skipped > 0 {
+                summary.push_str(&format!(" ({} program{} without examples skipped)", skipped, if skipped == 1 {""} else {"s"}));
+            }
+
+            println!("{}", summary);
+        },
+
+        Format::Json => {
+            let files = reports.iter()
+                .map(|report| Json::object(vec![
+                    ("file", report.file.as_str().into()),
+                    ("passed", report.passed().into()),
+                    ("examples", report.examples.into()),
+                    ("undecided", report.undecided.into()),
+                    ("error", report.error.clone().into()),
+                    ("mismatches", Json::Array(report.mismatches.iter()
+                        .map(|mismatch| Json::object(vec![
+                            ("line", mismatch.line.into()),
+                            ("word", mismatch.word.as_str().into()),
+                            ("expected", if mismatch.expected {"accept"} else {"reject"}.into()),
+                            ("wrong", Json::Array(mismatch.wrong.iter()
+                                .map(|(name, outcome)| Json::object(vec![("simulator", name.as_str().into()), ("verdict", outcome.to_json())]))
+                                .collect())),
+                        ]))
+                        .collect())),
+                ]))
+                .collect();
+
+            println!("{}", Json::object(vec![
+                ("passed", reports.iter().all(|report| report.passed()).into()),
+                ("examples", examples.into()),
+                ("failed", failed.into()),
+                ("skipped", skipped.into()),
+                ("files", Json::Array(files)),
+            ]));
+        },
+    }
+
+    Ok(if reports.iter().all(|report| report.passed()) {EXIT_ACCEPT} else {EXIT_REJECT})
+}
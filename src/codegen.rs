@@ -0,0 +1,202 @@
+// Generating standalone Rust source for recognizers, so that other projects can embed a deterministic
+// program without depending on twoc
+// The generated module holds the automaton's transitions as a table, along with a copy of the
+// Glueck procedure that glueck_array runs, so it decides a word in time linear in its length
+
+use crate::automaton::autom::Autom;
+use crate::automaton::compiled::{CompiledAutom, Symbol};
+use crate::automaton::determinism::find_conflicts;
+use crate::parser::ast::Readable;
+
+// The part of the generated module that doesn't depend on the automaton
+const DRIVER : &str = r#"
+// A configuration of the automaton: (state, read head position, counter)
+type Config = (usize, usize, i64);
+
+// What has to be done to find the terminator of a configuration
+enum Step {
+    // The configuration is its own terminator
+    Terminator,
+
+    // The terminator is that of the next configuration
+    Op(Config),
+
+    // The terminator of the next configuration has to be popped from first
+    Push(Config),
+}
+
+// Whether the automaton accepts a word
+pub fn accepts(input : &str) -> bool {
+    let mut tape = vec![0];
+    tape.extend(input.chars().map(symbol));
+    tape.push(1);
+
+    let len = tape.len();
+    let index = |(state, read, counter) : Config| (state * len + read) * 2 + if counter == 0 {0} else {1};
+
+    // The terminators found so far, with the change in the counter rather than its value
+    let mut terminators : Vec<Option<Config>> = vec![None; STATES * len * 2];
+
+    // The configurations that are waiting on the one being simulated
+    let mut on_stack = vec![false; STATES * len * 2];
+    let mut stack : Vec<(Config, bool)> = Vec::new();
+
+    let mut current = (0, 0, 0);
+
+    loop {
+        let i = index(current);
+
+        // A deterministic automaton that gets back to a configuration it's still simulating never halts
+        if on_stack[i] {
+            return false;
+        }
+
+        let terminator = match terminators[i] {
+            Some((state, read, change)) => (state, read, current.2 + change),
+
+            None => match step(&tape, current) {
+                Step::Terminator => current,
+
+                Step::Op(next_config) => {
+                    on_stack[i] = true;
+                    stack.push((current, false));
+                    current = next_config;
+                    continue;
+                },
+
+                Step::Push(next_config) => {
+                    on_stack[i] = true;
+                    stack.push((current, true));
+                    current = next_config;
+                    continue;
+                },
+            },
+        };
+
+        // Unwind the stack until some configuration has to carry on after a pop
+        loop {
+            match stack.pop() {
+                None => return HALTING[terminator.0] == Some(true),
+
+                Some((config, false)) => {
+                    let j = index(config);
+                    terminators[j] = Some((terminator.0, terminator.1, terminator.2 - config.2));
+                    on_stack[j] = false;
+                },
+
+                Some((config, true)) => match transition(&tape, terminator).and_then(|action| next(&tape, terminator, action)) {
+                    None => return HALTING[terminator.0] == Some(true),
+
+                    Some(next_config) => {
+                        stack.push((config, false));
+                        current = next_config;
+                        break;
+                    },
+                },
+            }
+        }
+    }
+}
+
+// Work out what to do with a configuration whose terminator isn't known
+fn step(tape : &[usize], config : Config) -> Step {
+    if let Some(accepting) = HALTING[config.0] {
+        if !accepting || config.2 == 0 {
+            return Step::Terminator;
+        }
+    }
+
+    let action = match transition(tape, config) {
+        None => return Step::Terminator,
+        Some(action) => action,
+    };
+
+    // Decrementing a non-empty counter pops
+    if action.1 < 0 && config.2 > 0 {
+        return Step::Terminator;
+    }
+
+    match next(tape, config, action) {
+        None => Step::Terminator,
+        Some(next_config) if action.1 > 0 => Step::Push(next_config),
+        Some(next_config) => Step::Op(next_config),
+    }
+}
+
+// The transition that fires from a configuration, if there is one
+fn transition(tape : &[usize], (state, read, counter) : Config) -> Option<(usize, i64, i64)> {
+    TRANSITIONS[(state * SYMBOLS + tape[read]) * 2 + if counter == 0 {0} else {1}]
+}
+
+// The configuration after a transition, if the transition is allowed to change the counter
+fn next(tape : &[usize], (_, read, counter) : Config, (goto, incr_by, move_by) : (usize, i64, i64)) -> Option<Config> {
+    let read = (read as i64 + move_by).clamp(0, tape.len() as i64 - 1) as usize;
+
+    let counter = counter + incr_by;
+    if !DECR_ZERO && counter < 0 {
+        return None;
+    }
+
+    Some((goto, read, counter.max(0)))
+}
+"#;
+
+// Generate a Rust module with a function accepts(input : &str) -> bool that decides the same
+// language as a deterministic automaton
+// Fails if the automaton has transitions that can fire at the same time
+pub fn rust_recognizer(autom : &Autom) -> Result<String, String> {
+    if let Some(conflict) = find_conflicts(autom).first() {
+        return Err(format!("The automaton isn't deterministic: {}", conflict));
+    }
+
+    let compiled = CompiledAutom::new(autom);
+    let states = compiled.state_total as usize;
+    let symbols = compiled.num_symbols();
+
+    let mut out = String::new();
+
+    out.push_str(&format!("// A recognizer generated by twoc from a deterministic two-way one-counter automaton with {} states\n", states));
+    out.push_str("// Call accepts(input) to check a word, which takes time linear in its length\n\n");
+
+    out.push_str(&format!("const STATES : usize = {};\n", states));
+    out.push_str(&format!("const SYMBOLS : usize = {};\n", symbols));
+    out.push_str(&format!("const DECR_ZERO : bool = {};\n\n", compiled.decr_zero));
+
+    // The letters the automaton tells apart (between the endmarkers and the symbol for every other letter)
+    out.push_str("// The symbol each letter is read as, where 0 and 1 are the endmarkers\n");
+    out.push_str("fn symbol(c : char) -> usize {\n    match c {\n");
+    for symbol in 2..symbols - 1 {
+        if let Readable::Char(c) = compiled.readable(symbol as Symbol) {
+            out.push_str(&format!("        {:?} => {},\n", c, symbol));
+        }
+    }
+    out.push_str(&format!("        _ => {},\n    }}\n}}\n\n", symbols - 1));
+
+    out.push_str("// Whether each state accepts (Some(true)), rejects (Some(false)) or doesn't halt (None)\n");
+    out.push_str("static HALTING : [Option<bool>; STATES] = [\n");
+    for state in 0..compiled.state_total {
+        out.push_str(&format!("    {:?},\n", compiled.check_if_halting(state)));
+    }
+    out.push_str("];\n\n");
+
+    out.push_str("// The (state, counter change, move) of the transition from each state on each symbol,\n");
+    out.push_str("// with the counter zero and then non-zero\n");
+    out.push_str("static TRANSITIONS : [Option<(usize, i64, i64)>; STATES * SYMBOLS * 2] = [\n");
+    for state in 0..compiled.state_total {
+        for symbol in 0..symbols {
+            let row = [true, false].iter()
+                .map(|counter_zero| match compiled.transition(state, symbol as Symbol, *counter_zero) {
+                    Ok(Some(action)) => Ok(format!("Some(({}, {}, {}))", action.goto, action.incr_by, action.move_by)),
+                    Ok(None) => Ok("None".to_string()),
+                    Err(fault) => Err(format!("The automaton isn't deterministic: {}", fault)),
+                })
+                .collect::<Result<Vec<String>, String>>()?;
+
+            out.push_str(&format!("    {},\n", row.join(", ")));
+        }
+    }
+    out.push_str("];\n");
+
+    out.push_str(DRIVER);
+    Ok(out)
+}
@@ -0,0 +1,266 @@
+// The pipeline from source code to an automaton that's ready to simulate:
+// parse, desugar, contract and construct, collecting anything wrong with the program along the way
+
+use std::fmt;
+
+use lalrpop_util::ParseError;
+
+use crate::automaton::{construction, determ_construction, one_way};
+use crate::automaton::autom::Autom;
+use crate::automaton::compiled::CompiledAutom;
+use crate::automaton::determinism::find_conflicts;
+use crate::parser::ast::{Cond, Readable};
+use crate::parser::program::Program;
+use crate::parser::sugar::ast::{Span, Stmt, Value};
+use crate::parser::sugar::convert_sugar::convert_sugar;
+use crate::parser::sugar::program::Program as SugarProgram;
+use crate::parser::sugar::sugar_grammar::TwocParser;
+
+// How bad a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    // The program can't be compiled
+    Error,
+
+    // The program compiles, but probably doesn't do what was meant
+    Warning,
+}
+
+// Something wrong with a program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity : Severity,
+    pub message : String,
+
+    // The byte range of the source the diagnostic is about, if it's known
+    pub span : Option<(usize, usize)>,
+}
+
+impl Diagnostic {
+    pub fn error(message : String, span : Option<(usize, usize)>) -> Self {
+        Self { severity : Severity::Error, message, span }
+    }
+
+    pub fn warning(message : String, span : Option<(usize, usize)>) -> Self {
+        Self { severity : Severity::Warning, message, span }
+    }
+
+    // The line and column (both starting at 1) that the diagnostic starts at
+    pub fn line_col(&self, source : &str) -> Option<(usize, usize)> {
+        let (start, _) = self.span?;
+        let before = &source[..start.min(source.len())];
+
+        let line = before.matches('\n').count() + 1;
+        let col = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+
+        Some((line, col))
+    }
+
+    // The diagnostic as a line of text, e.g. "prog.twoc:3:5: error: ..."
+    pub fn render(&self, source : &str, file_name : &str) -> String {
+        let severity = match self.severity {
+            Severity::Error   => "error",
+            Severity::Warning => "warning",
+        };
+
+        match self.line_col(source) {
+            Some((line, col)) => format!("{}:{}:{}: {}: {}", file_name, line, col, severity, self.message),
+            None => format!("{}: {}: {}", file_name, severity, self.message),
+        }
+    }
+}
+
+// Everything wrong with a program that couldn't be compiled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostics(pub Vec<Diagnostic>);
+
+impl Diagnostics {
+    // Every diagnostic, one per line
+    pub fn render(&self, source : &str, file_name : &str) -> String {
+        self.0.iter().map(|diagnostic| diagnostic.render(source, file_name)).collect::<Vec<_>>().join("\n")
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        for diagnostic in &self.0 {
+            writeln!(f, "{}", diagnostic.message)?;
+        }
+
+        Ok(())
+    }
+}
+
+// A program at every stage of the pipeline
+#[derive(Clone)]
+pub struct Compiled {
+    // The program as it was parsed
+    pub sugared : SugarProgram,
+
+    // The program once its syntactic sugar has been removed
+    pub desugared : Program,
+
+    // The desugared program with its moves and increments contracted into basic blocks
+    pub contracted : Program,
+
+    // Whether the program has no branch or while-choose statements
+    pub deterministic : bool,
+
+    // The automaton, from the deterministic construction if the program's deterministic
+    // and from the general one otherwise
+    pub autom : Autom,
+
+    // The automaton frozen for simulation
+    pub frozen : CompiledAutom,
+
+    // Whether the automaton never moves left
+    pub one_way : bool,
+
+    // Anything suspicious about the program that didn't stop it compiling
+    pub warnings : Vec<Diagnostic>,
+}
+
+impl Compiled {
+    // The general construction of the program frozen for simulation, which the nondeterministic
+    // simulators need even if the program's deterministic
+    pub fn general(&self) -> CompiledAutom {
+        match self.deterministic {
+            true  => CompiledAutom::new(&construction::construct_from_prog(self.contracted.clone())),
+            false => self.frozen.clone(),
+        }
+    }
+}
+
+// Parse a program, without desugaring it
+pub fn parse(source : &str) -> Result<SugarProgram, Diagnostics> {
+    TwocParser::new().parse(source).map_err(|err| Diagnostics(vec![parse_diagnostic(source, err)]))
+}
+
+// Parse, desugar, contract and construct a program
+pub fn compile(source : &str) -> Result<Compiled, Diagnostics> {
+    let sugared = parse(source)?;
+
+    let diagnostics = check(&sugared);
+    if diagnostics.iter().any(|diagnostic| diagnostic.severity == Severity::Error) {
+        return Err(Diagnostics(diagnostics));
+    }
+
+    let desugared = convert_sugar(sugared.clone());
+    let deterministic = desugared.deterministic();
+
+    let mut contracted = desugared.clone();
+    contracted.contract();
+
+    let autom = match deterministic {
+        true  => determ_construction::construct_from_prog(contracted.clone()),
+        false => construction::construct_from_prog(contracted.clone()),
+    };
+
+    // The deterministic simulators can't run an automaton with overlapping guards
+    if deterministic {
+        let conflicts : Vec<Diagnostic> = find_conflicts(&autom).iter()
+            .map(|conflict| Diagnostic::error(format!("The program's automaton isn't deterministic: {}", conflict), None))
+            .collect();
+
+        if !conflicts.is_empty() {
+            return Err(Diagnostics(conflicts));
+        }
+    }
+
+    let frozen = CompiledAutom::new(&autom);
+    let one_way = one_way::is_one_way(&autom);
+
+    Ok(Compiled { sugared, desugared, contracted, deterministic, autom, frozen, one_way, warnings : diagnostics })
+}
+
+// Turn a lalrpop error into a diagnostic
+fn parse_diagnostic<T : fmt::Display>(source : &str, err : ParseError<usize, T, (String, (usize, usize))>) -> Diagnostic {
+    let expected = |expected : Vec<String>| match expected.is_empty() {
+        true  => String::new(),
+        false => format!(", expected one of {}", expected.join(", ")),
+    };
+
+    match err {
+        ParseError::InvalidToken { location } => {
+            let c = source[location..].chars().next().unwrap_or(' ');
+            Diagnostic::error(format!("Unrecognised character {:?}", c), Some((location, location + c.len_utf8())))
+        },
+
+        ParseError::UnrecognizedEOF { location, expected : exp } =>
+            Diagnostic::error(format!("Unexpected end of file{}", expected(exp)), Some((location, location))),
+
+        ParseError::UnrecognizedToken { token : (start, _, end), expected : exp } =>
+            Diagnostic::error(format!("Unexpected {:?}{}", &source[start..end], expected(exp)), Some((start, end))),
+
+        ParseError::ExtraToken { token : (start, _, end) } =>
+            Diagnostic::error(format!("Unexpected {:?} after the end of the program", &source[start..end]), Some((start, end))),
+
+        ParseError::User { error : (message, span) } => Diagnostic::error(message, Some(span)),
+    }
+}
+
+// Find the problems with a parsed program that would otherwise make desugaring panic,
+// along with conditions that read letters outside of the alphabet
+fn check(prog : &SugarProgram) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    check_block(prog, &prog.stmts, &mut diagnostics);
+    diagnostics
+}
+
+fn check_block(prog : &SugarProgram, block : &[Stmt], diagnostics : &mut Vec<Diagnostic>) {
+    for stmt in block {
+        match stmt {
+            Stmt::Incr(value, span) | Stmt::Asgn(value, span) => {
+                if let Value::Par(par) | Value::NegPar(par) = value {
+                    if !prog.parmap.contains_key(par) {
+                        diagnostics.push(Diagnostic::error(format!("Parameter {:?} isn't declared", par), *span));
+                    }
+                }
+
+                match (stmt, value) {
+                    (Stmt::Asgn(..), Value::Lit(j)) if *j < 0 =>
+                        diagnostics.push(Diagnostic::error(format!("The counter can't be assigned the negative value {}", j), *span)),
+
+                    (Stmt::Asgn(..), Value::NegPar(par)) =>
+                        diagnostics.push(Diagnostic::error(format!("The counter can't be assigned the negative value -{}", par), *span)),
+
+                    _ => (),
+                }
+            },
+
+            Stmt::If(cond, if_block, else_block, span) => {
+                check_cond(prog, cond, *span, diagnostics);
+                check_block(prog, if_block, diagnostics);
+                check_block(prog, else_block, diagnostics);
+            },
+
+            Stmt::While(cond, body, span) => {
+                check_cond(prog, cond, *span, diagnostics);
+                check_block(prog, body, diagnostics);
+            },
+
+            Stmt::Branch(blocks) => blocks.iter().for_each(|block| check_block(prog, block, diagnostics)),
+
+            Stmt::WhileChoose(body) | Stmt::Repeat(_, body) | Stmt::Countertest(_, body) => check_block(prog, body, diagnostics),
+
+            Stmt::Accept() | Stmt::Reject() | Stmt::Move(_) | Stmt::Goto(_) => (),
+        }
+    }
+}
+
+// Conditions don't have spans of their own, so diagnostics about them are put on the whole condition
+fn check_cond(prog : &SugarProgram, cond : &Cond, span : Span, diagnostics : &mut Vec<Diagnostic>) {
+    match cond {
+        Cond::Read(Readable::Char(c)) | Cond::NotRead(Readable::Char(c)) if !prog.alpha.contains(c) =>
+            diagnostics.push(Diagnostic::warning(format!("{:?} isn't in the alphabet, so it's never read", c), span)),
+
+        Cond::And(x, y) | Cond::Or(x, y) => {
+            check_cond(prog, x, span, diagnostics);
+            check_cond(prog, y, span, diagnostics);
+        },
+
+        Cond::Not(x) => check_cond(prog, x, span, diagnostics),
+
+        _ => (),
+    }
+}
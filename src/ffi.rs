@@ -0,0 +1,243 @@
+// A C API for compiling programs and running them on words, so that twoc can be called from C, Python and so on
+// The header, include/twoc.h, is generated from this file by `cargo run -p xtask -- header` (the /// comments end up in it)
+//
+// Every export catches panics, so a bug in twoc makes the call fail with TWOC_ERROR instead of unwinding into C
+
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::sync::OnceLock;
+use std::thread;
+
+use crate::automaton::compiled::CompiledAutom;
+use crate::compiler::{compile, Compiled};
+use crate::simulation::algorithm::Algorithm;
+use crate::simulation::budget::{Budget, Outcome};
+
+/// twoc_run's result when the word is accepted
+pub const TWOC_ACCEPTED : c_int = 1;
+
+/// twoc_run's result when the word is rejected
+pub const TWOC_REJECTED : c_int = 0;
+
+/// The result of a call that failed, which twoc_last_error describes.
+/// Calls also fail this way if twoc panics, rather than unwinding into the caller.
+pub const TWOC_ERROR : c_int = -1;
+
+thread_local! {
+    // The message of the last call on this thread that failed
+    static LAST_ERROR : RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// A compiled program, made by twoc_compile and freed by twoc_free.
+/// twoc_stats and twoc_run only read it, so one automaton can be used from several threads at once,
+/// as long as none of them frees it while the others are still using it.
+pub struct TwocAutomaton {
+    compiled : Compiled,
+
+    // The general construction, built the first time a nondeterministic simulator needs it
+    // Threads running at the same time wait for whichever one builds it
+    general : OnceLock<CompiledAutom>,
+}
+
+/// The size and kind of a compiled program's automaton
+#[repr(C)]
+pub struct TwocStats {
+    /// The number of states in the automaton
+    pub states : u32,
+
+    /// The number of transitions in the automaton
+    pub transitions : u64,
+
+    /// The number of letters in the program's alphabet
+    pub alphabet_size : u32,
+
+    /// Whether the program has no branch or while-choose statements
+    pub deterministic : bool,
+
+    /// Whether the automaton never moves left
+    pub one_way : bool,
+}
+
+// Record why a call failed, returning what it should return
+fn fail<T>(message : String, result : T) -> T {
+    // C strings can't hold nul bytes, so anything after one is left out
+    let message = message.split('\0').next().unwrap_or_default().to_string();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(CString::new(message).unwrap()));
+    result
+}
+
+// Run the body of an export, failing with result if it panics
+fn guard<T>(result : T, body : impl FnOnce() -> T) -> T {
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(out) => out,
+        Err(payload) => {
+            let message = payload.downcast_ref::<&str>().map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or("unknown panic".to_string());
+
+            fail(format!("twoc panicked: {}", message), result)
+        },
+    }
+}
+
+// Borrow a C string as UTF-8
+unsafe fn borrow_str<'a>(string : *const c_char, name : &str) -> Result<&'a str, String> {
+    if string.is_null() {
+        return Err(format!("The {} is null", name));
+    }
+
+    CStr::from_ptr(string).to_str().map_err(|err| format!("The {} isn't UTF-8: {}", name, err))
+}
+
+/// Compile a program from its source code.
+/// Returns NULL if the program doesn't compile, and twoc_last_error then lists what's wrong with it.
+///
+/// # Safety
+/// source has to be NULL or a nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn twoc_compile(source : *const c_char) -> *mut TwocAutomaton {
+    guard(ptr::null_mut(), || {
+        let source = match borrow_str(source, "source") {
+            Ok(source) => source,
+            Err(err) => return fail(err, ptr::null_mut()),
+        };
+
+        match compile(source) {
+            Ok(compiled) => Box::into_raw(Box::new(TwocAutomaton { compiled, general : OnceLock::new() })),
+            Err(diagnostics) => fail(diagnostics.render(source, "source"), ptr::null_mut()),
+        }
+    })
+}
+
+/// Free a compiled program. Does nothing if automaton is NULL.
+///
+/// # Safety
+/// automaton has to be NULL or have come from twoc_compile, and can't be used after it's freed.
+#[no_mangle]
+pub unsafe extern "C" fn twoc_free(automaton : *mut TwocAutomaton) {
+    guard((), || {
+        if !automaton.is_null() {
+            drop(Box::from_raw(automaton));
+        }
+    })
+}
+
+/// Fill in the statistics of a compiled program.
+/// Returns 0, or TWOC_ERROR if either pointer is NULL.
+///
+/// # Safety
+/// automaton has to be NULL or have come from twoc_compile, and stats has to be NULL or point to a TwocStats.
+#[no_mangle]
+pub unsafe extern "C" fn twoc_stats(automaton : *const TwocAutomaton, stats : *mut TwocStats) -> c_int {
+    guard(TWOC_ERROR, || {
+        let (automaton, stats) = match (automaton.as_ref(), stats.as_mut()) {
+            (Some(automaton), Some(stats)) => (automaton, stats),
+            _ => return fail("The automaton or the stats are null".to_string(), TWOC_ERROR),
+        };
+
+        let compiled = &automaton.compiled;
+
+        *stats = TwocStats {
+            states : compiled.autom.state_total as u32,
+            transitions : compiled.autom.state_map.values().map(|transitions| transitions.len() as u64).sum(),
+            alphabet_size : compiled.desugared.alpha.len() as u32,
+            deterministic : compiled.deterministic,
+            one_way : compiled.one_way,
+        };
+
+        0
+    })
+}
+
+/// Run a compiled program on a word.
+/// algorithm names the simulator to use (e.g. "glueck_array" or "rytter"), or is NULL to pick one from the program:
+/// "streaming" for one-way programs, "glueck_array" for deterministic ones and "rytter" otherwise.
+/// The simulator gives up after max_steps steps, unless max_steps is 0.
+/// threads is the number of threads "rytter_parallel" uses, or 0 to use one per CPU; the other simulators ignore it.
+/// Returns TWOC_ACCEPTED or TWOC_REJECTED, or TWOC_ERROR if the simulator can't run the program,
+/// the word has letters outside of the alphabet or the simulator gave up.
+///
+/// # Safety
+/// automaton has to be NULL or have come from twoc_compile, and word and algorithm have to be NULL or
+/// nul-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn twoc_run(
+    automaton : *const TwocAutomaton,
+    word : *const c_char,
+    algorithm : *const c_char,
+    max_steps : u64,
+    threads : u32,
+) -> c_int {
+    guard(TWOC_ERROR, || {
+        let automaton = match automaton.as_ref() {
+            Some(automaton) => automaton,
+            None => return fail("The automaton is null".to_string(), TWOC_ERROR),
+        };
+
+        let word = match borrow_str(word, "word") {
+            Ok(word) => word,
+            Err(err) => return fail(err, TWOC_ERROR),
+        };
+
+        let algorithm = match algorithm.is_null() {
+            true  => None,
+            false => match borrow_str(algorithm, "algorithm") {
+                Ok(algorithm) => Some(algorithm),
+                Err(err) => return fail(err, TWOC_ERROR),
+            },
+        };
+
+        // Use a thread per CPU if the caller didn't say how many
+        let threads = match threads {
+            0 => thread::available_parallelism().map(|cpus| cpus.get()).unwrap_or(1),
+            threads => threads as usize,
+        };
+
+        match run(automaton, word, algorithm, max_steps, threads) {
+            Ok(true)  => TWOC_ACCEPTED,
+            Ok(false) => TWOC_REJECTED,
+            Err(err)  => fail(err, TWOC_ERROR),
+        }
+    })
+}
+
+fn run(automaton : &TwocAutomaton, word : &str, algorithm : Option<&str>, max_steps : u64, threads : usize) -> Result<bool, String> {
+    let compiled = &automaton.compiled;
+
+    let algorithm = match algorithm {
+        Some(name) => Algorithm::from_name(name)?,
+        None => Algorithm::pick(compiled, 1),
+    };
+
+    algorithm.check(compiled)?;
+
+    if !compiled.desugared.check_if_input_in_alphabet(word) {
+        return Err(format!("{:?} contains characters that aren't in the program's alphabet", word));
+    }
+
+    let autom = match algorithm.general() {
+        true  => automaton.general.get_or_init(|| compiled.general()),
+        false => &compiled.frozen,
+    };
+
+    let budget = Budget { max_steps : (max_steps > 0).then_some(max_steps), ..Budget::default() };
+
+    match algorithm.run(autom, word, &budget, threads, false).0 {
+        Outcome::Accepted => Ok(true),
+        Outcome::Rejected => Ok(false),
+        Outcome::BudgetExceeded(exceeded) => Err(format!("The simulator gave up: {}", exceeded)),
+        Outcome::Failed(fault) => Err(format!("The simulator failed: {}", fault)),
+    }
+}
+
+/// The message of the last call on this thread that failed, or NULL if none has.
+/// Each thread has its own last error, so a call failing on one thread doesn't change what another sees.
+/// The string belongs to twoc, and stays valid until the next call on this thread fails.
+#[no_mangle]
+pub extern "C" fn twoc_last_error() -> *const c_char {
+    guard(ptr::null(), || {
+        LAST_ERROR.with(|last| last.borrow().as_ref().map(|message| message.as_ptr()).unwrap_or(ptr::null()))
+    })
+}
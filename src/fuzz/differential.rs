@@ -0,0 +1,158 @@
+// Running every simulator that applies to a program on the same words, and shrinking any disagreements
+
+use std::fmt;
+
+use crate::automaton::{construction, determ_construction, one_way};
+use crate::automaton::compiled::CompiledAutom;
+use crate::fuzz::generate::{GenConfig, random_program, random_sugar_program};
+use crate::fuzz::shrink::shrink;
+use crate::parser::program::Program;
+use crate::parser::sugar::convert_sugar::convert_sugar;
+use crate::simulation::algorithm::Algorithm;
+use crate::simulation::budget::{Budget, Outcome};
+use crate::words::families::{Rng, random_words};
+
+// The most steps a simulator gets on a single word before its verdict is left out
+// Random programs can be slow to simulate, and a budget keeps one from holding up the rest
+pub const STEP_BUDGET : u64 = 1_000_000;
+
+// Threads for rytter_parallel, so that it's fuzzed with more than one
+const THREADS : usize = 2;
+
+// The verdict a simulator gave on a word
+pub type Verdict = (String, Outcome);
+
+// A program's automata, ready to be simulated by everything that applies to them
+pub struct Simulators {
+    general : CompiledAutom,
+    general_one_way : bool,
+
+    // Only deterministic programs have a deterministic construction
+    determ : Option<(CompiledAutom, bool)>,
+}
+
+impl Simulators {
+    // Constructor
+    pub fn new(prog : &Program) -> Self {
+        let mut prog = prog.clone();
+        prog.contract();
+
+        let general = construction::construct_from_prog(prog.clone());
+        let general_one_way = one_way::is_one_way(&general);
+
+        let determ = prog.deterministic().then(|| {
+            let autom = determ_construction::construct_from_prog(prog);
+            let one_way = one_way::is_one_way(&autom);
+            (CompiledAutom::new(&autom), one_way)
+        });
+
+        Self { general : CompiledAutom::new(&general), general_one_way, determ }
+    }
+
+    // The verdict of every simulator that applies on a word
+    pub fn verdicts(&self, word : &str) -> Vec<Verdict> {
+        let budget = Budget { max_steps : Some(STEP_BUDGET), ..Budget::default() };
+
+        // Everything but the deterministic simulators runs on the general construction
+        let mut verdicts : Vec<Verdict> = Algorithm::ALL.iter()
+            .filter(|algorithm| algorithm.applies(false, self.general_one_way))
+            .map(|algorithm| (algorithm.name().to_string(), algorithm.run(&self.general, word, &budget, THREADS, false).0))
+            .collect();
+
+        // The simulators that don't need the general construction also run on the deterministic one
+        if let Some((determ, one_way)) = &self.determ {
+            for algorithm in Algorithm::ALL.iter().filter(|algorithm| !algorithm.general() && algorithm.applies(true, *one_way)) {
+                let name = match algorithm.deterministic_only() {
+                    true  => algorithm.name().to_string(),
+                    false => format!("{} (deterministic construction)", algorithm.name()),
+                };
+
+                verdicts.push((name, algorithm.run(determ, word, &budget, THREADS, false).0));
+            }
+        }
+
+        verdicts
+    }
+}
+
+// Check if some simulators accept and others reject, leaving out any that ran out of budget
+// A simulator that failed on the word counts as disagreeing with the rest
+pub fn disagree(verdicts : &[Verdict]) -> bool {
+    if verdicts.iter().any(|(_, outcome)| matches!(outcome, Outcome::Failed(_))) {
+        return true;
+    }
+
+    let finished = || verdicts.iter().filter(|(_, outcome)| !matches!(outcome, Outcome::BudgetExceeded(_)));
+
+    finished().any(|(_, outcome)| *outcome == Outcome::Accepted) && finished().any(|(_, outcome)| *outcome == Outcome::Rejected)
+}
+
+// A program and a word that the simulators disagree on
+#[derive(Debug, Clone)]
+pub struct Disagreement {
+    pub program : Program,
+    pub word : String,
+    pub verdicts : Vec<Verdict>,
+}
+
+impl fmt::Display for Disagreement {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        let mut alphabet : Vec<char> = self.program.alpha.iter().copied().collect();
+        alphabet.sort();
+
+        writeln!(f, "The simulators disagree on {:?} (alphabet {:?}, decr_on_zero = {}) with the program:", self.word, alphabet, self.program.decr_zero)?;
+
+        for stmt in &self.program.stmts {
+            write!(f, "{}", stmt.print(2))?;
+        }
+
+        writeln!(f, "Verdicts:")?;
+        for (name, outcome) in &self.verdicts {
+            let verdict = match outcome {
+                Outcome::Accepted => "accept".to_string(),
+                Outcome::Rejected => "reject".to_string(),
+                Outcome::BudgetExceeded(exceeded) => format!("ran out of {} budget", exceeded),
+                Outcome::Failed(fault) => format!("failed: {}", fault),
+            };
+
+            writeln!(f, "  {}: {}", name, verdict)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Check programs random programs against every simulator that applies, on words_per_program random words each
+// of length at most max_word_len. Returns the first disagreement found, shrunk to a minimal program and word.
+// Sugared programs (desugared before simulation) are generated if config.sugar is set, and core ones otherwise
+pub fn differential_test(config : &GenConfig, programs : usize, words_per_program : usize, max_word_len : usize, seed : u64) -> Option<Disagreement> {
+    let mut rng = Rng::new(seed);
+
+    for _ in 0..programs {
+        let prog = match config.sugar {
+            true  => convert_sugar(random_sugar_program(config, &mut rng)),
+            false => random_program(config, &mut rng),
+        };
+
+        let simulators = Simulators::new(&prog);
+
+        for _ in 0..words_per_program {
+            let length = rng.below(max_word_len + 1);
+            let word = random_words(&config.alphabet, length, 1, rng.next_u64()).remove(0);
+
+            if disagree(&simulators.verdicts(&word)) {
+                return Some(shrink_disagreement(prog, word));
+            }
+        }
+    }
+
+    None
+}
+
+// Shrink a program and word that the simulators disagree on to a minimal one
+pub fn shrink_disagreement(prog : Program, word : String) -> Disagreement {
+    let (program, word) = shrink(prog, word, |prog, word| disagree(&Simulators::new(prog).verdicts(word)));
+    let verdicts = Simulators::new(&program).verdicts(&word);
+
+    Disagreement { program, word, verdicts }
+}
@@ -0,0 +1,245 @@
+// Random well-formed programs, for testing the simulators against each other
+
+use crate::parser::ast::{self, Cond, Readable};
+use crate::parser::program::Program;
+use crate::parser::sugar::ast::{self as sugar_ast, Endmarker, Value};
+use crate::parser::sugar::program::Program as SugarProgram;
+use crate::words::families::Rng;
+
+// Knobs for the size and features of random programs
+#[derive(Debug, Clone)]
+pub struct GenConfig {
+    // The program's alphabet
+    pub alphabet : Vec<char>,
+
+    // How deeply blocks can be nested
+    pub max_depth : usize,
+
+    // The most statements in a single block
+    pub max_block_len : usize,
+
+    // The largest distance a single move goes
+    pub max_move : i32,
+
+    // The largest amount a single statement changes the counter by
+    pub max_incr : i32,
+
+    // Whether to use while loops
+    pub loops : bool,
+
+    // Whether to use branch and while-choose statements
+    pub nondeterminism : bool,
+
+    // Whether to use repeat, goto, assignments and parameters (only in sugared programs)
+    pub sugar : bool,
+
+    // Whether c-- is allowed on an empty counter, or None to pick at random
+    pub decr_zero : Option<bool>,
+}
+
+impl Default for GenConfig {
+    fn default() -> Self {
+        Self {
+            alphabet : vec!['a', 'b'],
+            max_depth : 3,
+            max_block_len : 4,
+            max_move : 1,
+            max_incr : 2,
+            loops : true,
+            nondeterminism : true,
+            sugar : true,
+            decr_zero : None,
+        }
+    }
+}
+
+// A random desugared program
+pub fn random_program(config : &GenConfig, rng : &mut Rng) -> Program {
+    let mut generator = Generator { config, rng };
+
+    let stmts = generator.core_block(config.max_depth);
+    let decr_zero = generator.decr_zero();
+
+    Program { stmts, alpha : config.alphabet.iter().copied().collect(), decr_zero }
+}
+
+// A random sugared program
+// Half of them take a parameter for each letter of the alphabet, if config.sugar is set
+pub fn random_sugar_program(config : &GenConfig, rng : &mut Rng) -> SugarProgram {
+    let mut generator = Generator { config, rng };
+
+    let pars : Vec<String> = match config.sugar && generator.chance(2) {
+        true  => (0..config.alphabet.len()).map(|i| format!("p{}", (b'a' + i as u8) as char)).collect(),
+        false => Vec::new(),
+    };
+
+    let stmts = generator.sugar_block(config.max_depth, &pars);
+    let decr_zero = generator.decr_zero();
+
+    SugarProgram::new(stmts, config.alphabet.clone(), pars, decr_zero)
+}
+
+struct Generator<'a> {
+    config : &'a GenConfig,
+    rng : &'a mut Rng,
+}
+
+impl Generator<'_> {
+    // True one time in n
+    fn chance(&mut self, n : usize) -> bool {
+        self.rng.below(n) == 0
+    }
+
+    // A random number in -max..=max other than 0
+    fn nonzero(&mut self, max : i32) -> i32 {
+        let magnitude = 1 + self.rng.below(max.max(1) as usize) as i32;
+
+        match self.chance(2) {
+            true  => magnitude,
+            false => -magnitude,
+        }
+    }
+
+    fn decr_zero(&mut self) -> bool {
+        match self.config.decr_zero {
+            Some(decr_zero) => decr_zero,
+            None => self.chance(2),
+        }
+    }
+
+    // How many statements to put in a block
+    fn block_len(&mut self) -> usize {
+        1 + self.rng.below(self.config.max_block_len.max(1))
+    }
+
+    // How many blocks to put in a branch statement
+    fn branch_count(&mut self) -> usize {
+        2 + self.rng.below(2)
+    }
+
+    fn core_block(&mut self, depth : usize) -> Vec<ast::Stmt> {
+        (0..self.block_len()).map(|_| self.core_stmt(depth)).collect()
+    }
+
+    fn core_stmt(&mut self, depth : usize) -> ast::Stmt {
+        // Statements with blocks in them are only picked while there's depth left
+        let kinds = if depth == 0 { 4 } else { 8 };
+
+        match self.rng.below(kinds) {
+            0 => match self.chance(2) {
+                true  => ast::Stmt::Accept(),
+                false => ast::Stmt::Reject(),
+            },
+            1 | 2 => ast::Stmt::Move(self.nonzero(self.config.max_move)),
+            3 => ast::Stmt::Incr(self.nonzero(self.config.max_incr)),
+
+            4 | 5 => ast::Stmt::If(self.cond(2), self.core_block(depth - 1), self.optional_block(depth - 1, Self::core_block)),
+
+            6 if self.config.loops => ast::Stmt::While(self.cond(2), self.core_block(depth - 1)),
+
+            7 if self.config.nondeterminism => match self.chance(2) {
+                true  => ast::Stmt::Branch((0..self.branch_count()).map(|_| self.core_block(depth - 1)).collect()),
+                false => ast::Stmt::WhileChoose(self.core_block(depth - 1)),
+            },
+
+            // Fall back to a simple statement if a feature's turned off
+            _ => ast::Stmt::Move(self.nonzero(self.config.max_move)),
+        }
+    }
+
+    fn sugar_block(&mut self, depth : usize, pars : &[String]) -> Vec<sugar_ast::Stmt> {
+        (0..self.block_len()).map(|_| self.sugar_stmt(depth, pars)).collect()
+    }
+
+    fn sugar_stmt(&mut self, depth : usize, pars : &[String]) -> sugar_ast::Stmt {
+        let kinds = if depth == 0 { 6 } else { 11 };
+
+        match self.rng.below(kinds) {
+            0 => match self.chance(2) {
+                true  => sugar_ast::Stmt::Accept(),
+                false => sugar_ast::Stmt::Reject(),
+            },
+            1 | 2 => sugar_ast::Stmt::Move(self.nonzero(self.config.max_move)),
+            3 => sugar_ast::Stmt::Incr(self.value(pars, true), None),
+
+            4 if self.config.sugar => sugar_ast::Stmt::Asgn(self.value(pars, false), None),
+            5 if self.config.sugar => match self.chance(2) {
+                true  => sugar_ast::Stmt::Goto(Endmarker::LEnd),
+                false => sugar_ast::Stmt::Goto(Endmarker::REnd),
+            },
+
+            6 | 7 => {
+                let if_block = self.sugar_block(depth - 1, pars);
+                let else_block = self.optional_block(depth - 1, |g, d| g.sugar_block(d, pars));
+                sugar_ast::Stmt::If(self.cond(2), if_block, else_block, None)
+            },
+
+            8 if self.config.loops => sugar_ast::Stmt::While(self.cond(2), self.sugar_block(depth - 1, pars), None),
+
+            9 if self.config.nondeterminism => match self.chance(2) {
+                true  => sugar_ast::Stmt::Branch((0..self.branch_count()).map(|_| self.sugar_block(depth - 1, pars)).collect()),
+                false => sugar_ast::Stmt::WhileChoose(self.sugar_block(depth - 1, pars)),
+            },
+
+            10 if self.config.sugar => sugar_ast::Stmt::Repeat(1 + self.rng.below(3) as i32, self.sugar_block(depth - 1, pars)),
+
+            _ => sugar_ast::Stmt::Move(self.nonzero(self.config.max_move)),
+        }
+    }
+
+    // An else block, which is empty half the time
+    fn optional_block<T>(&mut self, depth : usize, block : impl FnOnce(&mut Self, usize) -> Vec<T>) -> Vec<T> {
+        match self.chance(2) {
+            true  => block(self, depth),
+            false => Vec::new(),
+        }
+    }
+
+    // The amount to change (or set) the counter by
+    // Only increments can be negative, since the counter can't be assigned a negative value
+    fn value(&mut self, pars : &[String], incr : bool) -> Value {
+        if !pars.is_empty() && self.chance(3) {
+            let par = pars[self.rng.below(pars.len())].clone();
+
+            return match incr && self.chance(2) {
+                true  => Value::NegPar(par),
+                false => Value::Par(par),
+            };
+        }
+
+        match incr {
+            true  => Value::Lit(self.nonzero(self.config.max_incr)),
+            false => Value::Lit(self.rng.below(self.config.max_incr.max(0) as usize + 1) as i32),
+        }
+    }
+
+    // A random condition, with connectives nested at most depth deep
+    fn cond(&mut self, depth : usize) -> Cond {
+        let kinds = if depth == 0 { 4 } else { 7 };
+
+        match self.rng.below(kinds) {
+            0 => match self.rng.below(4) {
+                0 => Cond::T(),
+                1 => Cond::F(),
+                2 => Cond::CheckZero(),
+                _ => Cond::CheckNotZero(),
+            },
+            1 | 2 => Cond::Read(self.readable()),
+            3 => Cond::NotRead(self.readable()),
+            4 => Cond::And(Box::new(self.cond(depth - 1)), Box::new(self.cond(depth - 1))),
+            5 => Cond::Or(Box::new(self.cond(depth - 1)), Box::new(self.cond(depth - 1))),
+            _ => Cond::Not(Box::new(self.cond(depth - 1))),
+        }
+    }
+
+    // A letter of the alphabet or an endmarker
+    fn readable(&mut self) -> Readable {
+        let alphabet = &self.config.alphabet;
+
+        match self.rng.below(alphabet.len() + 2) {
+            0 => Readable::LEnd(),
+            1 => Readable::REnd(),
+            i => Readable::Char(alphabet[i - 2]),
+        }
+    }
+}
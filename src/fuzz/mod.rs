@@ -0,0 +1,5 @@
+pub mod generate;
+
+pub mod shrink;
+
+pub mod differential;
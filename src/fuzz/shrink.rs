@@ -0,0 +1,165 @@
+// Shrinking failing test cases down to a minimal program and word
+
+use crate::parser::ast::{Cond, Stmt};
+use crate::parser::program::Program;
+
+// Shrink a program and a word that fail some test, by repeatedly taking the first one step smaller
+// version of either that still fails, until none do
+pub fn shrink(mut prog : Program, mut word : String, mut fails : impl FnMut(&Program, &str) -> bool) -> (Program, String) {
+    let mut alphabet : Vec<char> = prog.alpha.iter().copied().collect();
+    alphabet.sort();
+
+    loop {
+        let smaller_progs = smaller_blocks(&prog.stmts).into_iter()
+            .map(|stmts| (Program { stmts, ..prog.clone() }, word.clone()));
+
+        let smaller_words = smaller_words(&word, &alphabet).into_iter()
+            .map(|smaller| (prog.clone(), smaller));
+
+        match smaller_progs.chain(smaller_words).find(|(p, w)| fails(p, w)) {
+            Some((p, w)) => { prog = p; word = w; },
+            None => return (prog, word),
+        }
+    }
+}
+
+// The versions of a word with one letter removed or replaced with the first letter of the alphabet
+pub fn smaller_words(word : &str, alphabet : &[char]) -> Vec<String> {
+    let chars : Vec<char> = word.chars().collect();
+    let mut smaller = Vec::new();
+
+    for i in 0..chars.len() {
+        let mut removed = chars.clone();
+        removed.remove(i);
+        smaller.push(removed.into_iter().collect());
+    }
+
+    if let Some(first) = alphabet.first() {
+        for i in 0..chars.len() {
+            if chars[i] != *first {
+                let mut replaced = chars.clone();
+                replaced[i] = *first;
+                smaller.push(replaced.into_iter().collect());
+            }
+        }
+    }
+
+    smaller
+}
+
+// The versions of a block that are one step smaller
+// Statements are removed first, since that shrinks the most
+pub fn smaller_blocks(block : &[Stmt]) -> Vec<Vec<Stmt>> {
+    let mut smaller = Vec::new();
+
+    for i in 0..block.len() {
+        let mut removed = block.to_vec();
+        removed.remove(i);
+        smaller.push(removed);
+    }
+
+    for i in 0..block.len() {
+        for replacement in smaller_stmts(&block[i]) {
+            let mut replaced = block[..i].to_vec();
+            replaced.extend(replacement);
+            replaced.extend_from_slice(&block[i + 1..]);
+            smaller.push(replaced);
+        }
+    }
+
+    smaller
+}
+
+// The statements that a statement can be replaced with to make it one step smaller
+fn smaller_stmts(stmt : &Stmt) -> Vec<Vec<Stmt>> {
+    // Rebuild a statement with each smaller version of one of its blocks
+    fn with_smaller_block(block : &[Stmt], rebuild : impl Fn(Vec<Stmt>) -> Stmt) -> impl Iterator<Item = Vec<Stmt>> {
+        smaller_blocks(block).into_iter().map(move |smaller| vec![rebuild(smaller)])
+    }
+
+    match stmt {
+        Stmt::Accept() | Stmt::Reject() | Stmt::BasicBlock(_, _) => Vec::new(),
+
+        // Moves and increments get closer to 1 or -1
+        Stmt::Move(i) if i.abs() > 1 => vec![vec![Stmt::Move(i - i.signum())]],
+        Stmt::Incr(j) if j.abs() > 1 => vec![vec![Stmt::Incr(j - j.signum())]],
+        Stmt::Move(_) | Stmt::Incr(_) => Vec::new(),
+
+        Stmt::If(cond, if_block, else_block) => {
+            let mut smaller = vec![if_block.clone(), else_block.clone()];
+
+            smaller.extend(smaller_conds(cond).into_iter().map(|c| vec![Stmt::If(c, if_block.clone(), else_block.clone())]));
+            smaller.extend(with_smaller_block(if_block, |b| Stmt::If(cond.clone(), b, else_block.clone())));
+            smaller.extend(with_smaller_block(else_block, |b| Stmt::If(cond.clone(), if_block.clone(), b)));
+
+            smaller
+        },
+
+        Stmt::While(cond, body) => {
+            let mut smaller = vec![body.clone()];
+
+            smaller.extend(smaller_conds(cond).into_iter().map(|c| vec![Stmt::While(c, body.clone())]));
+            smaller.extend(with_smaller_block(body, |b| Stmt::While(cond.clone(), b)));
+
+            smaller
+        },
+
+        Stmt::Branch(blocks) => {
+            let mut smaller = blocks.clone();
+
+            // Branch statements keep at least two blocks
+            if blocks.len() > 2 {
+                for i in 0..blocks.len() {
+                    let mut removed = blocks.clone();
+                    removed.remove(i);
+                    smaller.push(vec![Stmt::Branch(removed)]);
+                }
+            }
+
+            for i in 0..blocks.len() {
+                smaller.extend(with_smaller_block(&blocks[i], |b| {
+                    let mut replaced = blocks.clone();
+                    replaced[i] = b;
+                    Stmt::Branch(replaced)
+                }));
+            }
+
+            smaller
+        },
+
+        Stmt::WhileChoose(body) => {
+            let mut smaller = vec![body.clone()];
+            smaller.extend(with_smaller_block(body, Stmt::WhileChoose));
+            smaller
+        },
+    }
+}
+
+// The conditions that are one step smaller than a condition
+fn smaller_conds(cond : &Cond) -> Vec<Cond> {
+    match cond {
+        Cond::T() | Cond::F() => Vec::new(),
+
+        Cond::Read(_) | Cond::NotRead(_) | Cond::CheckZero() | Cond::CheckNotZero() => vec![Cond::T(), Cond::F()],
+
+        Cond::And(left, right) | Cond::Or(left, right) => {
+            let mut smaller = vec![(**left).clone(), (**right).clone()];
+
+            let rebuild = |l : Cond, r : Cond| match cond {
+                Cond::And(_, _) => Cond::And(Box::new(l), Box::new(r)),
+                _ => Cond::Or(Box::new(l), Box::new(r)),
+            };
+
+            smaller.extend(smaller_conds(left).into_iter().map(|l| rebuild(l, (**right).clone())));
+            smaller.extend(smaller_conds(right).into_iter().map(|r| rebuild((**left).clone(), r)));
+
+            smaller
+        },
+
+        Cond::Not(inner) => {
+            let mut smaller = vec![(**inner).clone()];
+            smaller.extend(smaller_conds(inner).into_iter().map(|c| Cond::Not(Box::new(c))));
+            smaller
+        },
+    }
+}
@@ -0,0 +1,65 @@
+// A big-step interpreter for desugared programs, contracted or not
+
+use crate::interpret::{Halt, Machine, Positions, outcome};
+use crate::parser::ast::Stmt;
+use crate::parser::program::Program;
+use crate::simulation::budget::{Budget, Outcome};
+
+// Check if a program accepts a word by running it on every position a run could be in at once,
+// which explores every nondeterministic choice. Gives up once the budget runs out
+pub fn interpret(prog : &Program, input : &str, budget : &Budget) -> Outcome {
+    let mut machine = Machine::new(input, prog.decr_zero, program_points(&prog.stmts), budget);
+    let start = machine.start();
+
+    outcome(run_block(&mut machine, &prog.stmts, start))
+}
+
+// Run a block of statements on a set of positions
+pub fn run_block(machine : &mut Machine, block : &[Stmt], from : Positions) -> Result<Positions, Halt> {
+    block.iter().try_fold(from, |positions, stmt| run_stmt(machine, stmt, positions))
+}
+
+fn run_stmt(machine : &mut Machine, stmt : &Stmt, from : Positions) -> Result<Positions, Halt> {
+    match stmt {
+        Stmt::Accept() => machine.accept(from),
+        Stmt::Reject() => Ok(Positions::new()),
+
+        Stmt::Move(move_by) => machine.move_by(from, *move_by),
+        Stmt::Incr(incr_by) => machine.incr_by(from, *incr_by),
+
+        // Basic blocks move and then change the counter, like the constructions do
+        Stmt::BasicBlock(move_by, incr_by) => {
+            let moved = machine.move_by(from, *move_by)?;
+            machine.incr_by(moved, *incr_by)
+        },
+
+        Stmt::If(cond, if_block, else_block) => {
+            let (satisfied, unsatisfied) = machine.split(from, cond)?;
+
+            let mut to = run_block(machine, if_block, satisfied)?;
+            to.extend(run_block(machine, else_block, unsatisfied)?);
+
+            Ok(to)
+        },
+
+        Stmt::While(cond, body) => machine.while_loop(from, Some(cond), |machine, positions| run_block(machine, body, positions)),
+        Stmt::WhileChoose(body) => machine.while_loop(from, None, |machine, positions| run_block(machine, body, positions)),
+
+        Stmt::Branch(blocks) => machine.branch(from, blocks, |machine, block, positions| run_block(machine, block, positions)),
+    }
+}
+
+// The number of program points in a block, with a point for every unit an increment changes the counter by
+// (see Machine::new)
+pub fn program_points(block : &[Stmt]) -> usize {
+    let blocks_points = |blocks : &[Vec<Stmt>]| blocks.iter().map(|block| program_points(block)).sum::<usize>();
+
+    1 + block.iter().map(|stmt| match stmt {
+        Stmt::Accept() | Stmt::Reject() | Stmt::Move(_) => 1,
+        Stmt::Incr(incr_by) | Stmt::BasicBlock(_, incr_by) => 1 + incr_by.unsigned_abs() as usize,
+
+        Stmt::If(_, if_block, else_block) => 1 + program_points(if_block) + program_points(else_block),
+        Stmt::While(_, body) | Stmt::WhileChoose(body) => 1 + program_points(body),
+        Stmt::Branch(blocks) => 1 + blocks_points(blocks),
+    }).sum::<usize>()
+}
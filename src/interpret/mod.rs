@@ -0,0 +1,192 @@
+// Reference interpreters that run programs straight off of their ASTs, without building an automaton.
+// Comparing them with each other and with the simulators shows whether a bug lives in convert_sugar,
+// in contract or in the constructions
+
+pub mod desugared;
+
+pub mod sugared;
+
+use hashbrown::HashSet;
+
+use crate::parser::ast::{Cond, Readable};
+use crate::simulation::budget::{Budget, Exceeded, Meter, Outcome};
+use crate::simulation::config::next_counter;
+
+// Where a run is between two statements: (read head index, counter value)
+pub type Position = (i32, i32);
+
+// The interpreters run a statement on every position a run could be in at once, and
+// get back every position a run could be in once the statement's finished
+pub type Positions = HashSet<Position>;
+
+// The reasons an interpreter stops before it's run the whole program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Halt {
+    // Some run reached an accept statement
+    Accepted,
+
+    // The budget ran out
+    OutOfBudget(Exceeded),
+}
+
+impl From<Exceeded> for Halt {
+    fn from(exceeded : Exceeded) -> Self {
+        Halt::OutOfBudget(exceeded)
+    }
+}
+
+// Turn the result of running a whole program into an outcome. Runs that are left over at the end
+// of the program, or that hit a reject statement, get stuck, or loop forever, don't accept
+pub fn outcome(result : Result<Positions, Halt>) -> Outcome {
+    match result {
+        Ok(_) => Outcome::Rejected,
+        Err(Halt::Accepted) => Outcome::Accepted,
+        Err(Halt::OutOfBudget(exceeded)) => Outcome::BudgetExceeded(exceeded),
+    }
+}
+
+// The input and semantics the interpreters share
+pub struct Machine {
+    // The input, with its endmarkers
+    pub tape : Vec<Readable>,
+
+    // Whether or not c-- is allowed when c == 0
+    pub decr_zero : bool,
+
+    // Runs whose counter goes above this are dropped (see Machine::new)
+    counter_cutoff : i64,
+
+    // Counts a step for every position a statement runs on
+    meter : Meter,
+}
+
+impl Machine {
+    // Set up a machine to run a program with the given number of program points on an input
+    //
+    // The counter cutoff makes the interpreters terminate on every program, by the same argument as
+    // naive_nondeterm::counter_cutoff. Take a small-step version of the program where the counter
+    // only changes by 1 at a time, e.g. the desugared program with its increments split up, and treat
+    // each (program point, read head index) pair as a state of a one-counter automaton with k states.
+    // A shortest accepting run never has its counter go above k^2 + 1, so dropping runs that do
+    // doesn't change whether a word is accepted. Since the small-step program passes through the same
+    // positions between statements, neither does dropping them here
+    pub fn new(input : &str, decr_zero : bool, program_points : usize, budget : &Budget) -> Self {
+        let tape = Readable::from_input_str(input);
+
+        let k = program_points as i64 * tape.len() as i64;
+        let counter_cutoff = k.saturating_mul(k).saturating_add(1);
+
+        Self { tape, decr_zero, counter_cutoff, meter : Meter::new(budget) }
+    }
+
+    // The position every run starts at, on the left endmarker with an empty counter
+    pub fn start(&self) -> Positions {
+        [(0, 0)].into_iter().collect()
+    }
+
+    // The index of the right endmarker
+    pub fn rend(&self) -> i32 {
+        self.tape.len() as i32 - 1
+    }
+
+    // Take every position to a new one, or drop it if f gives None or the counter passes the cutoff
+    pub fn map(&mut self, from : Positions, f : impl Fn(Position) -> Option<Position>) -> Result<Positions, Halt> {
+        let mut to = Positions::new();
+
+        for position in from {
+            self.meter.step()?;
+
+            if let Some(next) = f(position) {
+                if next.1 as i64 <= self.counter_cutoff {
+                    to.insert(next);
+                }
+            }
+        }
+
+        Ok(to)
+    }
+
+    // move(move_by), which stops at the endmarkers
+    pub fn move_by(&mut self, from : Positions, move_by : i32) -> Result<Positions, Halt> {
+        let rend = self.rend();
+        self.map(from, |(read, counter)| Some(((read + move_by).max(0).min(rend), counter)))
+    }
+
+    // c += incr_by
+    pub fn incr_by(&mut self, from : Positions, incr_by : i32) -> Result<Positions, Halt> {
+        let decr_zero = self.decr_zero;
+        self.map(from, |(read, counter)| Some((read, next_counter(counter, incr_by, decr_zero)?)))
+    }
+
+    // accept, which accepts if any run gets to it
+    pub fn accept(&mut self, from : Positions) -> Result<Positions, Halt> {
+        match from.is_empty() {
+            true  => Ok(from),
+            false => Err(Halt::Accepted),
+        }
+    }
+
+    // Split positions into the ones that satisfy a condition and the ones that don't
+    pub fn split(&mut self, from : Positions, cond : &Cond) -> Result<(Positions, Positions), Halt> {
+        let mut satisfied = Positions::new();
+        let mut unsatisfied = Positions::new();
+
+        for (read, counter) in from {
+            self.meter.step()?;
+
+            match cond.check(self.tape[read as usize], counter) {
+                true  => satisfied.insert((read, counter)),
+                false => unsatisfied.insert((read, counter)),
+            };
+        }
+
+        Ok((satisfied, unsatisfied))
+    }
+
+    // A while loop with a condition, or a while-choose loop if cond is None, whose body is run by body
+    // Positions that have already been at the top of the loop aren't run again, so runs that loop forever
+    // just stop being tracked
+    pub fn while_loop(
+        &mut self,
+        from : Positions,
+        cond : Option<&Cond>,
+        mut body : impl FnMut(&mut Self, Positions) -> Result<Positions, Halt>,
+    ) -> Result<Positions, Halt> {
+        let mut seen = from.clone();
+        let mut frontier = from;
+        let mut exited = Positions::new();
+
+        while !frontier.is_empty() {
+            let (enter, exit) = match cond {
+                Some(cond) => self.split(frontier, cond)?,
+
+                // A while-choose loop can be left or run again from anywhere
+                None => (frontier.clone(), frontier),
+            };
+
+            exited.extend(exit);
+
+            frontier = body(self, enter)?.into_iter()
+                .filter(|position| seen.insert(*position))
+                .collect();
+        }
+
+        Ok(exited)
+    }
+
+    // A branch statement, with its blocks run by run_block
+    pub fn branch<B>(
+        &mut self,
+        from : Positions,
+        blocks : &[B],
+        mut run_block : impl FnMut(&mut Self, &B, Positions) -> Result<Positions, Halt>,
+    ) -> Result<Positions, Halt> {
+        let mut to = Positions::new();
+
+        for block in blocks {
+            to.extend(run_block(self, block, from.clone())?);
+        }
+
+        Ok(to)
+    }
+}
@@ -0,0 +1,156 @@
+// A big-step interpreter for programs with syntactic sugar, which runs the sugar directly instead of desugaring it
+
+use crate::interpret::{Halt, Machine, Positions, outcome};
+use crate::parser::sugar::ast::{Endmarker, Stmt, Value};
+use crate::parser::sugar::program::Program;
+use crate::simulation::budget::{Budget, Outcome};
+use crate::simulation::config::next_counter;
+
+// How many program points a statement that reads a parameter takes up once it's desugared
+// (a few loops that walk over the input, plus emptying the counter for assignments)
+const PARAMETER_POINTS : usize = 12;
+
+// Check if a program accepts a word by running it on every position a run could be in at once,
+// which explores every nondeterministic choice. Gives up once the budget runs out
+pub fn interpret(prog : &Program, input : &str, budget : &Budget) -> Outcome {
+    let mut machine = Machine::new(input, prog.decr_zero, program_points(&prog.stmts), budget);
+    let mut start = machine.start();
+
+    // Programs with parameters only take words with their letters in the order the parameters are declared in
+    if !prog.pars.is_empty() && !in_parameter_order(prog, input) {
+        start.clear();
+    }
+
+    let mut interpreter = Interpreter { prog, input, machine : &mut machine };
+    outcome(interpreter.run_block(&prog.stmts, start))
+}
+
+// Check that the letters of a word come in the order of the parameters they're counted by, e.g. x*y*z* for
+// twoc (int X, int Y, int Z). The parser collects parameters last to first, so pars holds them in reverse
+fn in_parameter_order(prog : &Program, input : &str) -> bool {
+    let order : Vec<char> = prog.pars.iter().rev().map(|par| prog.parmap[par]).collect();
+    let mut i = 0;
+
+    for c in input.chars() {
+        while i < order.len() && order[i] != c {
+            i += 1;
+        }
+
+        if i == order.len() {
+            return false;
+        }
+    }
+
+    true
+}
+
+struct Interpreter<'a> {
+    prog : &'a Program,
+    input : &'a str,
+    machine : &'a mut Machine,
+}
+
+impl Interpreter<'_> {
+    // The number of times a parameter's letter appears in the input
+    fn par_value(&self, par : &str) -> i32 {
+        let c = self.prog.parmap.get(par).unwrap_or_else(|| panic!("Parameter {:?} Undeclared!", par));
+        self.input.chars().filter(|d| d == c).count() as i32
+    }
+
+    fn run_block(&mut self, block : &[Stmt], from : Positions) -> Result<Positions, Halt> {
+        block.iter().try_fold(from, |positions, stmt| self.run_stmt(stmt, positions))
+    }
+
+    fn run_stmt(&mut self, stmt : &Stmt, from : Positions) -> Result<Positions, Halt> {
+        let decr_zero = self.machine.decr_zero;
+
+        match stmt {
+            Stmt::Accept() => self.machine.accept(from),
+            Stmt::Reject() => Ok(Positions::new()),
+
+            Stmt::Move(move_by) => self.machine.move_by(from, *move_by),
+            Stmt::Incr(Value::Lit(incr_by), _) => self.machine.incr_by(from, *incr_by),
+
+            // Reading a parameter walks over the input, which leaves the read head on the left endmarker
+            Stmt::Incr(Value::Par(par), _) => {
+                let incr_by = self.par_value(par);
+                self.machine.map(from, |(_, counter)| Some((0, next_counter(counter, incr_by, decr_zero)?)))
+            },
+
+            Stmt::Incr(Value::NegPar(par), _) => {
+                let incr_by = -self.par_value(par);
+                self.machine.map(from, |(_, counter)| Some((0, next_counter(counter, incr_by, decr_zero)?)))
+            },
+
+            Stmt::Asgn(Value::Lit(value), _) => {
+                if *value < 0 {
+                    panic!("{}", &format!("Counter can't contain negative value {:?}!", value));
+                }
+
+                self.machine.map(from, |(read, _)| Some((read, *value)))
+            },
+
+            Stmt::Asgn(Value::Par(par), _) => {
+                let value = self.par_value(par);
+                self.machine.map(from, |_| Some((0, value)))
+            },
+
+            Stmt::Asgn(Value::NegPar(par), _) => panic!("{}", &format!("Counter can't contain negative value -{:?}!", par)),
+
+            Stmt::Goto(Endmarker::LEnd) => self.machine.map(from, |(_, counter)| Some((0, counter))),
+            Stmt::Goto(Endmarker::REnd) => {
+                let rend = self.machine.rend();
+                self.machine.map(from, |(_, counter)| Some((rend, counter)))
+            },
+
+            Stmt::If(cond, if_block, else_block, _) => {
+                let (satisfied, unsatisfied) = self.machine.split(from, cond)?;
+
+                let mut to = self.run_block(if_block, satisfied)?;
+                to.extend(self.run_block(else_block, unsatisfied)?);
+
+                Ok(to)
+            },
+
+            Stmt::While(cond, body, _) => {
+                let (prog, input) = (self.prog, self.input);
+                self.machine.while_loop(from, Some(cond), |machine, positions| Interpreter { prog, input, machine }.run_block(body, positions))
+            },
+
+            Stmt::WhileChoose(body) => {
+                let (prog, input) = (self.prog, self.input);
+                self.machine.while_loop(from, None, |machine, positions| Interpreter { prog, input, machine }.run_block(body, positions))
+            },
+
+            Stmt::Branch(blocks) => {
+                let (prog, input) = (self.prog, self.input);
+                self.machine.branch(from, blocks, |machine, block, positions| Interpreter { prog, input, machine }.run_block(block, positions))
+            },
+
+            Stmt::Repeat(times, block) => (0..*times).try_fold(from, |positions, _| self.run_block(block, positions)),
+
+            // convert_sugar doesn't desugar countertests yet and just drops them, so they do nothing here too
+            Stmt::Countertest(_, _) => Ok(from),
+        }
+    }
+}
+
+// The number of program points in a block once it's desugared, with a point for every unit an increment
+// or assignment changes the counter by (see Machine::new)
+fn program_points(block : &[Stmt]) -> usize {
+    let blocks_points = |blocks : &[Vec<Stmt>]| blocks.iter().map(|block| program_points(block)).sum::<usize>();
+
+    1 + block.iter().map(|stmt| match stmt {
+        Stmt::Accept() | Stmt::Reject() | Stmt::Move(_) | Stmt::Countertest(_, _) => 1,
+        Stmt::Goto(_) => 2,
+
+        Stmt::Incr(Value::Lit(incr_by), _) => 1 + incr_by.unsigned_abs() as usize,
+        Stmt::Asgn(Value::Lit(value), _) => 2 + value.unsigned_abs() as usize,
+        Stmt::Incr(..) | Stmt::Asgn(..) => PARAMETER_POINTS,
+
+        Stmt::If(_, if_block, else_block, _) => 1 + program_points(if_block) + program_points(else_block),
+        Stmt::While(_, body, _) | Stmt::WhileChoose(body) => 1 + program_points(body),
+        Stmt::Branch(blocks) => 1 + blocks_points(blocks),
+        Stmt::Repeat(times, body) => times.max(&0).unsigned_abs() as usize * program_points(body),
+    }).sum::<usize>()
+}
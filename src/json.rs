@@ -0,0 +1,603 @@
+// A small JSON value type with a parser, and JSON forms of the pipeline's syntax trees, automata and diagnostics,
+// so that the CLI's output can be read by other programs (and the language server can talk to editors)
+
+use std::fmt;
+
+use crate::automaton::autom::Autom;
+use crate::compiler::{Diagnostic, Diagnostics, Severity};
+use crate::parser::ast::{Cond, Readable, Stmt};
+use crate::parser::program::Program;
+use crate::parser::sugar::ast::{CounterTestCond, Endmarker, Stmt as SugarStmt, Value};
+use crate::parser::sugar::program::Program as SugarProgram;
+use crate::simulation::budget::Outcome;
+use crate::simulation::explain::{Explanation, Step};
+use crate::simulation::stats::RunStats;
+
+// A JSON value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Int(i64),
+
+    // Printed as null if it isn't finite, since JSON has no NaN or infinity
+    Float(f64),
+
+    Str(String),
+    Array(Vec<Json>),
+
+    // Keys are printed in the order they're given
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    // An object from (key, value) pairs
+    pub fn object(pairs : Vec<(&str, Json)>) -> Json {
+        Json::Object(pairs.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+    }
+
+    // Look up a key of an object
+    pub fn get(&self, key : &str) -> Option<&Json> {
+        match self {
+            Json::Object(pairs) => pairs.iter().find(|(k, _)| k == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    // Look up a path of keys through nested objects
+    pub fn at(&self, path : &[&str]) -> Option<&Json> {
+        path.iter().try_fold(self, |value, key| value.get(key))
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Json::Int(i) => Some(*i),
+            Json::Float(x) if x.fract() == 0.0 => Some(*x as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+// Parse a JSON value, which has to take up the whole of the text (apart from whitespace)
+pub fn parse(text : &str) -> Result<Json, String> {
+    let mut parser = Parser { chars : text.chars().collect(), pos : 0 };
+
+    let value = parser.value()?;
+    parser.skip_whitespace();
+
+    match parser.peek() {
+        None => Ok(value),
+        Some(c) => Err(format!("Unexpected {:?} after the JSON value at character {}", c, parser.pos)),
+    }
+}
+
+// A recursive descent parser over the characters of some JSON
+struct Parser {
+    chars : Vec<char>,
+    pos : usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Result<char, String> {
+        let c = self.peek().ok_or("Unexpected end of JSON")?;
+        self.pos += 1;
+        Ok(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, expected : char) -> Result<(), String> {
+        match self.next()? {
+            c if c == expected => Ok(()),
+            c => Err(format!("Expected {:?} but found {:?} at character {}", expected, c, self.pos - 1)),
+        }
+    }
+
+    // The rest of a keyword whose first letter has been seen
+    fn keyword(&mut self, word : &str, value : Json) -> Result<Json, String> {
+        for expected in word.chars() {
+            self.expect(expected)?;
+        }
+
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+
+        match self.peek().ok_or("Unexpected end of JSON")? {
+            'n' => self.keyword("null", Json::Null),
+            't' => self.keyword("true", Json::Bool(true)),
+            'f' => self.keyword("false", Json::Bool(false)),
+            '"' => Ok(Json::Str(self.string()?)),
+
+            '[' => {
+                self.pos += 1;
+                let mut values = Vec::new();
+
+                self.skip_whitespace();
+                if self.peek() == Some(']') {
+                    self.pos += 1;
+                    return Ok(Json::Array(values));
+                }
+
+                loop {
+                    values.push(self.value()?);
+                    self.skip_whitespace();
+
+                    match self.next()? {
+                        ',' => continue,
+                        ']' => return Ok(Json::Array(values)),
+                        c => return Err(format!("Expected ',' or ']' but found {:?} at character {}", c, self.pos - 1)),
+                    }
+                }
+            },
+
+            '{' => {
+                self.pos += 1;
+                let mut pairs = Vec::new();
+
+                self.skip_whitespace();
+                if self.peek() == Some('}') {
+                    self.pos += 1;
+                    return Ok(Json::Object(pairs));
+                }
+
+                loop {
+                    self.skip_whitespace();
+                    let key = self.string()?;
+                    self.skip_whitespace();
+                    self.expect(':')?;
+                    pairs.push((key, self.value()?));
+                    self.skip_whitespace();
+
+                    match self.next()? {
+                        ',' => continue,
+                        '}' => return Ok(Json::Object(pairs)),
+                        c => return Err(format!("Expected ',' or '}}' but found {:?} at character {}", c, self.pos - 1)),
+                    }
+                }
+            },
+
+            c if c == '-' || c.is_ascii_digit() => self.number(),
+
+            c => Err(format!("Unexpected {:?} at character {}", c, self.pos)),
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, String> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit() || "+-.eE".contains(c)) {
+            self.pos += 1;
+        }
+
+        let text : String = self.chars[start..self.pos].iter().collect();
+
+        match text.parse::<i64>() {
+            Ok(i) => Ok(Json::Int(i)),
+            Err(_) => text.parse::<f64>().map(Json::Float).map_err(|_| format!("Invalid number {:?}", text)),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut out = String::new();
+
+        loop {
+            match self.next()? {
+                '"' => return Ok(out),
+
+                '\\' => match self.next()? {
+                    'n' => out.push('\n'),
+                    'r' => out.push('\r'),
+                    't' => out.push('\t'),
+                    'b' => out.push('\u{8}'),
+                    'f' => out.push('\u{c}'),
+                    'u' => {
+                        let mut code = self.hex()?;
+
+                        // Characters outside the basic multilingual plane are written as surrogate pairs
+                        // A high surrogate that isn't followed by a low one stands alone, and whatever
+                        // follows it is decoded on its own
+                        if (0xd800..0xdc00).contains(&code) && self.chars[self.pos..].starts_with(&['\\', 'u']) {
+                            let after_high = self.pos;
+                            self.pos += 2;
+
+                            let low = self.hex()?;
+                            match (0xdc00..0xe000).contains(&low) {
+                                true  => code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00),
+                                false => self.pos = after_high,
+                            }
+                        }
+
+                        out.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                    },
+                    c => out.push(c),
+                },
+
+                c => out.push(c),
+            }
+        }
+    }
+
+    // The four hex digits of a \u escape
+    fn hex(&mut self) -> Result<u32, String> {
+        let mut code = 0;
+
+        for _ in 0..4 {
+            let c = self.next()?;
+            code = code * 16 + c.to_digit(16).ok_or(format!("Invalid hex digit {:?} in a \\u escape", c))?;
+        }
+
+        Ok(code)
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Int(i) => write!(f, "{}", i),
+            Json::Float(x) if x.is_finite() => write!(f, "{}", x),
+            Json::Float(_) => write!(f, "null"),
+            Json::Str(s) => write_str(f, s),
+
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 { write!(f, ",")?; }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            },
+
+            Json::Object(pairs) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in pairs.iter().enumerate() {
+                    if i > 0 { write!(f, ",")?; }
+                    write_str(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            },
+        }
+    }
+}
+
+// Write a string with JSON's escapes
+fn write_str(f : &mut fmt::Formatter, s : &str) -> fmt::Result {
+    write!(f, "\"")?;
+
+    for c in s.chars() {
+        match c {
+            '"'  => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+
+    write!(f, "\"")
+}
+
+impl From<bool> for Json {
+    fn from(b : bool) -> Self { Json::Bool(b) }
+}
+
+impl From<u16> for Json {
+    fn from(i : u16) -> Self { Json::Int(i as i64) }
+}
+
+impl From<i32> for Json {
+    fn from(i : i32) -> Self { Json::Int(i as i64) }
+}
+
+impl From<i64> for Json {
+    fn from(i : i64) -> Self { Json::Int(i) }
+}
+
+impl From<u64> for Json {
+    fn from(i : u64) -> Self { Json::Int(i as i64) }
+}
+
+impl From<usize> for Json {
+    fn from(i : usize) -> Self { Json::Int(i as i64) }
+}
+
+impl From<f64> for Json {
+    fn from(x : f64) -> Self { Json::Float(x) }
+}
+
+impl From<&str> for Json {
+    fn from(s : &str) -> Self { Json::Str(s.to_string()) }
+}
+
+impl From<String> for Json {
+    fn from(s : String) -> Self { Json::Str(s) }
+}
+
+impl<T : Into<Json>> From<Option<T>> for Json {
+    fn from(option : Option<T>) -> Self {
+        match option {
+            Some(value) => value.into(),
+            None => Json::Null,
+        }
+    }
+}
+
+impl<T : Into<Json>> From<Vec<T>> for Json {
+    fn from(values : Vec<T>) -> Self { Json::Array(values.into_iter().map(|value| value.into()).collect()) }
+}
+
+// Things with a JSON form
+pub trait ToJson {
+    fn to_json(&self) -> Json;
+}
+
+fn block_json<T : ToJson>(block : &[T]) -> Json {
+    Json::Array(block.iter().map(|stmt| stmt.to_json()).collect())
+}
+
+// The alphabet in a fixed order
+fn alphabet_json(alpha : &std::collections::HashSet<char>) -> Json {
+    let mut alphabet : Vec<char> = alpha.iter().copied().collect();
+    alphabet.sort();
+    alphabet.into_iter().map(|c| c.to_string()).collect::<Vec<_>>().into()
+}
+
+impl ToJson for Readable {
+    fn to_json(&self) -> Json {
+        match self {
+            Readable::LEnd() => "lend".into(),
+            Readable::REnd() => "rend".into(),
+            Readable::Char(c) => c.to_string().into(),
+        }
+    }
+}
+
+impl ToJson for Cond {
+    fn to_json(&self) -> Json {
+        match self {
+            Cond::T() => Json::object(vec![("cond", "true".into())]),
+            Cond::F() => Json::object(vec![("cond", "false".into())]),
+            Cond::Read(read) => Json::object(vec![("cond", "read".into()), ("symbol", read.to_json())]),
+            Cond::NotRead(read) => Json::object(vec![("cond", "not_read".into()), ("symbol", read.to_json())]),
+            Cond::CheckZero() => Json::object(vec![("cond", "zero".into())]),
+            Cond::CheckNotZero() => Json::object(vec![("cond", "not_zero".into())]),
+            Cond::And(x, y) => Json::object(vec![("cond", "and".into()), ("left", x.to_json()), ("right", y.to_json())]),
+            Cond::Or(x, y) => Json::object(vec![("cond", "or".into()), ("left", x.to_json()), ("right", y.to_json())]),
+            Cond::Not(x) => Json::object(vec![("cond", "not".into()), ("inner", x.to_json())]),
+        }
+    }
+}
+
+impl ToJson for Stmt {
+    fn to_json(&self) -> Json {
+        match self {
+            Stmt::Accept() => Json::object(vec![("stmt", "accept".into())]),
+            Stmt::Reject() => Json::object(vec![("stmt", "reject".into())]),
+            Stmt::Move(move_by) => Json::object(vec![("stmt", "move".into()), ("by", (*move_by).into())]),
+            Stmt::Incr(incr_by) => Json::object(vec![("stmt", "incr".into()), ("by", (*incr_by).into())]),
+
+            Stmt::BasicBlock(move_by, incr_by) => Json::object(vec![
+                ("stmt", "basic_block".into()), ("move", (*move_by).into()), ("incr", (*incr_by).into()),
+            ]),
+
+            Stmt::If(cond, if_block, else_block) => Json::object(vec![
+                ("stmt", "if".into()), ("cond", cond.to_json()), ("then", block_json(if_block)), ("else", block_json(else_block)),
+            ]),
+
+            Stmt::While(cond, body) => Json::object(vec![("stmt", "while".into()), ("cond", cond.to_json()), ("body", block_json(body))]),
+
+            Stmt::Branch(blocks) => Json::object(vec![
+                ("stmt", "branch".into()), ("blocks", Json::Array(blocks.iter().map(|block| block_json(block)).collect())),
+            ]),
+
+            Stmt::WhileChoose(body) => Json::object(vec![("stmt", "while_choose".into()), ("body", block_json(body))]),
+        }
+    }
+}
+
+impl ToJson for Program {
+    fn to_json(&self) -> Json {
+        Json::object(vec![
+            ("alphabet", alphabet_json(&self.alpha)),
+            ("decr_on_zero", self.decr_zero.into()),
+            ("stmts", block_json(&self.stmts)),
+        ])
+    }
+}
+
+impl ToJson for Value {
+    fn to_json(&self) -> Json {
+        match self {
+            Value::Lit(j) => Json::object(vec![("lit", (*j).into())]),
+            Value::Par(par) => Json::object(vec![("par", par.as_str().into())]),
+            Value::NegPar(par) => Json::object(vec![("neg_par", par.as_str().into())]),
+        }
+    }
+}
+
+impl ToJson for CounterTestCond {
+    fn to_json(&self) -> Json {
+        let (op, j) = match self {
+            CounterTestCond::Eq(j)  => ("==", j),
+            CounterTestCond::LEq(j) => ("<=", j),
+            CounterTestCond::GEq(j) => (">=", j),
+            CounterTestCond::Lt(j)  => ("<", j),
+            CounterTestCond::Gt(j)  => (">", j),
+        };
+
+        Json::object(vec![("op", op.into()), ("value", (*j).into())])
+    }
+}
+
+impl ToJson for SugarStmt {
+    fn to_json(&self) -> Json {
+        match self {
+            SugarStmt::Accept() => Json::object(vec![("stmt", "accept".into())]),
+            SugarStmt::Reject() => Json::object(vec![("stmt", "reject".into())]),
+            SugarStmt::Move(move_by) => Json::object(vec![("stmt", "move".into()), ("by", (*move_by).into())]),
+            SugarStmt::Incr(value, _) => Json::object(vec![("stmt", "incr".into()), ("value", value.to_json())]),
+            SugarStmt::Asgn(value, _) => Json::object(vec![("stmt", "assign".into()), ("value", value.to_json())]),
+
+            SugarStmt::If(cond, if_block, else_block, _) => Json::object(vec![
+                ("stmt", "if".into()), ("cond", cond.to_json()), ("then", block_json(if_block)), ("else", block_json(else_block)),
+            ]),
+
+            SugarStmt::While(cond, body, _) => Json::object(vec![("stmt", "while".into()), ("cond", cond.to_json()), ("body", block_json(body))]),
+
+            SugarStmt::Branch(blocks) => Json::object(vec![
+                ("stmt", "branch".into()), ("blocks", Json::Array(blocks.iter().map(|block| block_json(block)).collect())),
+            ]),
+
+            SugarStmt::WhileChoose(body) => Json::object(vec![("stmt", "while_choose".into()), ("body", block_json(body))]),
+
+            SugarStmt::Repeat(times, body) => Json::object(vec![("stmt", "repeat".into()), ("times", (*times).into()), ("body", block_json(body))]),
+
+            SugarStmt::Goto(endmarker) => Json::object(vec![
+                ("stmt", "goto".into()),
+                ("to", match endmarker { Endmarker::LEnd => "lend", Endmarker::REnd => "rend" }.into()),
+            ]),
+
+            SugarStmt::Countertest(test, body) => Json::object(vec![("stmt", "counter_test".into()), ("test", test.to_json()), ("body", block_json(body))]),
+        }
+    }
+}
+
+impl ToJson for SugarProgram {
+    fn to_json(&self) -> Json {
+        Json::object(vec![
+            ("alphabet", alphabet_json(&self.alpha)),
+            ("parameters", self.pars.iter().rev().map(|par| par.as_str()).collect::<Vec<_>>().into()),
+            ("decr_on_zero", self.decr_zero.into()),
+            ("stmts", block_json(&self.stmts)),
+        ])
+    }
+}
+
+impl ToJson for Autom {
+    fn to_json(&self) -> Json {
+        let states = 0..self.state_total;
+
+        let halting = |accept : bool| states.clone()
+            .filter(|state| self.check_if_halting(*state) == Some(accept))
+            .collect::<Vec<_>>()
+            .into();
+
+        let transitions = states.clone()
+            .flat_map(|state| self.get_transitions(state).into_iter().map(move |transition| (state, transition)))
+            .map(|(state, transition)| Json::object(vec![
+                ("from", state.into()),
+                ("to", transition.goto.into()),
+                ("move", transition.move_by.into()),
+                ("incr", transition.incr_by.into()),
+                ("cond", transition.condition.as_ref().map(|cond| cond.to_json()).unwrap_or(Json::Null)),
+            ]))
+            .collect();
+
+        Json::object(vec![
+            ("alphabet", alphabet_json(&self.alpha)),
+            ("decr_on_zero", self.decr_zero.into()),
+            ("states", self.state_total.into()),
+            ("start", 0i64.into()),
+            ("accepting", halting(true)),
+            ("rejecting", halting(false)),
+            ("transitions", Json::Array(transitions)),
+        ])
+    }
+}
+
+impl ToJson for Outcome {
+    fn to_json(&self) -> Json {
+        match self {
+            Outcome::Accepted => "accept".into(),
+            Outcome::Rejected => "reject".into(),
+            Outcome::BudgetExceeded(_) => "unknown".into(),
+            Outcome::Failed(_) => "error".into(),
+        }
+    }
+}
+
+impl ToJson for RunStats {
+    fn to_json(&self) -> Json {
+        Json::object(vec![
+            ("steps", self.steps.into()),
+            ("max_counter", self.max_counter.into()),
+            ("head_reversals", self.head_reversals.into()),
+            ("distinct_configs", self.distinct_configs.into()),
+            ("table_entries", self.table_entries.into()),
+        ])
+    }
+}
+
+impl ToJson for Step {
+    fn to_json(&self) -> Json {
+        Json::object(vec![
+            ("state", self.config.state.into()),
+            ("index", self.config.read.into()),
+            ("counter", self.config.counter.into()),
+            ("read", self.read.to_json()),
+        ])
+    }
+}
+
+impl ToJson for Explanation {
+    fn to_json(&self) -> Json {
+        Json::object(vec![
+            ("cause", self.cause.to_string().into()),
+            ("source", self.source.clone().into()),
+            ("last", self.last.to_json()),
+            ("steps", block_json(&self.steps)),
+        ])
+    }
+}
+
+impl Diagnostic {
+    // The diagnostic with its line and column in the source
+    pub fn to_json(&self, source : &str) -> Json {
+        let (line, col) = match self.line_col(source) {
+            Some((line, col)) => (Json::from(line), Json::from(col)),
+            None => (Json::Null, Json::Null),
+        };
+
+        Json::object(vec![
+            ("severity", match self.severity { Severity::Error => "error", Severity::Warning => "warning" }.into()),
+            ("message", self.message.as_str().into()),
+            ("line", line),
+            ("col", col),
+        ])
+    }
+}
+
+impl Diagnostics {
+    pub fn to_json(&self, source : &str) -> Json {
+        Json::Array(self.0.iter().map(|diagnostic| diagnostic.to_json(source)).collect())
+    }
+}
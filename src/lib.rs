@@ -1,6 +1,17 @@
 pub mod parser;
+pub mod compiler;
+pub mod codegen;
+pub mod ffi;
+pub mod json;
+pub mod lsp;
 pub mod automaton;
 pub mod simulation;
+pub mod words;
+pub mod bench;
+pub mod fuzz;
+pub mod interpret;
 pub mod proofs;
 
-extern crate hashbrown;
\ No newline at end of file
+extern crate hashbrown;
+
+pub use compiler::{Compiled, Diagnostic, Diagnostics, compile};
\ No newline at end of file
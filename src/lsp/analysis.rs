@@ -0,0 +1,156 @@
+// The editor features, worked out from a program's source code with byte offsets for positions
+
+use crate::compiler::{compile, parse, Diagnostic};
+use crate::lsp::tokens::{Kind, Token, tokenize, token_at};
+use crate::parser::sugar::ast::{Stmt, Value};
+use crate::parser::sugar::convert_sugar::convert_sugar;
+
+// Information about the code under the cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hover {
+    // The code it's about
+    pub span : (usize, usize),
+
+    // Markdown to show
+    pub contents : String,
+}
+
+// A line of information shown above some code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lens {
+    pub span : (usize, usize),
+    pub title : String,
+}
+
+// The indices of the twoc keyword and of the brace opening the program's body
+fn body(tokens : &[Token]) -> Option<(usize, usize)> {
+    let twoc = tokens.iter().position(|token| token.kind == Kind::Word && token.is("twoc"))?;
+    let open = twoc + tokens[twoc..].iter().position(|token| token.is("{"))?;
+
+    Some((twoc, open))
+}
+
+// The span of the twoc keyword, or the start of the file if there isn't one
+fn twoc_span(tokens : &[Token]) -> (usize, usize) {
+    body(tokens).map(|(twoc, _)| (tokens[twoc].start, tokens[twoc].end)).unwrap_or((0, 0))
+}
+
+// Everything wrong with a program
+// Diagnostics the compiler doesn't know the position of are put on the twoc keyword
+pub fn diagnostics(source : &str) -> Vec<Diagnostic> {
+    let diagnostics = match compile(source) {
+        Ok(compiled) => compiled.warnings,
+        Err(diagnostics) => diagnostics.0,
+    };
+
+    let span = twoc_span(&tokenize(source));
+
+    diagnostics.into_iter()
+        .map(|diagnostic| Diagnostic { span : diagnostic.span.or(Some(span)), ..diagnostic })
+        .collect()
+}
+
+// The index of the brace closing the one at open
+fn matching_brace(tokens : &[Token], open : usize) -> Option<usize> {
+    let mut depth = 0;
+
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        if token.is("{") {
+            depth += 1;
+        } else if token.is("}") {
+            depth -= 1;
+
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+
+    None
+}
+
+// The first and last tokens of the simple statement or repeat block that the token at i is part of
+fn statement_around(tokens : &[Token], i : usize, body_open : usize) -> Option<(usize, usize)> {
+    let is_boundary = |token : &Token| token.is(";") || token.is("{") || token.is("}") || token.is_comment();
+
+    let start = (0..=i).rev().find(|&j| j == 0 || is_boundary(&tokens[j - 1]))?;
+    if start <= body_open || is_boundary(&tokens[start]) {
+        return None;
+    }
+
+    if tokens[start].is("repeat") {
+        let open = start + tokens[start..].iter().position(|token| token.is("{"))?;
+        return Some((start, matching_brace(tokens, open)?));
+    }
+
+    let end = start + tokens[start..].iter().position(|token| token.is(";") || token.is("{") || token.is("}"))?;
+    match tokens[end].is(";") {
+        true  => Some((start, end)),
+        false => None,
+    }
+}
+
+// Whether a statement is syntactic sugar for something longer
+fn is_sugar(stmt : &Stmt) -> bool {
+    matches!(
+        stmt,
+        Stmt::Asgn(..) | Stmt::Incr(Value::Par(_), _) | Stmt::Incr(Value::NegPar(_), _) | Stmt::Repeat(..) | Stmt::Goto(_) | Stmt::Countertest(..)
+    )
+}
+
+// What the syntactic sugar under the cursor desugars to, e.g. what c = 2; expands into
+pub fn hover(source : &str, offset : usize) -> Option<Hover> {
+    let tokens = tokenize(source);
+    let (_, body_open) = body(&tokens)?;
+    let (start, end) = statement_around(&tokens, token_at(&tokens, offset)?, body_open)?;
+    let span = (tokens[start].start, tokens[end].end);
+
+    // Desugar the statement on its own, in a program with the same header so that parameters
+    // and the alphabet mean the same thing
+    let program = format!("{}\n{}\n}}", &source[..tokens[body_open].end], &source[span.0..span.1]);
+    let mut sugared = parse(&program).ok()?;
+
+    if sugared.stmts.len() != 1 || !is_sugar(&sugared.stmts[0]) {
+        return None;
+    }
+
+    // Leave out the check that the input is in the right form, which desugaring adds for programs with parameters
+    sugared.pars.clear();
+    let desugared : String = convert_sugar(sugared).stmts.iter().map(|stmt| stmt.print(0)).collect();
+
+    Some(Hover { span, contents : format!("`{}` desugars to\n```\n{}```", &source[span.0..span.1], desugared) })
+}
+
+// Where the parameter under the cursor is declared
+pub fn definition(source : &str, offset : usize) -> Option<(usize, usize)> {
+    let tokens = tokenize(source);
+    let (twoc, body_open) = body(&tokens)?;
+    let i = token_at(&tokens, offset)?;
+
+    // Parameters are only used as the values of increments and assignments
+    let is_use = i > body_open && ["=", "+=", "-="].contains(&tokens[i - 1].text);
+    let is_declaration = i > twoc && i < body_open && tokens[i - 1].is("int");
+
+    if tokens[i].kind != Kind::Word || !(is_use || is_declaration) {
+        return None;
+    }
+
+    tokens[twoc..body_open].windows(2)
+        .find(|pair| pair[0].is("int") && pair[1].text == tokens[i].text)
+        .map(|pair| (pair[1].start, pair[1].end))
+}
+
+// A lens on the twoc keyword saying whether the program is deterministic, along with its automaton's size
+// Returns None if the program doesn't compile
+pub fn code_lens(source : &str) -> Option<Lens> {
+    let compiled = compile(source).ok()?;
+
+    let title = format!(
+        "{} ({}, {} states)",
+        if compiled.deterministic {"Deterministic"} else {"Nondeterministic"},
+        if compiled.one_way {"one-way"} else {"two-way"},
+        compiled.autom.state_total,
+    );
+
+    Some(Lens { span : twoc_span(&tokenize(source)), title })
+}
@@ -0,0 +1,165 @@
+// Formatting twoc source: a statement per line, blocks indented by four spaces and operators spaced out,
+// keeping comments and single blank lines where they were
+
+use crate::compiler::parse;
+use crate::json::ToJson;
+use crate::lsp::tokens::{Kind, Token, tokenize};
+use crate::parser::sugar::program::Program;
+
+const INDENT : &str = "    ";
+
+// Operators with a space on either side
+const BINARY_OPERATORS : [&str; 7] = ["=", "==", "!=", "&&", "||", "+=", "-="];
+
+// Keywords followed by a space before their parentheses (unlike move(1) and goto(rend))
+const SPACED_KEYWORDS : [&str; 4] = ["if", "while", "repeat", "twoc"];
+
+// Format a program
+// Returns None if the program doesn't parse, or if formatting would somehow change what it parses to
+pub fn format(source : &str) -> Option<String> {
+    let before = parse(source).ok()?;
+    let formatted = Formatter::new(source).run();
+    let after = parse(&formatted).ok()?;
+
+    match same_program(&before, &after) {
+        true  => Some(formatted),
+        false => None,
+    }
+}
+
+// Whether two programs parsed to the same thing
+// Their JSON forms are compared, since those leave out where in the source each statement came from
+// and list the alphabet in a fixed order
+fn same_program(x : &Program, y : &Program) -> bool {
+    x.to_json() == y.to_json()
+}
+
+struct Formatter<'a> {
+    source : &'a str,
+    tokens : Vec<Token<'a>>,
+    out : String,
+    depth : usize,
+
+    // Whether the next token starts a line
+    line_start : bool,
+}
+
+impl<'a> Formatter<'a> {
+    fn new(source : &'a str) -> Self {
+        Self { source, tokens : tokenize(source), out : String::new(), depth : 0, line_start : true }
+    }
+
+    fn run(mut self) -> String {
+        for i in 0..self.tokens.len() {
+            self.token(i);
+        }
+
+        if !self.line_start {
+            self.out.push('\n');
+        }
+
+        self.out
+    }
+
+    // The number of line breaks in the source between a token and the one before it
+    fn breaks_before(&self, i : usize) -> usize {
+        let start = if i == 0 { 0 } else { self.tokens[i - 1].end };
+        self.source[start..self.tokens[i].start].matches('\n').count()
+    }
+
+    fn newline(&mut self) {
+        if !self.line_start {
+            self.out.push('\n');
+            self.line_start = true;
+        }
+    }
+
+    // Write a token, indenting it if it starts a line or spacing it from the token before
+    fn write(&mut self, i : usize, spaced : bool) {
+        let token = self.tokens[i];
+
+        if self.line_start {
+            // Keep a single blank line where there were blank lines, except at the start or end of a block
+            let after_open = self.out.is_empty() || self.out.ends_with("{\n");
+            if self.breaks_before(i) > 1 && !after_open && !token.is("}") {
+                self.out.push('\n');
+            }
+
+            self.out.push_str(&INDENT.repeat(self.depth));
+        } else if spaced {
+            self.out.push(' ');
+        }
+
+        self.out.push_str(token.text.trim_end());
+        self.line_start = false;
+    }
+
+    fn token(&mut self, i : usize) {
+        let token = self.tokens[i];
+        let next = self.tokens[i + 1..].iter().find(|token| !token.is_comment()).copied();
+
+        match token.kind {
+            // Comments stay on their own line or at the end of the line they were on
+            Kind::LineComment | Kind::BlockComment => {
+                if i > 0 && self.breaks_before(i) > 0 {
+                    self.newline();
+                } else if i > 0 && self.line_start && token.kind == Kind::LineComment {
+                    // Put a trailing comment back on the line it was on
+                    self.out.pop();
+                    self.line_start = false;
+                }
+
+                self.write(i, true);
+
+                let own_line = self.tokens.get(i + 1).is_none_or(|_| self.breaks_before(i + 1) > 0);
+                if token.kind == Kind::LineComment || own_line {
+                    self.newline();
+                }
+            },
+
+            _ if token.is("{") => {
+                self.write(i, true);
+                self.depth += 1;
+                self.newline();
+            },
+
+            _ if token.is("}") => {
+                self.newline();
+                self.depth = self.depth.saturating_sub(1);
+                self.write(i, false);
+
+                // else and also blocks carry on from the closing brace
+                if !next.is_some_and(|next| next.is("else") || next.is("also")) {
+                    self.newline();
+                }
+            },
+
+            _ if token.is(";") => {
+                self.write(i, false);
+                self.newline();
+            },
+
+            _ => {
+                let spaced = i > 0 && space_between(&self.tokens[i - 1], &token);
+                self.write(i, spaced);
+            },
+        }
+    }
+}
+
+// Whether two tokens on the same line have a space between them
+fn space_between(prev : &Token, token : &Token) -> bool {
+    if prev.is_comment() {
+        return true;
+    }
+
+    if [")", ",", ";", "]", "++", "--"].contains(&token.text) || ["(", "[", "!"].contains(&prev.text) {
+        return false;
+    }
+
+    if token.is("(") {
+        return SPACED_KEYWORDS.contains(&prev.text) || BINARY_OPERATORS.contains(&prev.text);
+    }
+
+    true
+}
@@ -0,0 +1,6 @@
+// A language server for twoc programs, which editors talk to over stdio (see src/bin/twoc-lsp.rs)
+pub mod tokens;
+pub mod format;
+pub mod analysis;
+pub mod protocol;
+pub mod server;
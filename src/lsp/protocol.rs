@@ -0,0 +1,100 @@
+// Reading and writing language server protocol messages, and converting between byte offsets
+// and the protocol's positions (lines, and UTF-16 code units along them)
+
+use std::io::{self, BufRead, Write};
+
+use crate::json::{self, Json};
+
+// Read a message, which is a Content-Length header, a blank line and then that many bytes of JSON
+// Returns None at the end of the input
+pub fn read_message<R : BufRead>(input : &mut R) -> Result<Option<Json>, String> {
+    let mut length = None;
+
+    loop {
+        let mut line = String::new();
+        let read = input.read_line(&mut line).map_err(|err| format!("Couldn't read a header: {}", err))?;
+
+        if read == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = Some(value.trim().parse::<usize>().map_err(|err| format!("Bad Content-Length {:?}: {}", value.trim(), err))?);
+            }
+        }
+    }
+
+    let length = length.ok_or("A message has no Content-Length header")?;
+
+    let mut content = vec![0; length];
+    input.read_exact(&mut content).map_err(|err| format!("Couldn't read a message: {}", err))?;
+
+    let text = String::from_utf8(content).map_err(|err| format!("A message isn't UTF-8: {}", err))?;
+    json::parse(&text).map(Some)
+}
+
+// Write a message with its header
+pub fn write_message<W : Write>(output : &mut W, message : &Json) -> io::Result<()> {
+    let content = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", content.len(), content)?;
+    output.flush()
+}
+
+// The position of a byte offset, as (line, character)
+// An offset in the middle of a character is moved back to the start of that character
+pub fn position(source : &str, offset : usize) -> (usize, usize) {
+    let before = &source[..char_boundary(source, offset)];
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+
+    (before.matches('\n').count(), before[line_start..].encode_utf16().count())
+}
+
+// The byte offset of a position, clamped to the end of its line
+pub fn offset(source : &str, line : usize, character : usize) -> usize {
+    let line_start = match line {
+        0 => 0,
+        _ => match source.match_indices('\n').nth(line - 1) {
+            Some((i, _)) => i + 1,
+            None => return source.len(),
+        },
+    };
+
+    let mut units = 0;
+
+    for (i, c) in source[line_start..].char_indices() {
+        if units >= character || c == '\n' {
+            return line_start + i;
+        }
+
+        units += c.len_utf16();
+    }
+
+    source.len()
+}
+
+// A protocol range from a span of byte offsets
+pub fn range(source : &str, (start, end) : (usize, usize)) -> Json {
+    let point = |offset| {
+        let (line, character) = position(source, offset);
+        Json::object(vec![("line", line.into()), ("character", character.into())])
+    };
+
+    Json::object(vec![("start", point(start)), ("end", point(end))])
+}
+
+// The nearest char boundary at or before a byte offset, clamped to the end of the source
+fn char_boundary(source : &str, offset : usize) -> usize {
+    let mut offset = offset.min(source.len());
+
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+
+    offset
+}
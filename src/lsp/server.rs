@@ -0,0 +1,203 @@
+// The language server's state, and its responses to the messages an editor sends it
+
+use std::collections::HashMap;
+
+use crate::compiler::Severity;
+use crate::json::Json;
+use crate::lsp::{analysis, format};
+use crate::lsp::protocol::{offset, range};
+
+// The JSON-RPC error code for requests the server doesn't handle
+const METHOD_NOT_FOUND : i64 = -32601;
+
+// The open documents, by URI
+#[derive(Debug, Default)]
+pub struct Server {
+    documents : HashMap<String, String>,
+
+    // Whether the editor has asked the server to shut down
+    shutdown : bool,
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Whether the server should stop, and the exit code it should stop with if so
+    pub fn exit_code(&self, message : &Json) -> Option<i32> {
+        match message.get("method").and_then(Json::as_str) {
+            Some("exit") => Some(if self.shutdown {0} else {1}),
+            _ => None,
+        }
+    }
+
+    // Handle a message, returning the messages to send back (responses and notifications)
+    pub fn handle(&mut self, message : &Json) -> Vec<Json> {
+        let method = message.get("method").and_then(Json::as_str).unwrap_or_default();
+        let params = message.get("params").cloned().unwrap_or(Json::Null);
+
+        // Notifications don't have an id, and don't get a response
+        let id = match message.get("id") {
+            Some(id) => id.clone(),
+            None => return self.notification(method, &params),
+        };
+
+        let result = match method {
+            "initialize" => Some(capabilities()),
+            "shutdown" => {
+                self.shutdown = true;
+                Some(Json::Null)
+            },
+            "textDocument/hover" => Some(self.hover(&params)),
+            "textDocument/definition" => Some(self.definition(&params)),
+            "textDocument/formatting" => Some(self.formatting(&params)),
+            "textDocument/codeLens" => Some(self.code_lens(&params)),
+            _ => None,
+        };
+
+        match result {
+            Some(result) => vec![Json::object(vec![("jsonrpc", "2.0".into()), ("id", id), ("result", result)])],
+            None => vec![Json::object(vec![
+                ("jsonrpc", "2.0".into()),
+                ("id", id),
+                ("error", Json::object(vec![
+                    ("code", METHOD_NOT_FOUND.into()),
+                    ("message", format!("Unknown method {:?}", method).into()),
+                ])),
+            ])],
+        }
+    }
+
+    fn notification(&mut self, method : &str, params : &Json) -> Vec<Json> {
+        let uri = params.at(&["textDocument", "uri"]).and_then(Json::as_str).unwrap_or_default().to_string();
+
+        match method {
+            "textDocument/didOpen" => {
+                let text = params.at(&["textDocument", "text"]).and_then(Json::as_str).unwrap_or_default();
+                self.documents.insert(uri.clone(), text.to_string());
+                vec![self.publish_diagnostics(&uri)]
+            },
+
+            // The server asks for the whole document on every change, so the last change holds all of it
+            "textDocument/didChange" => {
+                let text = params.get("contentChanges").and_then(Json::as_array)
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change.get("text"))
+                    .and_then(Json::as_str);
+
+                match text {
+                    Some(text) => {
+                        self.documents.insert(uri.clone(), text.to_string());
+                        vec![self.publish_diagnostics(&uri)]
+                    },
+                    None => vec![],
+                }
+            },
+
+            // Clear the diagnostics of a closed document
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                vec![diagnostics_notification(&uri, vec![])]
+            },
+
+            _ => vec![],
+        }
+    }
+
+    // The document a request is about, and the byte offset of its position if it has one
+    fn document(&self, params : &Json) -> Option<(&str, usize)> {
+        let uri = params.at(&["textDocument", "uri"]).and_then(Json::as_str)?;
+        let source = self.documents.get(uri)?;
+
+        let line = params.at(&["position", "line"]).and_then(Json::as_i64).unwrap_or(0);
+        let character = params.at(&["position", "character"]).and_then(Json::as_i64).unwrap_or(0);
+
+        Some((source, offset(source, line.max(0) as usize, character.max(0) as usize)))
+    }
+
+    fn publish_diagnostics(&self, uri : &str) -> Json {
+        let source = &self.documents[uri];
+
+        let diagnostics = analysis::diagnostics(source).iter()
+            .map(|diagnostic| Json::object(vec![
+                ("range", range(source, diagnostic.span.unwrap_or((0, 0)))),
+                ("severity", match diagnostic.severity { Severity::Error => 1, Severity::Warning => 2 }.into()),
+                ("source", "twoc".into()),
+                ("message", diagnostic.message.as_str().into()),
+            ]))
+            .collect();
+
+        diagnostics_notification(uri, diagnostics)
+    }
+
+    fn hover(&self, params : &Json) -> Json {
+        let hover = self.document(params).and_then(|(source, offset)| Some((source, analysis::hover(source, offset)?)));
+
+        match hover {
+            Some((source, hover)) => Json::object(vec![
+                ("contents", Json::object(vec![("kind", "markdown".into()), ("value", hover.contents.into())])),
+                ("range", range(source, hover.span)),
+            ]),
+            None => Json::Null,
+        }
+    }
+
+    fn definition(&self, params : &Json) -> Json {
+        let uri = params.at(&["textDocument", "uri"]).cloned().unwrap_or(Json::Null);
+        let definition = self.document(params).and_then(|(source, offset)| Some((source, analysis::definition(source, offset)?)));
+
+        match definition {
+            Some((source, span)) => Json::object(vec![("uri", uri), ("range", range(source, span))]),
+            None => Json::Null,
+        }
+    }
+
+    // Formatting replaces the whole document, or does nothing if it doesn't parse
+    fn formatting(&self, params : &Json) -> Json {
+        let formatted = self.document(params).and_then(|(source, _)| Some((source, format::format(source)?)));
+
+        match formatted {
+            Some((source, formatted)) if formatted != source => Json::Array(vec![Json::object(vec![
+                ("range", range(source, (0, source.len()))),
+                ("newText", formatted.into()),
+            ])]),
+            _ => Json::Array(vec![]),
+        }
+    }
+
+    fn code_lens(&self, params : &Json) -> Json {
+        let lens = self.document(params).and_then(|(source, _)| Some((source, analysis::code_lens(source)?)));
+
+        match lens {
+            Some((source, lens)) => Json::Array(vec![Json::object(vec![
+                ("range", range(source, lens.span)),
+                ("command", Json::object(vec![("title", lens.title.into()), ("command", "".into())])),
+            ])]),
+            None => Json::Array(vec![]),
+        }
+    }
+}
+
+// What the server can do, in response to initialize
+fn capabilities() -> Json {
+    Json::object(vec![
+        ("capabilities", Json::object(vec![
+            // Full document sync
+            ("textDocumentSync", 1.into()),
+            ("hoverProvider", true.into()),
+            ("definitionProvider", true.into()),
+            ("documentFormattingProvider", true.into()),
+            ("codeLensProvider", Json::object(vec![("resolveProvider", false.into())])),
+        ])),
+        ("serverInfo", Json::object(vec![("name", "twoc-lsp".into()), ("version", env!("CARGO_PKG_VERSION").into())])),
+    ])
+}
+
+fn diagnostics_notification(uri : &str, diagnostics : Vec<Json>) -> Json {
+    Json::object(vec![
+        ("jsonrpc", "2.0".into()),
+        ("method", "textDocument/publishDiagnostics".into()),
+        ("params", Json::object(vec![("uri", uri.into()), ("diagnostics", Json::Array(diagnostics))])),
+    ])
+}
@@ -0,0 +1,94 @@
+// A lexer for twoc source that keeps comments and byte offsets, for the editor features that work on
+// the text rather than the syntax tree (formatting, and finding what's under the cursor)
+
+// The kinds of tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    // Keywords and names, e.g. while, rend or X
+    Word,
+
+    // Integers, including negative ones like the -1 in move(-1)
+    Number,
+
+    // Letters of the alphabet, e.g. '0'
+    Letter,
+
+    // Operators and punctuation, e.g. +=, { or ;
+    Symbol,
+
+    // // comments, without the line break
+    LineComment,
+
+    // /* comments */
+    BlockComment,
+}
+
+// A token and where it is in the source (as byte offsets)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind : Kind,
+    pub text : &'a str,
+    pub start : usize,
+    pub end : usize,
+}
+
+impl<'a> Token<'a> {
+    pub fn is(&self, text : &str) -> bool {
+        self.text == text
+    }
+
+    pub fn is_comment(&self) -> bool {
+        matches!(self.kind, Kind::LineComment | Kind::BlockComment)
+    }
+}
+
+// Operators that are two characters long
+const DOUBLE_SYMBOLS : [&str; 8] = ["==", "!=", "&&", "||", "++", "--", "+=", "-="];
+
+// Split source code into tokens, skipping whitespace
+pub fn tokenize(source : &str) -> Vec<Token<'_>> {
+    let bytes = source.as_bytes();
+    let mut tokens : Vec<Token> = Vec::new();
+    let mut pos = 0;
+
+    while pos < source.len() {
+        let rest = &source[pos..];
+        let c = rest.chars().next().unwrap();
+
+        if c.is_whitespace() {
+            pos += c.len_utf8();
+            continue;
+        }
+
+        // A minus sign is part of a number where an operand is expected, e.g. move(-1) or c = -1
+        let operand_expected = tokens.last().is_none_or(|last| ["(", "=", "+=", "-=", ","].contains(&last.text));
+
+        let (kind, len) = if rest.starts_with("//") {
+            (Kind::LineComment, rest.find(['\n', '\r']).unwrap_or(rest.len()))
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            (Kind::BlockComment, comment.find("*/").map(|end| end + 4).unwrap_or(rest.len()))
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            (Kind::Word, rest.find(|c : char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(rest.len()))
+        } else if c.is_ascii_digit() || (c == '-' && operand_expected && bytes.get(pos + 1).is_some_and(|b| b.is_ascii_digit())) {
+            (Kind::Number, 1 + rest[1..].find(|c : char| !c.is_ascii_digit()).unwrap_or(rest.len() - 1))
+        } else if c == '\'' && rest.len() >= 3 && rest[1..].chars().nth(1) == Some('\'') {
+            (Kind::Letter, 2 + rest[1..].chars().next().unwrap().len_utf8())
+        } else if DOUBLE_SYMBOLS.iter().any(|symbol| rest.starts_with(symbol)) {
+            (Kind::Symbol, 2)
+        } else {
+            (Kind::Symbol, c.len_utf8())
+        };
+
+        tokens.push(Token { kind, text : &source[pos..pos + len], start : pos, end : pos + len });
+        pos += len;
+    }
+
+    tokens
+}
+
+// The index of the token at a byte offset, if there is one
+// A cursor just after the end of a token counts as being on it
+pub fn token_at(tokens : &[Token], offset : usize) -> Option<usize> {
+    tokens.iter().position(|token| token.start <= offset && offset < token.end)
+        .or_else(|| tokens.iter().position(|token| token.end == offset))
+}
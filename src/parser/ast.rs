@@ -32,6 +32,11 @@ pub enum Stmt {
 }
 
 impl Stmt {
+    // The first line of the printed statement, e.g. "while (CheckNotZero)" or "move(1), c += -1"
+    pub fn summary(&self) -> String {
+        self.print(0).lines().next().unwrap_or_default().to_string()
+    }
+
     // AST Printer method
     pub fn print(&self, offset : usize) -> String {
         // Generate whitespace buffer
@@ -86,7 +91,7 @@ impl Stmt {
                 // Do the same for the else block if it exists
                 if !else_body.is_empty() {
                     out.push_str(&buffer);
-                    out.push_str(&format!("else\n"));
+                    out.push_str("else\n");
 
                     for stmt in else_body.iter() {
                         let line = stmt.print(offset + 2);
@@ -114,7 +119,7 @@ impl Stmt {
                 for block in blocks.iter() {
                     // Print branch
                     out.push_str(&buffer);
-                    out.push_str(&format!("branch\n"));
+                    out.push_str("branch\n");
 
                     // Print each statement in the branch block
                     for stmt in block.iter() {
@@ -127,7 +132,7 @@ impl Stmt {
             Stmt::WhileChoose(while_body) => {
                 // Print while
                 out.push_str(&buffer);
-                out.push_str(&format!("while (choose)\n"));
+                out.push_str("while (choose)\n");
 
                 // Print each statement in the while block
                 for stmt in while_body.iter() {
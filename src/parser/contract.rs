@@ -49,8 +49,8 @@ pub fn contract(program : &Vec<ast::Stmt>) -> Vec<ast::Stmt> {
                 }
 
                 // Recurse on the if and else blocks
-                let if_block_contr   = contract(&if_block);
-                let else_block_contr = contract(&else_block);
+                let if_block_contr   = contract(if_block);
+                let else_block_contr = contract(else_block);
                 
                 // Reform if statement and push
                 let if_stmt_contr = ast::Stmt::If(cond.clone(), if_block_contr, else_block_contr);
@@ -68,7 +68,7 @@ pub fn contract(program : &Vec<ast::Stmt>) -> Vec<ast::Stmt> {
                 }
 
                 // Recurse on the while block
-                let while_block_contr = contract(&while_block);
+                let while_block_contr = contract(while_block);
 
                 // Reform while statement and push
                 let while_stmt_contr = ast::Stmt::While(cond.clone(), while_block_contr);
@@ -87,7 +87,7 @@ pub fn contract(program : &Vec<ast::Stmt>) -> Vec<ast::Stmt> {
 
                 // Contract each branch block
                 let branch_blocks_contr : Vec<Vec<ast::Stmt>> = 
-                    branch_blocks.into_iter()
+                    branch_blocks.iter()
                     .map(contract)
                     .collect();
 
@@ -106,7 +106,7 @@ pub fn contract(program : &Vec<ast::Stmt>) -> Vec<ast::Stmt> {
                 }
 
                 // Recurse on the while block
-                let while_block_contr = contract(&while_block);
+                let while_block_contr = contract(while_block);
 
                 // Reform while statement and push
                 let while_stmt_contr = ast::Stmt::WhileChoose(while_block_contr);
@@ -4,6 +4,7 @@ use std::collections::HashSet;
 
 use crate::parser::{ast, contract::contract};
 
+#[derive(Debug, Clone)]
 pub struct Program {
     pub stmts : Vec<ast::Stmt>,
     pub alpha : HashSet<char>,
@@ -37,7 +38,7 @@ impl Program {
 
     // Check if this program is deterministic
     pub fn deterministic(&self) -> bool {
-        return Program::no_branches(self.stmts.clone());
+        Program::no_branches(self.stmts.clone())
     }
 
     // True if prog contains no branches, false otherwise
@@ -3,6 +3,10 @@
 pub type Cond = crate::parser::ast::Cond;
 pub type Readable = crate::parser::ast::Readable;
 
+// The byte range of the source a part of a statement was parsed from,
+// or None if the statement wasn't parsed (e.g. it was generated by the fuzzer)
+pub type Span = Option<(usize, usize)>;
+
 // AST nodes for statements 
 #[derive(Debug, Clone)]
 pub enum Stmt {
@@ -15,17 +19,17 @@ pub enum Stmt {
     // move(i)
     Move(i32),
 
-    // c += j
-    Incr(Value), 
+    // c += j, with the span of j
+    Incr(Value, Span),
 
-    // c = j
-    Asgn(Value),
+    // c = j, with the span of j
+    Asgn(Value, Span),
 
-    // if-else
-    If(Cond, Vec<Stmt>, Vec<Stmt>),
+    // if-else, with the span of the condition
+    If(Cond, Vec<Stmt>, Vec<Stmt>, Span),
 
-    // while
-    While(Cond, Vec<Stmt>),
+    // while, with the span of the condition
+    While(Cond, Vec<Stmt>, Span),
 
     // branch
     Branch(Vec<Vec<Stmt>>),
@@ -75,19 +79,19 @@ impl Stmt {
             },
 
             // Print increment/decrement statement
-            Stmt::Incr(incr_by) => {
+            Stmt::Incr(incr_by, _) => {
                 out.push_str(&buffer);
                 out.push_str(&format!("c += {:?}\n", incr_by));
             },
 
             // Print increment/decrement statement
-            Stmt::Asgn(incr_by) => {
+            Stmt::Asgn(incr_by, _) => {
                 out.push_str(&buffer);
                 out.push_str(&format!("c = {:?}\n", incr_by));
             },
 
             // Print if statement
-            Stmt::If(cond, if_body, else_body) => {
+            Stmt::If(cond, if_body, else_body, _) => {
                 // Print if (condition printing just uses the debug trait for now)
                 out.push_str(&buffer);
                 out.push_str(&format!("if ({:?})\n", cond));
@@ -101,7 +105,7 @@ impl Stmt {
                 // Do the same for the else block if it exists
                 if !else_body.is_empty() {
                     out.push_str(&buffer);
-                    out.push_str(&format!("else\n"));
+                    out.push_str("else\n");
 
                     for stmt in else_body.iter() {
                         let line = stmt.print(offset + 2);
@@ -111,7 +115,7 @@ impl Stmt {
             },
 
             // Print while block
-            Stmt::While(cond, while_body) => {
+            Stmt::While(cond, while_body, _) => {
                 // Print while
                 out.push_str(&buffer);
                 out.push_str(&format!("while ({:?})\n", cond));
@@ -129,7 +133,7 @@ impl Stmt {
                 for block in blocks.iter() {
                     // Print branch
                     out.push_str(&buffer);
-                    out.push_str(&format!("branch\n"));
+                    out.push_str("branch\n");
 
                     // Print each statement in the branch block
                     for stmt in block.iter() {
@@ -143,7 +147,7 @@ impl Stmt {
             Stmt::WhileChoose(while_body) => {
                 // Print while
                 out.push_str(&buffer);
-                out.push_str(&format!("while (choose)\n"));
+                out.push_str("while (choose)\n");
 
                 // Print each statement in the while block
                 for stmt in while_body.iter() {
@@ -21,7 +21,7 @@ pub fn convert_sugar(in_prog : SugarProg) -> Prog {
     let mut stmts = Vec::new();
 
     // Insert code to check that the program satisfies alpha[0]* + alpha[1]* + ... 
-    if in_prog.pars.len() > 0 {
+    if !in_prog.pars.is_empty() {
         // Find the order in which characters should show up
         let mut char_order = Vec::new();
         for p in in_prog.pars.into_iter().rev() {
@@ -72,7 +72,7 @@ fn convert_statement(sugar : SugarStmt, parmap : &HashMap<String, char>) -> Vec<
         SugarStmt::Move(i) => vec![Stmt::Move(i)],
 
         // Increments
-        SugarStmt::Incr(incr) => match incr {
+        SugarStmt::Incr(incr, _) => match incr {
             // Incr statements on literals don't need fancy logic either
             super::ast::Value::Lit(j) => vec![Stmt::Incr(j)],
 
@@ -80,7 +80,7 @@ fn convert_statement(sugar : SugarStmt, parmap : &HashMap<String, char>) -> Vec<
 
             super::ast::Value::Par(par) => {
                 // Get alphabet character
-                let c = parmap.get(&par).expect(&format!("Parameter {:?} Undeclared!", par));
+                let c = parmap.get(&par).unwrap_or_else(|| panic!("Parameter {:?} Undeclared!", par));
                 
                 // Move to left endmarker
                 let move_to_lend = Stmt::While(
@@ -108,7 +108,7 @@ fn convert_statement(sugar : SugarStmt, parmap : &HashMap<String, char>) -> Vec<
 
             super::ast::Value::NegPar(par) => {
                 // Get alphabet character
-                let c = parmap.get(&par).expect(&format!("Parameter {:?} Undeclared!", par));
+                let c = parmap.get(&par).unwrap_or_else(|| panic!("Parameter {:?} Undeclared!", par));
                 
                 // Move to left endmarker
                 let move_to_lend = Stmt::While(
@@ -136,7 +136,7 @@ fn convert_statement(sugar : SugarStmt, parmap : &HashMap<String, char>) -> Vec<
         },
 
         // Counter assignments
-        SugarStmt::Asgn(incr) => match incr {
+        SugarStmt::Asgn(incr, _) => match incr {
             // Assigning to literal values 
             super::ast::Value::Lit(j) => {
                 // Panic if negative
@@ -159,7 +159,7 @@ fn convert_statement(sugar : SugarStmt, parmap : &HashMap<String, char>) -> Vec<
             // Assigning to parameters
             super::ast::Value::Par(par) => {
                 // Get alphabet character
-                let c = parmap.get(&par).expect(&format!("Parameter {:?} Undeclared!", par));
+                let c = parmap.get(&par).unwrap_or_else(|| panic!("Parameter {:?} Undeclared!", par));
 
                 // Empty counter
                 let empty = Stmt::While(
@@ -197,7 +197,7 @@ fn convert_statement(sugar : SugarStmt, parmap : &HashMap<String, char>) -> Vec<
         },
         
         // If statements
-        SugarStmt::If(cond, if_block, else_block) => {
+        SugarStmt::If(cond, if_block, else_block, _) => {
             // Recursively convert if block
             let mut converted_if = Vec::new();
             for stmt in if_block {
@@ -213,7 +213,7 @@ fn convert_statement(sugar : SugarStmt, parmap : &HashMap<String, char>) -> Vec<
             vec![Stmt::If(cond, converted_if, converted_else)]
         },
 
-        SugarStmt::While(cond, while_block) => {
+        SugarStmt::While(cond, while_block, _) => {
             // Recursively convert while block
             let mut converted_while = Vec::new();
             for stmt in while_block {
@@ -1,3 +1,6 @@
 pub mod ast;
 pub mod program;
-pub mod convert_sugar;
\ No newline at end of file
+pub mod convert_sugar;
+
+// The parser for programs with syntactic sugar, generated by lalrpop from sugar_grammar.lalrpop
+lalrpop_util::lalrpop_mod!(#[allow(clippy::all)] pub sugar_grammar, "/parser/sugar/sugar_grammar.rs");
@@ -5,6 +5,7 @@ use std::iter::zip;
 
 use crate::parser::sugar::ast;
 
+#[derive(Debug, Clone)]
 pub struct Program {
     // The actual program itself
     pub stmts  : Vec<ast::Stmt>,
@@ -24,7 +25,13 @@ pub struct Program {
 
 impl Program {
     // Construct a Program given an AST and an alphabet
+    // Panics if there are parameters, but not one for each letter of the alphabet
     pub fn new(prog : Vec<ast::Stmt>, char_list : Vec<char>, par_list : Vec<String>, decr_zero : bool) -> Self {
+        Self::try_new(prog, char_list, par_list, decr_zero).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    // Same as new, but returns an error instead of panicking
+    pub fn try_new(prog : Vec<ast::Stmt>, char_list : Vec<char>, par_list : Vec<String>, decr_zero : bool) -> Result<Self, String> {
         // Convert the alphabet from a vector to a HashSet
         let mut char_set = HashSet::new();
         for char in char_list.clone() {
@@ -32,8 +39,8 @@ impl Program {
         }
 
         // Check that the alphabet and parameter list are of the same size
-        if (par_list.len() > 0) && (par_list.len() != char_set.len()) {
-            panic!("Different number of parameters to characters in alphabet!");
+        if (!par_list.is_empty()) && (par_list.len() != char_set.len()) {
+            return Err("Different number of parameters to characters in alphabet!".to_string());
         }
 
         let mut map = HashMap::new();
@@ -44,7 +51,7 @@ impl Program {
         }
 
         // Construct the Program object
-        Self { stmts : prog, alpha : char_set, pars : par_list, parmap : map, decr_zero }
+        Ok(Self { stmts : prog, alpha : char_set, pars : par_list, parmap : map, decr_zero })
     }
 
     // Print out the program
@@ -56,7 +63,7 @@ impl Program {
 
     // Check if this program is deterministic
     pub fn deterministic(&self) -> bool {
-        return Program::no_branches(self.stmts.clone());
+        Program::no_branches(self.stmts.clone())
     }
 
     // True if prog contains no branches, false otherwise
@@ -67,7 +74,7 @@ impl Program {
                 ast::Stmt::Branch(_) => return false,
 
                 // Recursively check each of the branches of an if statement
-                ast::Stmt::If(_, if_branch, else_branch) => { 
+                ast::Stmt::If(_, if_branch, else_branch, _) => { 
                     if !Program::no_branches(if_branch) || !Program::no_branches(else_branch) {
                         return false;
                     }
@@ -76,7 +83,7 @@ impl Program {
                 },
 
                 // Recursively check a while statement
-                ast::Stmt::While(_, while_branch) => {
+                ast::Stmt::While(_, while_branch, _) => {
                     if !Program::no_branches(while_branch) {
                         return false;
                     }
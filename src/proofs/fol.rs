@@ -145,19 +145,15 @@ mod rules {
     // specification
     pub fn specification<A : Eq + PartialEq + Clone>(expr : Arith<A>, proof : PropFormula<Fol<A>>) -> PropFormula<Fol<A>> {
         match proof {
-            PropFormula::Var(formula) => match formula {
-                Fol::ForAll(x, y) => {
-                    let arith_vars = get_arith_vars(expr.clone());
-                    let bound_vars = bound_vars(*y.clone());
-
-                    for v in arith_vars {
-                        if bound_vars.contains(&v) { panic!("specification used incorrecty"); }
-                    }
+            PropFormula::Var(Fol::ForAll(x, y)) => {
+                let arith_vars = get_arith_vars(expr.clone());
+                let bound_vars = bound_vars(*y.clone());
 
-                    substitute(*y, Arith::Var(x), expr)
-                },
+                for v in arith_vars {
+                    if bound_vars.contains(&v) { panic!("specification used incorrecty"); }
+                }
 
-                _ => panic!("specification used incorrectly"),
+                substitute(*y, Arith::Var(x), expr)
             },
 
             _ => panic!("specification used incorrectly"),
@@ -190,13 +186,11 @@ mod rules {
 
     // interchange
     pub fn forall_to_negext<A : Eq + PartialEq + Clone>(proof : PropFormula<Fol<A>>) -> PropFormula<Fol<A>> {
-        if let PropFormula::Var(inner) = proof {
-            if let Fol::ForAll(x, y) = inner {
-                if let PropFormula::Not(negated) = *y {
-                    let existential = PropFormula::Var(Fol::Exists(x, negated));
-                    
-                    return PropFormula::Not(Box::new(existential));
-                }
+        if let PropFormula::Var(Fol::ForAll(x, y)) = proof {
+            if let PropFormula::Not(negated) = *y {
+                let existential = PropFormula::Var(Fol::Exists(x, negated));
+                
+                return PropFormula::Not(Box::new(existential));
             }
         }
 
@@ -205,13 +199,11 @@ mod rules {
 
     pub fn negext_to_forall<A: Eq + PartialEq + Clone>(proof : PropFormula<Fol<A>>) -> PropFormula<Fol<A>> {
         if let PropFormula::Not(inner) = proof {
-            if let PropFormula::Var(ext) = *inner {
-                if let Fol::Exists(x, y) = ext {
-                    let negated = PropFormula::Not(y);
-                    let universal = Fol::ForAll(x, Box::new(negated));
-                    
-                    return PropFormula::Var(universal);
-                }
+            if let PropFormula::Var(Fol::Exists(x, y)) = *inner {
+                let negated = PropFormula::Not(y);
+                let universal = Fol::ForAll(x, Box::new(negated));
+                
+                return PropFormula::Var(universal);
             }
         }
 
@@ -143,7 +143,7 @@ pub fn apply_prop_rule<A, F>(path : &[Pos], rule : F, formula : PropFormula<A>)
             PropFormula::Not(Box::new(new_inner))
         },
 
-        PropFormula::Var(v) => return PropFormula::Var(v),
+        PropFormula::Var(v) => PropFormula::Var(v),
 
         PropFormula::And(left, right) => {
             match *head {
@@ -74,7 +74,7 @@ impl<'a> AhuSimulator<'a> {
                 let config = Config { state, read : i as i32, counter };
 
                 // Find the transitions that can be taken from this config
-                let transitions = get_transitions(self.autom, config, self.input.clone());
+                let transitions = get_transitions(self.autom, config, &self.input);
 
                 for trans in transitions {
                     // Check if the transition decrements the counter
@@ -105,7 +105,7 @@ impl<'a> AhuSimulator<'a> {
                 let config = Config { state, read : i, counter };
 
                 // Find the transitions that can be taken from this config
-                let transitions = get_transitions(self.autom, config, self.input.clone());
+                let transitions = get_transitions(self.autom, config, &self.input);
 
                 for trans in transitions {
                     // Check if the transition increments the counter
@@ -0,0 +1,143 @@
+// The simulation procedures that can be picked by name, and which programs each of them can simulate
+// The command line, the C API and the fuzzer all pick from here, so they agree on what's valid
+
+use crate::automaton::compiled::CompiledAutom;
+use crate::compiler::Compiled;
+use crate::simulation::{glueck, glueck_nondeterm, glueck_array, naive_determ, naive_nondeterm};
+use crate::simulation::{rytter, rytter_matrix, rytter_reachable, rytter_parallel, streaming};
+use crate::simulation::budget::{Budget, Outcome};
+use crate::simulation::parallel::Procedure;
+use crate::simulation::stats::RunStats;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    Streaming,
+    Glueck,
+    GlueckArray,
+    GlueckNondeterm,
+    NaiveDeterm,
+    NaiveNondeterm,
+    Rytter,
+    RytterMatrix,
+    RytterReachable,
+    RytterParallel,
+}
+
+impl Algorithm {
+    // Every algorithm, in the order they're listed in
+    pub const ALL : [Algorithm; 10] = [
+        Algorithm::Streaming,
+        Algorithm::Glueck,
+        Algorithm::GlueckArray,
+        Algorithm::GlueckNondeterm,
+        Algorithm::NaiveDeterm,
+        Algorithm::NaiveNondeterm,
+        Algorithm::Rytter,
+        Algorithm::RytterMatrix,
+        Algorithm::RytterReachable,
+        Algorithm::RytterParallel,
+    ];
+
+    // The name of the algorithm, as it's picked by and printed in results
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Streaming       => "streaming",
+            Algorithm::Glueck          => "glueck",
+            Algorithm::GlueckArray     => "glueck_array",
+            Algorithm::GlueckNondeterm => "glueck_nondeterm",
+            Algorithm::NaiveDeterm     => "naive_determ",
+            Algorithm::NaiveNondeterm  => "naive_nondeterm",
+            Algorithm::Rytter          => "rytter",
+            Algorithm::RytterMatrix    => "rytter_matrix",
+            Algorithm::RytterReachable => "rytter_reachable",
+            Algorithm::RytterParallel  => "rytter_parallel",
+        }
+    }
+
+    // The algorithm with a name
+    pub fn from_name(name : &str) -> Result<Self, String> {
+        Algorithm::ALL.iter()
+            .find(|algorithm| algorithm.name() == name)
+            .copied()
+            .ok_or(format!("There's no algorithm called {:?}", name))
+    }
+
+    // Whether the algorithm only works on deterministic automata
+    pub fn deterministic_only(&self) -> bool {
+        matches!(self, Algorithm::Glueck | Algorithm::GlueckArray | Algorithm::NaiveDeterm)
+    }
+
+    // Whether the algorithm only works on automata that never move left
+    pub fn one_way_only(&self) -> bool {
+        matches!(self, Algorithm::Streaming)
+    }
+
+    // Whether the algorithm runs on the general construction, even for deterministic programs,
+    // since that's the automaton the nondeterministic simulators are written for
+    pub fn general(&self) -> bool {
+        !self.deterministic_only() && !self.one_way_only()
+    }
+
+    // Whether the algorithm can simulate an automaton
+    pub fn applies(&self, deterministic : bool, one_way : bool) -> bool {
+        (deterministic || !self.deterministic_only()) && (one_way || !self.one_way_only())
+    }
+
+    // Check that the algorithm can simulate a program
+    pub fn check(&self, compiled : &Compiled) -> Result<(), String> {
+        if self.deterministic_only() && !compiled.deterministic {
+            return Err(format!("{} can only simulate deterministic programs!", self.name()));
+        }
+
+        if self.one_way_only() && !compiled.one_way {
+            return Err(format!("{} can only simulate one-way programs!", self.name()));
+        }
+
+        Ok(())
+    }
+
+    // The automaton the algorithm simulates for a program
+    pub fn automaton(&self, compiled : &Compiled) -> CompiledAutom {
+        match self.general() {
+            true  => compiled.general(),
+            false => compiled.frozen.clone(),
+        }
+    }
+
+    // The algorithm to simulate a program with if none is asked for
+    pub fn pick(compiled : &Compiled, threads : usize) -> Self {
+        match (compiled.one_way, compiled.deterministic) {
+            // One-way automata only need a single sweep over the input
+            (true, _)                     => Algorithm::Streaming,
+            (false, true)                 => Algorithm::GlueckArray,
+            (false, false) if threads > 1 => Algorithm::RytterParallel,
+            (false, false)                => Algorithm::Rytter,
+        }
+    }
+
+    // Simulate the automaton on a word (threads is only used by rytter_parallel)
+    // naive_determ only counts the distinct configs it goes through if stats is set
+    pub fn run(&self, compiled : &CompiledAutom, word : &str, budget : &Budget, threads : usize, stats : bool) -> (Outcome, RunStats) {
+        match self {
+            Algorithm::Streaming       => streaming::streaming_procedure_with_budget(compiled, word, budget),
+            Algorithm::Glueck          => glueck::glueck_procedure_with_budget(compiled, word, budget),
+            Algorithm::GlueckArray     => glueck_array::glueck_procedure_with_budget(compiled, word, budget),
+            Algorithm::GlueckNondeterm => glueck_nondeterm::glueck_procedure_with_budget(compiled, word, budget),
+            Algorithm::NaiveDeterm     => naive_determ::naive_with_options(compiled, word, budget, stats),
+            Algorithm::NaiveNondeterm  => naive_nondeterm::naive_with_budget(compiled, word, budget),
+            Algorithm::Rytter          => rytter::rytter_procedure_with_budget(compiled, word, budget),
+            Algorithm::RytterMatrix    => rytter_matrix::rytter_procedure_with_budget(compiled, word, budget),
+            Algorithm::RytterReachable => rytter_reachable::rytter_procedure_with_budget(compiled, word, budget),
+            Algorithm::RytterParallel  => rytter_parallel::rytter_procedure_with_budget(compiled, word, threads, budget),
+        }
+    }
+}
+
+// The procedure to simulate a program's automaton (compiled.frozen) on many words with
+pub fn procedure_for(compiled : &Compiled) -> Procedure {
+    match (compiled.one_way, compiled.deterministic) {
+        (true, _)      => streaming::streaming_procedure,
+        (false, true)  => glueck_array::glueck_procedure,
+        (false, false) => rytter_reachable::rytter_procedure,
+    }
+}
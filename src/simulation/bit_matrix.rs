@@ -0,0 +1,248 @@
+// A square boolean matrix packed into 64-bit words, stored both row-wise and column-wise
+
+// The type of the words that rows and columns are packed into
+type Word = u64;
+
+// Number of bits in a word
+const WORD_BITS : usize = Word::BITS as usize;
+
+#[derive(Debug, Clone)]
+pub struct BitMatrix {
+    // The number of rows (and columns) in the matrix
+    size : usize,
+
+    // The number of words each row or column takes up
+    words : usize,
+
+    // Bit j of row i is set iff (i, j) is in the matrix
+    rows : Vec<Word>,
+
+    // Bit i of column j is set iff (i, j) is in the matrix
+    cols : Vec<Word>,
+}
+
+impl BitMatrix {
+    // Create a new size x size matrix with every entry set to false
+    pub fn new(size : usize) -> Self {
+        let words = size.div_ceil(WORD_BITS);
+
+        Self {
+            size,
+            words,
+            rows : vec![0; size * words],
+            cols : vec![0; size * words],
+        }
+    }
+
+    // The number of rows (and columns) in the matrix
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    // The number of bytes used to store the matrix
+    pub fn bytes(&self) -> usize {
+        (self.rows.len() + self.cols.len()) * std::mem::size_of::<Word>()
+    }
+
+    // Get matrix[i, j]
+    pub fn get(&self, i : usize, j : usize) -> bool {
+        self.rows[i * self.words + j / WORD_BITS] & Self::mask(j) != 0
+    }
+
+    // Set matrix[i, j] to true, returning whether or not it was false before
+    pub fn set(&mut self, i : usize, j : usize) -> bool {
+        if self.get(i, j) { return false; }
+
+        self.rows[i * self.words + j / WORD_BITS] |= Self::mask(j);
+        self.cols[j * self.words + i / WORD_BITS] |= Self::mask(i);
+        true
+    }
+
+    // The number of entries that are true
+    pub fn count(&self) -> usize {
+        self.rows.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    // Get every j such that matrix[i, j] is true
+    pub fn row(&self, i : usize) -> Vec<usize> {
+        Self::ones(&self.rows[i * self.words..(i + 1) * self.words])
+    }
+
+    // For every k such that matrix[k, from] is true and matrix[k, to] is false, set matrix[k, to] to true
+    // Returns all such k, which are found a word at a time
+    pub fn or_col_into(&mut self, from : usize, to : usize) -> Vec<usize> {
+        let mut new_ks = Vec::new();
+
+        for w in 0..self.words {
+            let new_bits = self.cols[from * self.words + w] & !self.cols[to * self.words + w];
+            if new_bits == 0 { continue; }
+
+            self.cols[to * self.words + w] |= new_bits;
+
+            for k in Self::word_ones(new_bits, w) {
+                self.rows[k * self.words + to / WORD_BITS] |= Self::mask(to);
+                new_ks.push(k);
+            }
+        }
+
+        new_ks
+    }
+
+    // For every k such that matrix[from, k] is true and matrix[to, k] is false, set matrix[to, k] to true
+    // Returns all such k, which are found a word at a time
+    pub fn or_row_into(&mut self, from : usize, to : usize) -> Vec<usize> {
+        let mut new_ks = Vec::new();
+
+        for w in 0..self.words {
+            let new_bits = self.rows[from * self.words + w] & !self.rows[to * self.words + w];
+            if new_bits == 0 { continue; }
+
+            self.rows[to * self.words + w] |= new_bits;
+
+            for k in Self::word_ones(new_bits, w) {
+                self.cols[k * self.words + to / WORD_BITS] |= Self::mask(to);
+                new_ks.push(k);
+            }
+        }
+
+        new_ks
+    }
+
+    // The mask that picks out bit i of the word containing it
+    fn mask(i : usize) -> Word {
+        1 << (i % WORD_BITS)
+    }
+
+    // The indices of all the set bits in a slice of words
+    fn ones(words : &[Word]) -> Vec<usize> {
+        let mut out = Vec::new();
+        for (w, word) in words.iter().enumerate() {
+            out.extend(Self::word_ones(*word, w));
+        }
+        out
+    }
+
+    // The indices of all the set bits in the wth word of a row or column
+    fn word_ones(mut word : Word, w : usize) -> impl Iterator<Item = usize> {
+        std::iter::from_fn(move || {
+            if word == 0 { return None; }
+
+            let bit = word.trailing_zeros() as usize;
+            word &= word - 1;
+            Some(w * WORD_BITS + bit)
+        })
+    }
+}
+
+// A BitMatrix that can be read and written by several threads at once
+// Entries are only ever set, never cleared, and every access is sequentially consistent
+#[derive(Debug)]
+pub struct AtomicBitMatrix {
+    // The number of rows (and columns) in the matrix
+    size : usize,
+
+    // The number of words each row or column takes up
+    words : usize,
+
+    // Bit j of row i is set iff (i, j) is in the matrix
+    rows : Vec<AtomicWord>,
+
+    // Bit i of column j is set iff (i, j) is in the matrix
+    cols : Vec<AtomicWord>,
+}
+
+// The atomic counterpart of Word
+type AtomicWord = std::sync::atomic::AtomicU64;
+
+// All the atomic operations on the matrix use the same ordering
+const ORDERING : std::sync::atomic::Ordering = std::sync::atomic::Ordering::SeqCst;
+
+impl AtomicBitMatrix {
+    // Create a new size x size matrix with every entry set to false
+    pub fn new(size : usize) -> Self {
+        let words = size.div_ceil(WORD_BITS);
+
+        Self {
+            size,
+            words,
+            rows : (0..size * words).map(|_| AtomicWord::new(0)).collect(),
+            cols : (0..size * words).map(|_| AtomicWord::new(0)).collect(),
+        }
+    }
+
+    // The number of rows (and columns) in the matrix
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    // Get matrix[i, j]
+    pub fn get(&self, i : usize, j : usize) -> bool {
+        self.rows[i * self.words + j / WORD_BITS].load(ORDERING) & BitMatrix::mask(j) != 0
+    }
+
+    // Set matrix[i, j] to true, returning whether or not it was false before
+    // Only one of the threads setting the same entry at the same time gets true back
+    pub fn set(&self, i : usize, j : usize) -> bool {
+        let old = self.rows[i * self.words + j / WORD_BITS].fetch_or(BitMatrix::mask(j), ORDERING);
+        self.cols[j * self.words + i / WORD_BITS].fetch_or(BitMatrix::mask(i), ORDERING);
+        old & BitMatrix::mask(j) == 0
+    }
+
+    // The number of entries that are true
+    pub fn count(&self) -> usize {
+        self.rows.iter().map(|word| word.load(ORDERING).count_ones() as usize).sum()
+    }
+
+    // Get every j such that matrix[i, j] is true
+    pub fn row(&self, i : usize) -> Vec<usize> {
+        let words : Vec<Word> = self.rows[i * self.words..(i + 1) * self.words]
+            .iter()
+            .map(|word| word.load(ORDERING))
+            .collect();
+
+        BitMatrix::ones(&words)
+    }
+
+    // For every k such that matrix[k, from] is true and matrix[k, to] is false, set matrix[k, to] to true
+    // Returns the k for which this thread was the one to set matrix[k, to]
+    pub fn or_col_into(&self, from : usize, to : usize) -> Vec<usize> {
+        let mut new_ks = Vec::new();
+
+        for w in 0..self.words {
+            let new_bits = self.cols[from * self.words + w].load(ORDERING) & !self.cols[to * self.words + w].load(ORDERING);
+            if new_bits == 0 { continue; }
+
+            self.cols[to * self.words + w].fetch_or(new_bits, ORDERING);
+
+            // The row bits decide which thread gets to report each new entry
+            for k in BitMatrix::word_ones(new_bits, w) {
+                let old = self.rows[k * self.words + to / WORD_BITS].fetch_or(BitMatrix::mask(to), ORDERING);
+                if old & BitMatrix::mask(to) == 0 {
+                    new_ks.push(k);
+                }
+            }
+        }
+
+        new_ks
+    }
+
+    // For every k such that matrix[from, k] is true and matrix[to, k] is false, set matrix[to, k] to true
+    // Returns the k for which this thread was the one to set matrix[to, k]
+    pub fn or_row_into(&self, from : usize, to : usize) -> Vec<usize> {
+        let mut new_ks = Vec::new();
+
+        for w in 0..self.words {
+            let new_bits = self.rows[from * self.words + w].load(ORDERING) & !self.rows[to * self.words + w].load(ORDERING);
+            if new_bits == 0 { continue; }
+
+            let old = self.rows[to * self.words + w].fetch_or(new_bits, ORDERING);
+
+            for k in BitMatrix::word_ones(new_bits & !old, w) {
+                self.cols[k * self.words + to / WORD_BITS].fetch_or(BitMatrix::mask(to), ORDERING);
+                new_ks.push(k);
+            }
+        }
+
+        new_ks
+    }
+}
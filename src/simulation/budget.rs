@@ -0,0 +1,201 @@
+// Limits on how much work a simulator can do before it gives up on a word
+
+use std::fmt;
+use std::time::Instant;
+
+use crate::automaton::compiled::CompiledAutom;
+use crate::automaton::generic_autom::State;
+use crate::simulation::stats::RunStats;
+
+// How often (in steps) a Meter looks at the clock, since reading it every step is slow
+const CLOCK_INTERVAL : u64 = 1024;
+
+// The resources a simulator is allowed to use. Every limit is off by default
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Budget {
+    // The most steps the simulator can take
+    // (transitions followed, or configs and pairs of configs processed by the nondeterministic procedures)
+    pub max_steps : Option<u64>,
+
+    // The most configurations the simulator can keep in its tables
+    // (Rytter's procedures count the pairs of configurations R has room for)
+    pub max_configs : Option<usize>,
+
+    // The time by which the simulator has to have finished
+    pub deadline : Option<Instant>,
+}
+
+// A simulation procedure that runs under a budget, e.g. glueck_array::glueck_procedure_with_budget
+pub type BudgetedProcedure = fn(&CompiledAutom, &str, &Budget) -> (Outcome, RunStats);
+
+// The limit of a budget that a simulator ran into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exceeded {
+    Steps,
+    Configs,
+    Deadline,
+}
+
+impl fmt::Display for Exceeded {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Exceeded::Steps    => write!(f, "step"),
+            Exceeded::Configs  => write!(f, "config"),
+            Exceeded::Deadline => write!(f, "time"),
+        }
+    }
+}
+
+// Something about an automaton that keeps a simulator from deciding a word, whatever its budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    // The counter went past the bound that a one-way automaton keeps it under
+    CounterBound(i32),
+
+    // A deterministic simulator found more than one transition that can fire from a state
+    Nondeterministic(State),
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Fault::CounterBound(bound) => write!(f, "the counter went past {} in an automaton that should be one-way", bound),
+            Fault::Nondeterministic(state) => write!(f, "the automaton is nondeterministic from state {}", state),
+        }
+    }
+}
+
+// Why a simulator stopped before deciding a word
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Exceeded(Exceeded),
+    Fault(Fault),
+}
+
+impl From<Exceeded> for Stop {
+    fn from(exceeded : Exceeded) -> Self {
+        Stop::Exceeded(exceeded)
+    }
+}
+
+impl From<Fault> for Stop {
+    fn from(fault : Fault) -> Self {
+        Stop::Fault(fault)
+    }
+}
+
+// The result of simulating a word under a budget
+// Simulators return this alongside their RunStats, which only cover the work done before
+// giving up if the budget was exceeded or the simulator hit a fault
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Accepted,
+    Rejected,
+    BudgetExceeded(Exceeded),
+    Failed(Fault),
+}
+
+impl Outcome {
+    // Whether or not the word is known to be accepted
+    pub fn accepted(&self) -> bool {
+        *self == Outcome::Accepted
+    }
+
+    // The outcome of a simulator that decided the word
+    pub fn decided(accepted : bool) -> Self {
+        match accepted {
+            true  => Outcome::Accepted,
+            false => Outcome::Rejected,
+        }
+    }
+}
+
+impl From<Result<bool, Exceeded>> for Outcome {
+    fn from(result : Result<bool, Exceeded>) -> Self {
+        match result {
+            Ok(accepted)  => Outcome::decided(accepted),
+            Err(exceeded) => Outcome::BudgetExceeded(exceeded),
+        }
+    }
+}
+
+impl From<Result<bool, Stop>> for Outcome {
+    fn from(result : Result<bool, Stop>) -> Self {
+        match result {
+            Ok(accepted) => Outcome::decided(accepted),
+            Err(Stop::Exceeded(exceeded)) => Outcome::BudgetExceeded(exceeded),
+            Err(Stop::Fault(fault)) => Outcome::Failed(fault),
+        }
+    }
+}
+
+impl Budget {
+    // Check that the given number of steps is allowed
+    pub fn check_steps(&self, steps : u64) -> Result<(), Exceeded> {
+        match self.max_steps {
+            Some(max_steps) if steps > max_steps => Err(Exceeded::Steps),
+            _ => Ok(()),
+        }
+    }
+
+    // Check that keeping the given number of configurations is allowed
+    pub fn check_configs(&self, configs : usize) -> Result<(), Exceeded> {
+        match self.max_configs {
+            Some(max_configs) if configs > max_configs => Err(Exceeded::Configs),
+            _ => Ok(()),
+        }
+    }
+
+    // Check that the given number of steps is allowed, and (every so often, starting with the first step)
+    // that the deadline hasn't passed
+    pub fn check_progress(&self, steps : u64) -> Result<(), Exceeded> {
+        self.check_steps(steps)?;
+
+        if steps % CLOCK_INTERVAL == 1 {
+            self.check_deadline()?;
+        }
+
+        Ok(())
+    }
+
+    // Check that the deadline hasn't passed
+    pub fn check_deadline(&self) -> Result<(), Exceeded> {
+        match self.deadline {
+            Some(deadline) if Instant::now() > deadline => Err(Exceeded::Deadline),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Counts the steps a simulator takes against its budget
+#[derive(Debug)]
+pub struct Meter {
+    // The budget being spent
+    pub budget : Budget,
+
+    // Steps taken so far
+    steps : u64,
+}
+
+impl Meter {
+    // Constructor
+    pub fn new(budget : &Budget) -> Self {
+        Self { budget : *budget, steps : 0 }
+    }
+
+    // Take a step, checking it against the budget
+    pub fn step(&mut self) -> Result<(), Exceeded> {
+        self.steps += 1;
+        self.budget.check_progress(self.steps)
+    }
+
+    // The number of steps taken so far
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    // Check that keeping the given number of configurations is allowed
+    pub fn check_configs(&self, configs : usize) -> Result<(), Exceeded> {
+        self.budget.check_configs(configs)
+    }
+}
@@ -1,9 +1,8 @@
 // Types corresponding to automaton configurations
 
 use crate::automaton::generic_autom::State;
-use crate::automaton::autom;
-//use crate::automaton::autom;
-use crate::parser::ast::Input;
+use crate::automaton::compiled::{Action, CompiledAutom, Tape};
+use crate::simulation::budget::Fault;
 
 // Configuration of an automaton (i.e. all the information required to keep track of a computation)
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
@@ -38,16 +37,32 @@ pub fn make_delta_config(from : Config, to : Config) -> DeltaConfig {
     }
 }
 
+// Find the counter value after a transition changes it by incr_by, if the change is allowed
+// Decrementing at zero leaves the counter at zero if decr_zero is set, and blocks the transition otherwise
+pub fn next_counter(counter : i32, incr_by : i32, decr_zero : bool) -> Option<i32> {
+    let new_counter = counter + incr_by;
+    if !decr_zero && new_counter < 0 { return None; }
+    Some(new_counter.max(0))
+}
+
+// The read head positions on a tape of length n that a move of move_by goes to read from
+// Moves off the end of the tape leave the read head on the endmarker, so an endmarker can be reached from several
+pub fn move_sources(read : i32, move_by : i32, n : i32) -> impl Iterator<Item = i32> {
+    // Clamping only ever shortens a move
+    let reach = move_by.abs();
+
+    ((read - reach).max(0)..=(read + reach).min(n - 1))
+        .filter(move |from| (from + move_by).max(0).min(n - 1) == read)
+}
+
 // Given a config, a determ transition off of it and an input string, find the next config
-pub fn next(config : Config, transition : autom::Transition, input : &Input, decr_zero : bool) -> Option<Config> {
+pub fn next(config : Config, transition : Action, input : &Tape, decr_zero : bool) -> Option<Config> {
     // Find the new readhead position
     let mut new_read = config.read + transition.move_by;
     new_read = new_read.max(0).min(input.len() as i32 - 1);
 
     // Find the new counter value
-    let mut new_counter = config.counter + transition.incr_by;
-    if !decr_zero && new_counter < 0 { return None; }
-    new_counter = new_counter.max(0);
+    let new_counter = next_counter(config.counter, transition.incr_by, decr_zero)?;
 
     // Return new config
     Some(Config {
@@ -58,15 +73,13 @@ pub fn next(config : Config, transition : autom::Transition, input : &Input, dec
 }
 
 // Given a config, a nondeterm transition off of it and an input string, find the next config
-pub fn next_nondeterm(config : Config, transition : autom::Transition, input : &Input, decr_zero : bool) -> Option<Config> {
+pub fn next_nondeterm(config : Config, transition : Action, input : &Tape, decr_zero : bool) -> Option<Config> {
     // Find the new readhead position
     let mut new_read = config.read + transition.move_by;
     new_read = new_read.max(0).min(input.len() as i32 - 1);
 
     // Find the new counter value
-    let mut new_counter = config.counter + transition.incr_by;
-    if !decr_zero && new_counter < 0 { return None; }
-    new_counter = new_counter.max(0);
+    let new_counter = next_counter(config.counter, transition.incr_by, decr_zero)?;
 
     // Return new config
     Some(Config {
@@ -76,86 +89,13 @@ pub fn next_nondeterm(config : Config, transition : autom::Transition, input : &
     })
 }
 
-// Get the legal nondeterministic transition off of an automaton if one exists
 // Get the transition that the automaton can take from the given configuration, if one exists
-pub fn get_transition(autom : &autom::Autom, config : Config, input : Input) -> Option<autom::Transition> {
-    // Get transitions from the automaton
-    let transitions = autom.get_transitions(config.state);
-
-    // Declare vector of legal transitions
-    let mut legal_transitions = Vec::new();
-
-    for trans in transitions {
-        match trans.condition {
-            // If this transition has a condition, 
-            // check that it's true before adding it to legal transitions
-            Some(ref cond) => {
-                // Find the character under the readhead
-                let read_char = input[config.read as usize];
-                
-                // Check the condition and push
-                if cond.check(read_char, config.counter) {
-                    legal_transitions.push(trans);
-                }
-            },
-
-            // Otherwise, add to legal transitions
-            None => legal_transitions.push(trans), 
-        }
-    }
-
-    // Check that all the potentially conflicting transitions do the same thing
-    // If this isn't the case, then the automaton is non-deterministic
-    if legal_transitions.len() > 1 {
-        // Get a tuple of actions executed by the first transition
-        let first_actions = (
-            legal_transitions[0].goto, 
-            legal_transitions[0].move_by, 
-            legal_transitions[0].incr_by
-        );
-
-        // Panic if any other legal transition doesn't do exactly the same thing
-        for trans in legal_transitions.iter().skip(1) {
-            if (trans.goto, trans.move_by, trans.incr_by) != first_actions {
-                panic!("From state {:?}, this automaton is nondeterministic!", config.state);
-            }
-        }
-    }
-
-    // Return based on number of legal transitions 
-    match legal_transitions.len() {
-        0 => None,
-        _ => Some(legal_transitions[0].clone()),
-    }
+// Fails if the automaton is nondeterministic from this configuration
+pub fn get_transition(autom : &CompiledAutom, config : Config, input : &Tape) -> Result<Option<Action>, Fault> {
+    autom.transition(config.state, input[config.read as usize], config.counter == 0)
 }
 
 // Get all the legal nondeterministic transitions off of a given config
-pub fn get_transitions(autom : &autom::Autom, config : Config, input : Input) -> Vec<autom::Transition> {
-    // Get transitions from the automaton
-    let transitions = autom.get_transitions(config.state);
-
-    // Declare vector of legal transitions
-    let mut legal_transitions = Vec::new();
-
-    for trans in transitions {
-        match trans.condition {
-            // If this transition has a condition, 
-            // check that it's true before adding it to legal transitions
-            Some(ref cond) => {
-                // Find the character under the readhead
-                let read_char = input[config.read as usize];
-                
-                // Check the condition and push
-                if cond.check(read_char, config.counter) {
-                    legal_transitions.push(trans);
-                }
-            },
-
-            // Otherwise, add to legal transitions
-            None => legal_transitions.push(trans), 
-        }
-    }
-
-    // Return
-    legal_transitions
-}
\ No newline at end of file
+pub fn get_transitions<'a>(autom : &'a CompiledAutom, config : Config, input : &Tape) -> &'a [Action] {
+    autom.transitions(config.state, input[config.read as usize], config.counter == 0)
+}
@@ -0,0 +1,184 @@
+// Explaining why a deterministic automaton rejects a word, by running it one step at a time
+
+use std::collections::VecDeque;
+use std::fmt;
+use hashbrown::{HashMap, HashSet};
+
+use crate::automaton::compiled::CompiledAutom;
+use crate::automaton::generic_autom::State;
+use crate::parser::ast::Readable;
+use crate::simulation::config::{Config, get_transition, next};
+use crate::simulation::budget::{Budget, Meter, Stop};
+
+// The reasons a deterministic run can reject
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cause {
+    // The run reached a reject statement
+    ExplicitReject,
+
+    // No transition is enabled, e.g. because the run fell off the end of the program
+    NoTransition,
+
+    // The only enabled transition decrements an empty counter, and decr_on_zero is false
+    DecrementAtZero,
+
+    // The run reached an accept state, but can't empty the counter from there
+    AcceptWithCounter,
+
+    // The run came back to a configuration it had already been in, or will keep repeating the same
+    // steps with a bigger and bigger counter
+    Loop,
+}
+
+impl fmt::Display for Cause {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Cause::ExplicitReject    => write!(f, "the run reached a reject statement"),
+            Cause::NoTransition      => write!(f, "no transition is enabled (e.g. the run fell off the end of the program)"),
+            Cause::DecrementAtZero   => write!(f, "the counter would be decremented below zero, and decr_on_zero is false"),
+            Cause::AcceptWithCounter => write!(f, "the run reached an accept state with a non-empty counter it can't empty"),
+            Cause::Loop              => write!(f, "the run loops forever"),
+        }
+    }
+}
+
+// A configuration along with the symbol under the read head
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub config : Config,
+    pub read : Readable,
+}
+
+// Why a run rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    // The configuration the run stopped in (or the first one it repeats, for loops)
+    pub last : Step,
+
+    // Why it stopped
+    pub cause : Cause,
+
+    // The statement responsible, if the automaton knows where its states came from
+    pub source : Option<String>,
+
+    // The steps leading up to the last configuration, oldest first and ending with it
+    pub steps : Vec<Step>,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Rejected in {}", describe(&self.last))?;
+        writeln!(f, "Cause: {}", self.cause)?;
+
+        if let Some(source) = &self.source {
+            writeln!(f, "Statement: {}", source)?;
+        }
+
+        writeln!(f, "Last {} steps:", self.steps.len())?;
+        for step in &self.steps {
+            writeln!(f, "  {}", describe(step))?;
+        }
+
+        Ok(())
+    }
+}
+
+// A configuration as a line of text
+fn describe(step : &Step) -> String {
+    let read = match step.read {
+        Readable::LEnd() => "lend".to_string(),
+        Readable::REnd() => "rend".to_string(),
+        Readable::Char(c) => format!("{:?}", c),
+    };
+
+    format!("state {}, reading {} at index {}, with counter {}", step.config.state, read, step.config.read, step.config.counter)
+}
+
+// Run a deterministic automaton on a word and explain why it rejects, keeping the last history steps.
+// Returns None if the word is accepted, and fails if the automaton is nondeterministic from a configuration
+// the run reaches
+pub fn explain(autom : &CompiledAutom, input : &str, history : usize) -> Result<Option<Explanation>, Stop> {
+    explain_with_budget(autom, input, history, &Budget::default())
+}
+
+// Same as explain, but gives up once the budget runs out, or if the automaton is nondeterministic
+pub fn explain_with_budget(autom : &CompiledAutom, input : &str, history : usize, budget : &Budget) -> Result<Option<Explanation>, Stop> {
+    let tape = autom.encode(input);
+    let readables = Readable::from_input_str(input);
+    let step_at = |config : Config| Step { config, read : readables[config.read as usize] };
+
+    let mut meter = Meter::new(budget);
+    let mut visited : HashSet<Config> = HashSet::new();
+    let mut steps : VecDeque<Step> = VecDeque::new();
+
+    // The last time the run was at each (state, read head index), with the counter it had then
+    let mut last_visits : HashMap<(State, i32), (u64, i32)> = HashMap::new();
+
+    // The last time the counter was empty
+    let mut last_zero = 0;
+
+    // Automaton always starts from 0, read==lend and c==0
+    let mut cfg = Config { state : 0, read : 0, counter : 0 };
+    let mut time : u64 = 0;
+
+    let explanation = |cfg : Config, cause : Cause, source : Option<&str>, steps : &VecDeque<Step>| Some(Explanation {
+        last : step_at(cfg),
+        cause,
+        source : source.map(|s| s.to_string()),
+        steps : steps.iter().copied().collect(),
+    });
+
+    loop {
+        if history > 0 {
+            if steps.len() == history { steps.pop_front(); }
+            steps.push_back(step_at(cfg));
+        }
+
+        // A deterministic run that gets back to the same config loops forever
+        if !visited.insert(cfg) {
+            return Ok(explanation(cfg, Cause::Loop, autom.source(cfg.state), &steps));
+        }
+        meter.check_configs(visited.len())?;
+
+        // So does one that gets back to the same state and read head index with a bigger counter,
+        // if the counter hasn't been empty since: the steps in between can be taken again and again
+        if cfg.counter == 0 {
+            last_zero = time;
+        } else if let Some((then, counter)) = last_visits.insert((cfg.state, cfg.read), (time, cfg.counter)) {
+            if then > last_zero && counter < cfg.counter {
+                return Ok(explanation(cfg, Cause::Loop, autom.source(cfg.state), &steps));
+            }
+        }
+
+        // Check if this state halts
+        // Accept states empty the counter before halting
+        let halting = autom.check_if_halting(cfg.state);
+        match halting {
+            Some(false) => return Ok(explanation(cfg, Cause::ExplicitReject, autom.source(cfg.state), &steps)),
+            Some(true) if cfg.counter == 0 => return Ok(None),
+            _ => (),
+        }
+
+        let trans = match get_transition(autom, cfg, &tape)? {
+            Some(t) => t,
+
+            None => {
+                let cause = match halting {
+                    Some(true) => Cause::AcceptWithCounter,
+                    _ => Cause::NoTransition,
+                };
+
+                return Ok(explanation(cfg, cause, autom.source(cfg.state), &steps));
+            },
+        };
+
+        // The transition was introduced for the statement whose state it goes to
+        cfg = match next(cfg, trans, &tape, autom.decr_zero) {
+            None => return Ok(explanation(cfg, Cause::DecrementAtZero, autom.source(trans.goto), &steps)),
+            Some(c) => c,
+        };
+
+        meter.step()?;
+        time += 1;
+    }
+}
@@ -1,197 +1,250 @@
 // Glueck's 2dc simulation algorithm using a hashmap to store terminators
 
-use hashbrown::HashMap;
-//use hashbrown::HashSet;
+use hashbrown::{HashMap, HashSet};
 
-use crate::automaton::autom::Autom;
+use crate::automaton::compiled::{CompiledAutom, Tape};
+use crate::simulation::stats::{RunStats, StepTracker};
+use crate::simulation::budget::{Budget, Fault, Meter, Outcome, Stop};
 use crate::simulation::config::{Config, DeltaConfig, StrippedConfig, strip_config, make_delta_config, next, get_transition};
-use crate::parser::ast::{Readable, Input};
 
 // Check if a string is accepted by a deterministic automaton using the glueck procedure
 // This should run in time O(|input|)
 // See https://arxiv.org/pdf/1309.5142.pdf for more info
-pub fn glueck_procedure<'a>(autom : &'a Autom, input : &str) -> bool {
-    // Convert the input into a list of Readables
-    let readable_input = Readable::from_input_str(input);
+pub fn glueck_procedure(autom : &CompiledAutom, input : &str) -> bool {
+    glueck_procedure_with_stats(autom, input).0
+}
+
+// Same as glueck_procedure, but also reports what the simulator did
+pub fn glueck_procedure_with_stats(autom : &CompiledAutom, input : &str) -> (bool, RunStats) {
+    let (outcome, stats) = glueck_procedure_with_budget(autom, input, &Budget::default());
+    (outcome.accepted(), stats)
+}
+
+// Same as glueck_procedure_with_stats, but gives up once the budget runs out
+pub fn glueck_procedure_with_budget(autom : &CompiledAutom, input : &str, budget : &Budget) -> (Outcome, RunStats) {
+    // Convert the input into a tape of the automaton's symbols
+    let tape = autom.encode(input);
 
     // Get the starting configuration of the automaton
     // Automaton always starts in state zero from lend with c=0
     let start_config = Config { state : 0, read : 0, counter : 0 };
-    
+
     // Declare the GlueckSimulator object
-    let mut simulator = GlueckSimulator::new(autom, readable_input);
+    let mut simulator = GlueckSimulator::new(autom, tape, budget);
 
     // Run the simulator to find the terminator of this config
-    let final_config = simulator.simulate(start_config);
-
-    // Return based on the final config
-    match autom.check_if_halting(final_config.state) {
+    // If there isn't one then the automaton loops forever
+    let result = simulator.simulate(start_config).map(|terminator| match terminator {
         None => false,
-        Some(accept) => accept,
-    }
+        Some(final_config) => autom.check_if_halting(final_config.state).unwrap_or_default(),
+    });
+
+    (Outcome::from(result), simulator.stats())
+}
+
+// What the procedure has to do to find the terminator of a configuration
+enum Step {
+    // The configuration is its own terminator
+    Terminator(Config),
+
+    // op(config), i.e. the terminator is that of the next configuration
+    Op(Config),
+
+    // push(config), i.e. the terminator of the next configuration has to be popped from first
+    Push(Config),
+}
+
+// Frames of the explicit work stack that replaces the recursion of the procedure
+enum Frame {
+    // This config has the same terminator as the one being simulated
+    Op(Config),
+
+    // This config pushed to the one being simulated, and continues from its terminator
+    Push(Config),
 }
 
 // Struct to hold variables for the Glueck procedure
 struct GlueckSimulator<'a> {
     // Automaton being simulated
-    autom : &'a Autom,
+    autom : &'a CompiledAutom,
 
-    // Input being simulated on
-    input : Input,
+    // Tape being simulated on
+    input : Tape,
 
     // Table that stores the previously computed terminators
     config_table : HashMap<StrippedConfig, DeltaConfig>,
 
-    // Past configurations
-    past_configs : Vec<StrippedConfig>,
+    // The configurations that are currently on the work stack
+    on_stack : HashSet<StrippedConfig>,
+
+    // The configurations that have been simulated from
+    visited : HashSet<StrippedConfig>,
+
+    // Statistics about the transitions followed
+    tracker : StepTracker,
+
+    // The steps and configs taken so far against the budget
+    meter : Meter,
 }
 
 impl<'a> GlueckSimulator<'a> {
     // Constructor
-    pub fn new(autom : &'a Autom, input : Input) -> Self {
-        Self { 
-            config_table : HashMap::new(), 
+    pub fn new(autom : &'a CompiledAutom, input : Tape, budget : &Budget) -> Self {
+        Self {
+            config_table : HashMap::new(),
             autom,
             input,
-            past_configs : Vec::new(),
+            on_stack : HashSet::new(),
+            visited : HashSet::new(),
+            tracker : StepTracker::default(),
+            meter : Meter::new(budget),
         }
     }
 
-    // Find the terminator of a given configuration
-    pub fn simulate(&mut self, config : Config) -> Config {
-        //println!("{:?}", config);
+    // Find the terminator of a given configuration, or None if the automaton loops forever from it
+    // Uses an explicit stack instead of recursing, so long inputs can't overflow the native stack
+    pub fn simulate(&mut self, config : Config) -> Result<Option<Config>, Stop> {
+        // Stack of configs that are waiting on the terminator of the current config
+        let mut stack : Vec<Frame> = Vec::new();
 
-        let stripped_config = strip_config(config);
+        let mut current = config;
 
-        // Check for infinite loops
-        // If we loop infinitely, return the starting config
-        if self.past_configs.contains(&stripped_config) {
-            return Config { state : 0, read : 0, counter : 0 };
-        }
+        loop {
+            let stripped_config = strip_config(current);
+            self.visited.insert(stripped_config);
+            self.meter.check_configs(self.visited.len())?;
+
+            // Check for infinite loops
+            // A deterministic automaton that gets back to a config it's still simulating never halts
+            if self.on_stack.contains(&stripped_config) {
+                return Ok(None);
+            }
 
-        // Record config in past configs stack
-        self.past_configs.push(stripped_config);
-
-        // Check if we've seen this configuration before
-        if let Some(delta_config) = self.config_table.get(&stripped_config) {
-            // Calculate the new config and return
-            self.past_configs.pop();
-            return Config {
-                state : delta_config.state,
-                read : delta_config.read,
-                counter : config.counter + delta_config.counter,
+            // Find the terminator of the current config if we can do so without descending
+            let terminator = match self.config_table.get(&stripped_config).copied() {
+                // Check if we've seen this configuration before
+                Some(delta_config) => Config {
+                    state : delta_config.state,
+                    read : delta_config.read,
+                    counter : current.counter + delta_config.counter,
+                },
+
+                None => match self.step(current)? {
+                    Step::Terminator(c) => c,
+
+                    Step::Op(next_config) => {
+                        self.meter.step()?;
+                        self.tracker.step(current, next_config);
+                        self.on_stack.insert(stripped_config);
+                        stack.push(Frame::Op(current));
+                        current = next_config;
+                        continue;
+                    },
+
+                    Step::Push(next_config) => {
+                        self.meter.step()?;
+                        self.tracker.step(current, next_config);
+                        self.on_stack.insert(stripped_config);
+                        stack.push(Frame::Push(current));
+                        current = next_config;
+                        continue;
+                    },
+                },
             };
+
+            // Unwind the stack until some config has to carry on after a pop
+            loop {
+                match stack.pop() {
+                    // The original config has found its terminator
+                    None => return Ok(Some(terminator)),
+
+                    // Memoize the terminators of configs that were waiting on this one
+                    Some(Frame::Op(cfg)) => self.memoize(cfg, terminator),
+
+                    Some(Frame::Push(cfg)) => {
+                        // Find the configuration following the terminator
+                        let follow = get_transition(self.autom, terminator, &self.input)?
+                            .and_then(|trans| next(terminator, trans, &self.input, self.autom.decr_zero));
+
+                        match follow {
+                            // If the terminator halts then so does the whole computation
+                            None => return Ok(Some(terminator)),
+
+                            // Otherwise cfg has the same terminator as the config after the pop
+                            Some(c) => {
+                                self.meter.step()?;
+                                self.tracker.step(terminator, c);
+                                stack.push(Frame::Op(cfg));
+                                current = c;
+                                break;
+                            },
+                        }
+                    },
+                }
+            }
         }
+    }
+
+    // Statistics about the simulation so far
+    pub fn stats(&self) -> RunStats {
+        let mut stats = RunStats {
+            distinct_configs : Some(self.visited.len()),
+            table_entries : Some(self.config_table.len()),
+            ..RunStats::default()
+        };
+
+        self.tracker.finish(&mut stats);
+        stats
+    }
 
+    // Work out what to do with a config whose terminator isn't in the table
+    // Fails if the automaton is nondeterministic from the config
+    fn step(&self, config : Config) -> Result<Step, Fault> {
         // Check if this configuration is halting
         if let Some(accepting) = self.autom.check_if_halting(config.state) {
             // Return if we are in a reject state or in an accept state with an empty counter
-            if !accepting || (accepting && config.counter == 0) { 
-                self.past_configs.pop();
-                return config; 
-            } 
+            if !accepting || config.counter == 0 {
+                return Ok(Step::Terminator(config));
+            }
         }
 
         // Find the legal transition from this config if one exists
-        let trans = match get_transition(self.autom, config, self.input.clone()) {
+        let trans = match get_transition(self.autom, config, &self.input)? {
             // If no such transition exists, then the automaton halts and rejects on this config
-            None => {
-                self.past_configs.pop(); 
-                return config;
-            },
+            None => return Ok(Step::Terminator(config)),
 
             // If such a transition exists, save it in trans
             Some(t) => t,
-        };  
-
-        // Variable to hold the value the procedure should output
-        let out;
+        };
 
-        // Check if this transition is decrementing
+        // Check if this transition is decrementing a non-empty counter
         // i.e. pop(config)
-        if trans.incr_by < 0 { 
-            // We've found the terminator (yaaaayyyyyyyyyyyy!!!!!!!!!)
-            out = config;
+        if trans.incr_by < 0 && config.counter > 0 {
+            return Ok(Step::Terminator(config));
         }
-        
-        // Check if this transition is incrementing
-        // i.e. push(config)
-        else if trans.incr_by > 0 {  
-            // Record the current value of the counter
-            //let start_counter_val = config.counter;
-
-            // Find the next configuration
-            let next_config = match next(
-                config, 
-                trans.clone(), 
-                &self.input,
-                self.autom.decr_zero
-            ) {
-                None => return config,
-                Some(c) => c,
-            };
 
-            // Find the terminator of the next configuration
-            let next_terminator = self.simulate(next_config);
-
-            // Find the legal transition off of the next terminator if one exists
-            let next_terminator_trans = match get_transition(
-                self.autom, 
-                next_terminator, 
-                self.input.clone()
-            ) {
-                None => return next_terminator,
-                Some(t) => t,
-            };  
-
-            
-            // Find the configuration following the last terminator
-            let follow = match next(
-                next_terminator, 
-                next_terminator_trans,
-                &self.input,
-                self.autom.decr_zero
-            ) {
-                None => return next_terminator,
-                Some(c) => c,
-            };
-
-            // Recurse
-            out = self.simulate(follow);
+        // Find the next configuration (popping an empty counter either blocks or acts as an op)
+        let next_config = match next(config, trans, &self.input, self.autom.decr_zero) {
+            None => return Ok(Step::Terminator(config)),
+            Some(c) => c,
+        };
 
-            /*
-            if out.counter != start_counter_val {
-                println!("Counter not the same");
-            }
-            */
+        // Check if this transition is incrementing
+        // i.e. push(config)
+        if trans.incr_by > 0 {
+            Ok(Step::Push(next_config))
+        }
 
-            //print!("push: {:?}, push_to : {:?}\nnext_term: {:?}\nfollow: {:?}", config, next_config, next_terminator, follow);
-            //println!("\nout: {:?}\n", out);
-        } 
-        
         // op(config)
         else {
-            // Find the next configuration
-            let next_config = match next(
-                config, 
-                trans.clone(), 
-                &self.input,
-                self.autom.decr_zero
-            ) {
-                None => return config,
-                Some(c) => c,
-            };
-
-            // Recurse
-            out = self.simulate(next_config);
+            Ok(Step::Op(next_config))
         }
+    }
 
-        // Memoize
-        let map_config = make_delta_config(config, out);
-        self.config_table.insert(stripped_config, map_config);
-
-        // Return
-        self.past_configs.pop();
-        out
+    // Record the terminator of a config and take it off the stack
+    fn memoize(&mut self, config : Config, terminator : Config) {
+        let stripped_config = strip_config(config);
+        self.config_table.insert(stripped_config, make_delta_config(config, terminator));
+        self.on_stack.remove(&stripped_config);
     }
 }
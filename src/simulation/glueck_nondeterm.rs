@@ -1,193 +1,245 @@
-// Glueck's 2nc simulation algorithm using a hashmap to store terminators
+// Glueck's 2nc simulation algorithm, computing the terminator sets as a least fixpoint
 
 use hashbrown::{HashMap, HashSet};
 
-use crate::automaton::autom::Autom;
-use crate::simulation::config::{Config, DeltaConfig, StrippedConfig, strip_config, make_delta_config, next_nondeterm, get_transitions};
-use crate::parser::ast::{Readable, Input};
+use crate::automaton::compiled::{Action, CompiledAutom, Tape};
+use crate::automaton::generic_autom::State;
+use crate::simulation::config::{Config, StrippedConfig, next_nondeterm, get_transitions};
+use crate::simulation::stats::RunStats;
+use crate::simulation::budget::{Budget, Exceeded, Meter, Outcome};
 
-// Check if a string is accepted by a deterministic automaton using the glueck procedure
-// This should run in time O(|input|)
+// A terminator of a stripped config. Terminators always sit at the same counter level
+// as the config they belong to, so the counter delta is always zero and can be dropped
+type Terminator = (State, i32);
+
+// Check if a string is accepted by a nondeterministic automaton using the glueck procedure
+// The terminator sets are computed as the least fixpoint of the equations Glueck's recursion
+// describes, so cycles between configurations can't cut off any terminators
 // See https://arxiv.org/pdf/1309.5142.pdf for more info
-pub fn glueck_procedure<'a>(autom : &'a Autom, input : &str) -> bool {
-    // Convert the input into a list of Readables
-    let readable_input = Readable::from_input_str(input);
+pub fn glueck_procedure(autom : &CompiledAutom, input : &str) -> bool {
+    glueck_procedure_with_stats(autom, input).0
+}
+
+// Same as glueck_procedure, but also reports what the simulator did
+pub fn glueck_procedure_with_stats(autom : &CompiledAutom, input : &str) -> (bool, RunStats) {
+    let (outcome, stats) = glueck_procedure_with_budget(autom, input, &Budget::default());
+    (outcome.accepted(), stats)
+}
+
+// Same as glueck_procedure_with_stats, but gives up once the budget runs out
+pub fn glueck_procedure_with_budget(autom : &CompiledAutom, input : &str, budget : &Budget) -> (Outcome, RunStats) {
+    // Convert the input into a tape of the automaton's symbols
+    let tape = autom.encode(input);
 
     // Get the starting configuration of the automaton
     // Automaton always starts in state zero from lend with c=0
-    let start_config = Config { state : 0, read : 0, counter : 0 };
-    
+    let start_config = (0, 0, true);
+
     // Declare the GlueckSimulator object
-    let mut simulator = GlueckSimulator::new(autom, readable_input);
+    let mut simulator = GlueckSimulator::new(autom, tape, budget);
 
-    // Run the simulator to find the terminator of this config
-    let final_configs = simulator.simulate(start_config);
+    // Run the simulator to find the terminators of this config
+    // The only terminators of a config with an empty counter are accepting ones
+    let result = simulator.simulate(start_config).map(|final_configs| !final_configs.is_empty());
 
-    for final_config in final_configs {
-        // Return based on the final config
-        if let Some(a) = autom.check_if_halting(final_config.state) {
-            if a { return true; }
-        }
-    }
-    
-    false
+    (Outcome::from(result), simulator.stats())
+}
+
+// Ways in which the terminators of one config feed into those of another
+#[derive(Debug, Clone, Copy)]
+enum Dependent {
+    // The dependent config reaches this config without changing the counter,
+    // so every terminator of this config is also a terminator of the dependent
+    Op(StrippedConfig),
+
+    // The dependent config reaches this config by pushing,
+    // so the dependent continues from wherever this config's terminators pop to
+    Push(StrippedConfig),
 }
 
 // Struct to hold variables for the Glueck procedure
 struct GlueckSimulator<'a> {
-    // Table that stores the previously computed terminators
-    config_table : HashMap<StrippedConfig, Vec<DeltaConfig>>,
-
     // Automaton being simulated
-    autom : &'a Autom,
+    autom : &'a CompiledAutom,
+
+    // Tape being simulated on
+    input : Tape,
 
-    // Input being simulated on
-    input : Input,
+    // Table that stores the terminators found so far for each config that's been reached
+    config_table : HashMap<StrippedConfig, HashSet<Terminator>>,
 
-    // Stack of past configurations
-    past_configs : HashSet<StrippedConfig>,
+    // The configs whose terminators depend on the terminators of a given config
+    dependents : HashMap<StrippedConfig, Vec<Dependent>>,
+
+    // Configs that have been reached but whose transitions haven't been explored yet
+    unexplored : Vec<StrippedConfig>,
+
+    // Newly found terminators that haven't been passed on to their dependents yet
+    worklist : Vec<(StrippedConfig, Terminator)>,
+
+    // The steps and configs taken so far against the budget
+    meter : Meter,
 }
 
 impl<'a> GlueckSimulator<'a> {
     // Constructor
-    pub fn new(autom : &'a Autom, input : Input) -> Self {
-        Self { 
-            config_table : HashMap::new(), 
+    pub fn new(autom : &'a CompiledAutom, input : Tape, budget : &Budget) -> Self {
+        Self {
             autom,
             input,
-            past_configs : HashSet::new(),
+            config_table : HashMap::new(),
+            dependents : HashMap::new(),
+            unexplored : Vec::new(),
+            worklist : Vec::new(),
+            meter : Meter::new(budget),
         }
     }
 
-    // Find the terminator of a given configuration
-    pub fn simulate(&mut self, config : Config) -> Vec<Config> {    
-        let stripped_config = strip_config(config);
+    // Find the terminators of a given configuration
+    // Terminators are the configs at the same counter level that can either pop or halt and accept
+    pub fn simulate(&mut self, config : StrippedConfig) -> Result<HashSet<Terminator>, Exceeded> {
+        self.reach(config);
 
-        // Check for infinite loops
-        // If we loop infinitely, return nothing
-        if self.past_configs.contains(&stripped_config) {
-            return vec![];
-        }
+        // Iterate until the table stabilises
+        // Each (config, terminator) pair is only ever added to the worklist once, so this terminates
+        loop {
+            // Each step only reaches a handful of new configs, so the table is checked once per step
+            self.meter.step()?;
+            self.meter.check_configs(self.config_table.len())?;
 
-        // Record config in past configs stack
-        self.past_configs.insert(stripped_config);
-
-        // Check if we've seen this configuration before
-        if let Some(delta_configs) = self.config_table.get(&stripped_config) {
-            // Calculate the new configs and return
-            let mut new_configs = Vec::new();
+            if let Some(cfg) = self.unexplored.pop() {
+                self.explore(cfg);
+            }
 
-            for delta_config in delta_configs {
-                new_configs.push(Config {
-                    state : delta_config.state,
-                    read : delta_config.read,
-                    counter : config.counter + delta_config.counter,
-                });
+            else if let Some((cfg, terminator)) = self.worklist.pop() {
+                let dependents = self.dependents.get(&cfg).cloned().unwrap_or_default();
+                for dependent in dependents {
+                    self.propagate(dependent, terminator);
+                }
             }
 
-            return new_configs;
+            else { break; }
         }
 
-        // Check if this configuration is halting
-        if let Some(accepting) = self.autom.check_if_halting(config.state) {
-            // Return if we are in a reject state or in an accept state with an empty counter
-            if !accepting || (accepting && config.counter == 0) { 
-                return vec![config]; 
-            } 
+        Ok(self.config_table.get(&config).cloned().unwrap_or_default())
+    }
+
+    // Statistics about the simulation so far
+    pub fn stats(&self) -> RunStats {
+        RunStats {
+            distinct_configs : Some(self.config_table.len()),
+            table_entries : Some(self.config_table.values().map(|terminators| terminators.len()).sum()),
+            ..RunStats::default()
         }
+    }
 
-        // Find the legal transition from this config if one exists
-        let transes = get_transitions(self.autom, config, self.input.clone());
-        if transes.len() == 0 { 
-            return vec![config]; 
-        }  
+    // Record that a config has been reached, scheduling it to be explored if it's new
+    fn reach(&mut self, config : StrippedConfig) {
+        if !self.config_table.contains_key(&config) {
+            self.config_table.insert(config, HashSet::new());
+            self.unexplored.push(config);
+        }
+    }
 
-        // Variable to hold the value the procedure should output
-        let mut outs = Vec::new();
+    // Add a terminator to a config's terminator set, scheduling it to be propagated if it's new
+    fn add_terminator(&mut self, config : StrippedConfig, terminator : Terminator) {
+        let terminators = self.config_table.get_mut(&config).unwrap();
 
-        for trans in transes {
-            let mut out = Vec::new() ;
+        if terminators.insert(terminator) {
+            self.worklist.push((config, terminator));
+        }
+    }
+
+    // Make dependent depend on the terminators of config, including the ones already found
+    fn add_dependent(&mut self, config : StrippedConfig, dependent : Dependent) {
+        self.reach(config);
+
+        self.dependents.entry(config).or_default().push(dependent);
+
+        let terminators : Vec<Terminator> = self.config_table.get(&config).unwrap().iter().copied().collect();
+        for terminator in terminators {
+            self.propagate(dependent, terminator);
+        }
+    }
 
-            // Check if this transition is decrementing
-            // i.e. pop(config)
-            if trans.incr_by < 0 { 
-                // We've found the terminator (yaaaayyyyyyyyyyyy!!!!!!!!!)
-                out.push(config);
+    // Pass a terminator on to one of the configs that depends on it
+    fn propagate(&mut self, dependent : Dependent, terminator : Terminator) {
+        match dependent {
+            // Terminators of op(config) are terminators of config
+            Dependent::Op(cfg) => self.add_terminator(cfg, terminator),
+
+            // Terminators of push(config) pop back to config's counter level,
+            // and the computation carries on from there
+            Dependent::Push(cfg) => {
+                let (state, read) = terminator;
+                let popping_config = Config { state, read, counter : 1 };
+
+                for trans in self.popping_transitions(popping_config) {
+                    let follow = match next_nondeterm(popping_config, trans, &self.input, self.autom.decr_zero) {
+                        None => continue,
+                        Some(c) => (c.state, c.read, cfg.2),
+                    };
+
+                    self.add_dependent(follow, Dependent::Op(cfg));
+                }
+            },
+        }
+    }
+
+    // Find the dependencies of a config on the configs it can move to
+    fn explore(&mut self, stripped_config : StrippedConfig) {
+        let (state, read, counter_zero) = stripped_config;
+        let config = Config { state, read, counter : if counter_zero {0} else {1} };
+
+        // Check if this configuration is halting
+        if let Some(accepting) = self.autom.check_if_halting(state) {
+            // Accept states with an empty counter are terminators, reject states have none
+            if !accepting || counter_zero {
+                if accepting { self.add_terminator(stripped_config, (state, read)); }
+                return;
             }
-            
-            // Check if this transition is incrementing
-            // i.e. push(config)
-            else if trans.incr_by > 0 { 
-                // Find the next configuration
-                let next_config = match next_nondeterm(
-                    config, 
-                    trans.clone(), 
-                    &self.input,
-                    self.autom.decr_zero
-                ) {
-                    None => continue,
-                    Some(c) => c,
-                };
-
-                // Find the terminator of the next configuration
-                let next_terminators = self.simulate(next_config);
-
-                let mut follow = Vec::new();
-
-                for next_terminator in next_terminators {
-                    // Find the legal transitions off of the next terminator if one exists
-                    let next_terminator_transes = get_transitions(self.autom, next_terminator, self.input.clone());  
-                    
-                    for next_terminator_trans in next_terminator_transes {
-                        // Find the configuration following the last terminator
-                        match next_nondeterm(
-                            next_terminator, 
-                            next_terminator_trans,
-                            &self.input,
-                            self.autom.decr_zero
-                        ) {
-                            None => continue,
-                            Some(c) => follow.push(c),
-                        };
-                    }
+        }
+
+        for &trans in get_transitions(self.autom, config, &self.input) {
+            // Find the next configuration
+            let next_config = match next_nondeterm(config, trans, &self.input, self.autom.decr_zero) {
+                None => continue,
+                Some(c) => c,
+            };
+
+            let next_stripped = (next_config.state, next_config.read, counter_zero && next_config.counter == 0);
+
+            // pop(config)
+            if trans.incr_by < 0 {
+                // Popping from an empty counter leaves it empty, so this is really just an op
+                if counter_zero {
+                    self.add_dependent(next_stripped, Dependent::Op(stripped_config));
                 }
 
-                // Recurse
-                for cfg in follow {
-                    let mut new_cfgs = self.simulate(cfg);
-                    out.append(&mut new_cfgs);
+                // Otherwise config is one of its own terminators
+                else {
+                    self.add_terminator(stripped_config, (state, read));
                 }
-            } 
-            
-            // op(config)
-            else {
-                // Find the next configuration
-                let next_config = match next_nondeterm(
-                    config, 
-                    trans.clone(), 
-                    &self.input,
-                    self.autom.decr_zero
-                ) {
-                    None => continue,
-                    Some(c) => c,
-                };
-
-                // Recurse
-                out.append(&mut self.simulate(next_config));
             }
 
-            outs.append(&mut out);
-        }
+            // push(config)
+            else if trans.incr_by > 0 {
+                self.add_dependent((next_config.state, next_config.read, false), Dependent::Push(stripped_config));
+            }
 
-        // Memoize
-        let mut new_delta_configs = Vec::new();
-        for out in &outs {
-            new_delta_configs.push(make_delta_config(config, *out));
+            // op(config)
+            else {
+                self.add_dependent(next_stripped, Dependent::Op(stripped_config));
+            }
         }
-        self.config_table.insert(stripped_config, new_delta_configs);
+    }
 
-        // Return
-        self.past_configs.remove(&stripped_config);
-        outs
+    // Get the transitions that pop off of a config with a non-empty counter
+    fn popping_transitions(&self, config : Config) -> Vec<Action> {
+        get_transitions(self.autom, config, &self.input)
+            .iter()
+            .filter(|trans| trans.incr_by < 0)
+            .copied()
+            .collect()
     }
 }
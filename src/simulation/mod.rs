@@ -15,3 +15,21 @@ pub mod naive_determ;
 pub mod rytter;
 
 pub mod rytter_matrix;
+
+pub mod rytter_reachable;
+
+pub mod bit_matrix;
+
+pub mod rytter_parallel;
+
+pub mod parallel;
+
+pub mod streaming;
+
+pub mod stats;
+
+pub mod budget;
+
+pub mod explain;
+
+pub mod algorithm;
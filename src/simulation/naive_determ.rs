@@ -1,61 +1,126 @@
 // Naive 2dc simulation algorithm
 
-use crate::automaton::autom::Autom;
+use hashbrown::HashSet;
+
+use crate::automaton::compiled::{CompiledAutom, Tape};
 use crate::simulation::config::{Config, get_transition, next};
-use crate::parser::ast::{Readable, Input};
+use crate::simulation::stats::{RunStats, StepTracker};
+use crate::simulation::budget::{Budget, Meter, Outcome, Stop};
+
+pub fn naive(autom : &CompiledAutom, input : &str) -> bool {
+    naive_with_options(autom, input, &Budget::default(), false).0.accepted()
+}
+
+// Same as naive, but also reports what the simulator did, including the distinct configs it went through
+pub fn naive_with_stats(autom : &CompiledAutom, input : &str) -> (bool, RunStats) {
+    let (outcome, stats) = naive_with_options(autom, input, &Budget::default(), true);
+    (outcome.accepted(), stats)
+}
+
+// Same as naive, but gives up once the budget runs out
+// The run only ever holds a couple of configs, so it doesn't count distinct configs and max_configs never runs out
+pub fn naive_with_budget(autom : &CompiledAutom, input : &str, budget : &Budget) -> (Outcome, RunStats) {
+    naive_with_options(autom, input, budget, false)
+}
 
-pub fn naive<'a>(autom : &'a Autom, input : &str) -> bool {
-    // Convert the input string into a list of readables 
-    let readable_input = Readable::from_input_str(input);
+// Same as naive_with_budget, but also counts the distinct configs the run goes through if count_configs is set,
+// which takes memory linear in the number of steps
+pub fn naive_with_options(autom : &CompiledAutom, input : &str, budget : &Budget, count_configs : bool) -> (Outcome, RunStats) {
+    // Convert the input string into a tape of the automaton's symbols
+    let tape = autom.encode(input);
 
-    // Run the simulator 
-    let mut simulator = NaiveSimulator::new(autom, readable_input);
-    simulator.run()
+    // Run the simulator
+    let mut simulator = NaiveSimulator::new(autom, tape, budget, count_configs);
+    let outcome = Outcome::from(simulator.run());
+
+    let mut stats = RunStats { distinct_configs : simulator.visited.as_ref().map(|visited| visited.len()), ..RunStats::default() };
+    simulator.tracker.finish(&mut stats);
+
+    (outcome, stats)
 }
 
 struct NaiveSimulator<'a> {
     // The automaton being simulated
-    autom : &'a Autom,
+    autom : &'a CompiledAutom,
 
     // The input string
-    input : Input,
+    input : Tape,
+
+    // The configurations the run has been through, if they're being counted
+    visited : Option<HashSet<Config>>,
+
+    // Statistics about the transitions followed
+    tracker : StepTracker,
+
+    // The steps taken so far against the budget
+    meter : Meter,
 }
 
 impl<'a> NaiveSimulator<'a> {
-    pub fn new(autom : &'a Autom, input : Input) -> Self {
-        Self { autom, input }
+    pub fn new(autom : &'a CompiledAutom, input : Tape, budget : &Budget, count_configs : bool) -> Self {
+        Self {
+            autom,
+            input,
+            visited : count_configs.then(HashSet::new),
+            tracker : StepTracker::default(),
+            meter : Meter::new(budget),
+        }
     }
 
-    pub fn run(&mut self) -> bool {
-        // Automaton always starts from 0, read==lend and c==0 
+    pub fn run(&mut self) -> Result<bool, Stop> {
+        // Automaton always starts from 0, read==lend and c==0
         let mut cfg = Config { state : 0, read : 0, counter : 0 };
 
+        // A deterministic run that gets back to the same config loops forever
+        // Brent's cycle detection finds that in constant memory: checkpoint is moved up to the current config
+        // whenever the steps since it was last moved reach a power of two, so a loop eventually runs into it
+        let mut checkpoint = cfg;
+        let mut since_checkpoint : u64 = 0;
+        let mut power : u64 = 1;
+
         loop {
-            let trans = match get_transition(self.autom, cfg, self.input.clone()) {
+            if let Some(visited) = &mut self.visited {
+                visited.insert(cfg);
+            }
+
+            // Check if this state halts; if so return
+            // Accept states empty the counter before halting
+            if let Some(accepting) = self.autom.check_if_halting(cfg.state) {
+                if !accepting || cfg.counter == 0 {
+                    return Ok(accepting);
+                }
+            }
+
+            let trans = match get_transition(self.autom, cfg, &self.input)? {
                 Some(t) => t,
-                None => return false,
+                None => return Ok(false),
             };
 
-            
             let next_cfg = match next(
-                cfg, 
-                trans, 
+                cfg,
+                trans,
                 &self.input,
                 self.autom.decr_zero
             ) {
-                None => return false,
+                None => return Ok(false),
                 Some(c) => c,
             };
 
+            // Continue from the next config
+            self.meter.step()?;
+            self.tracker.step(cfg, next_cfg);
+            cfg = next_cfg;
 
-           
-            // Check if this state halts; if so return
-            if let Some(accepting) = self.autom.check_if_halting(cfg.state) {
-                return accepting;
+            if cfg == checkpoint {
+                return Ok(false);
             }
 
-            // Continue from the next config
-            cfg = next_cfg;
+            since_checkpoint += 1;
+            if since_checkpoint == power {
+                checkpoint = cfg;
+                since_checkpoint = 0;
+                power *= 2;
+            }
         }
     }
-}
\ No newline at end of file
+}
@@ -1,86 +1,123 @@
 // Naive 2nc simulation algorithm
 
-use crate::automaton::autom::Autom;
+use std::collections::VecDeque;
+use hashbrown::HashSet;
+
+use crate::automaton::compiled::{CompiledAutom, Tape};
 use crate::simulation::config::{Config, get_transitions, next_nondeterm};
-use crate::parser::ast::{Readable, Input};
+use crate::simulation::stats::RunStats;
+use crate::simulation::budget::{Budget, Exceeded, Meter, Outcome};
+
+// Check if a string is accepted by a nondeterministic automaton by searching every configuration
+// it can reach. This is slow, but simple enough to be used as a reference for the other procedures
+pub fn naive(autom : &CompiledAutom, input : &str) -> bool {
+    naive_with_stats(autom, input).0
+}
+
+// Same as naive, but also reports what the simulator did
+pub fn naive_with_stats(autom : &CompiledAutom, input : &str) -> (bool, RunStats) {
+    let (outcome, stats) = naive_with_budget(autom, input, &Budget::default());
+    (outcome.accepted(), stats)
+}
+
+// Same as naive_with_stats, but gives up once the budget runs out
+pub fn naive_with_budget(autom : &CompiledAutom, input : &str, budget : &Budget) -> (Outcome, RunStats) {
+    // Convert the input string into a tape of the automaton's symbols
+    let tape = autom.encode(input);
+
+    // Run the simulator
+    let mut simulator = NaiveSimulator::new(autom, tape, budget);
+    let outcome = Outcome::from(simulator.run());
+
+    let stats = RunStats {
+        max_counter : simulator.visited.iter().map(|cfg| cfg.counter).max(),
+        distinct_configs : Some(simulator.visited.len()),
+        ..RunStats::default()
+    };
 
-pub fn naive<'a>(autom : &'a Autom, input : &str) -> bool {
-    // Convert the input string into a list of readables 
-    let readable_input = Readable::from_input_str(input);
+    (outcome, stats)
+}
 
-    // Run the simulator 
-    let mut simulator = NaiveSimulator::new(autom, readable_input);
-    simulator.run()
+// The largest counter value the search ever needs to look at
+//
+// Treat each (state, read head index) pair as a state of a one-counter automaton with k states,
+// and take a shortest run from the start config to an accept state with an empty counter
+// (accept states can always empty the counter, so one exists iff the word is accepted).
+// The counter changes by at most 1 per transition. If it got above k^2 + 1, then at two levels
+// 1 <= h < h' below the peak, the last config at that level before the peak and the first one at
+// that level after the peak would have the same (state, index) pairs. Cutting out the parts of
+// the run between levels h and h' would then give a shorter run, since the counter stays non-zero
+// in between and so every transition behaves the same.
+pub fn counter_cutoff(autom : &CompiledAutom, input : &Tape) -> i64 {
+    let k = autom.state_total as i64 * input.len() as i64;
+    k * k + 1
 }
 
 struct NaiveSimulator<'a> {
     // The automaton being simulated
-    autom : &'a Autom,
+    autom : &'a CompiledAutom,
 
     // The input string
-    input : Input,
+    input : Tape,
+
+    // The configurations that have been reached
+    visited : HashSet<Config>,
+
+    // The steps and configs taken so far against the budget
+    meter : Meter,
 }
 
 impl<'a> NaiveSimulator<'a> {
-    pub fn new(autom : &'a Autom, input : Input) -> Self {
-        Self { autom, input }
+    pub fn new(autom : &'a CompiledAutom, input : Tape, budget : &Budget) -> Self {
+        Self { autom, input, visited : HashSet::new(), meter : Meter::new(budget) }
     }
 
-    pub fn run(&mut self) -> bool {
-        // Automaton always starts from 0, read==lend and c==0 
+    pub fn run(&mut self) -> Result<bool, Exceeded> {
+        // Automaton always starts from 0, read==lend and c==0
         let start_cfg = Config { state : 0, read : 0, counter : 0 };
 
-        // Vector to store current computation paths
-        let mut paths = vec![start_cfg];
-
-        loop {
-            // Reject if all of the computation paths have failed
-            if paths.len() == 0 { return false; }
+        // Configs with counters above this can be ignored (see counter_cutoff)
+        let cutoff = counter_cutoff(self.autom, &self.input);
 
-            // Vector to store possible paths of the next computation
-            let mut possible_next_paths = Vec::new();
+        // Breadth first search over the configurations reachable from the start
+        // There are finitely many configurations below the cutoff, so this always terminates
+        let mut queue = VecDeque::new();
 
-            for cfg in &paths {
-                let transes = get_transitions(
-                    self.autom, 
-                    *cfg, 
-                    self.input.clone()
-                );
+        self.visited.insert(start_cfg);
+        queue.push_back(start_cfg);
 
-                for trans in &transes {
-                    let next_cfg = match next_nondeterm(
-                        *cfg, 
-                        trans.clone(), 
-                        &self.input,
-                        self.autom.decr_zero
-                    ) {
-                        None => continue,
+        while let Some(cfg) = queue.pop_front() {
+            self.meter.step()?;
 
-                        Some(c) => c,
-                    };
+            // Check halting states
+            if let Some(accepting) = self.autom.check_if_halting(cfg.state) {
+                match accepting {
+                    // Stop if any path is accepting
+                    // (accept states can always empty the counter, so the counter doesn't matter)
+                    true => return Ok(true),
 
-                    possible_next_paths.push(next_cfg);
+                    // Exclude any path that is rejecting
+                    false => continue,
                 }
             }
 
-            // Clear the current vector of computation paths
-            paths = Vec::new();
+            for &trans in get_transitions(self.autom, cfg, &self.input) {
+                let next_cfg = match next_nondeterm(cfg, trans, &self.input, self.autom.decr_zero) {
+                    None => continue,
 
-            // Reform paths vector
-            for cfg in possible_next_paths {
-                // Check halting states
-                if let Some(accepting) = self.autom.check_if_halting(cfg.state) {
-                    match accepting {
-                        // Stop if any path is accepting
-                        true => return true,
+                    Some(c) => c,
+                };
 
-                        // Exclude any path that is rejecting
-                        false => continue,
-                    }
-                }
+                if next_cfg.counter as i64 > cutoff { continue; }
 
-                paths.push(cfg);
+                if self.visited.insert(next_cfg) {
+                    self.meter.check_configs(self.visited.len())?;
+                    queue.push_back(next_cfg);
+                }
             }
         }
+
+        // Reject if all of the computation paths have failed
+        Ok(false)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,48 @@
+// Running one automaton on many words at once
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::automaton::compiled::CompiledAutom;
+
+// A simulation procedure, e.g. glueck_array::glueck_procedure or rytter::rytter_procedure
+pub type Procedure = fn(&CompiledAutom, &str) -> bool;
+
+// Automata are only ever read while simulating, so one can be shared between threads
+const _ : () = {
+    const fn assert_send_sync<T : Send + Sync>() {}
+    assert_send_sync::<CompiledAutom>();
+};
+
+// Check which of a list of words are accepted by an automaton, using up to threads threads
+// Each thread takes the next unsimulated word when it finishes one,
+// so a few slow words don't hold up the rest. The results are in the same order as the words
+pub fn simulate_words(autom : &CompiledAutom, words : &[&str], procedure : Procedure, threads : usize) -> Vec<bool> {
+    // Index of the next word that hasn't been taken by a thread
+    let next_word = AtomicUsize::new(0);
+
+    let mut results = vec![false; words.len()];
+
+    let finished : Vec<Vec<(usize, bool)>> = thread::scope(|scope| {
+        let handles : Vec<_> = (0..threads.clamp(1, words.len().max(1)))
+            .map(|_| scope.spawn(|| {
+                let mut out = Vec::new();
+
+                loop {
+                    let i = next_word.fetch_add(1, Ordering::Relaxed);
+                    if i >= words.len() { return out; }
+
+                    out.push((i, procedure(autom, words[i])));
+                }
+            }))
+            .collect();
+
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+
+    for (i, accepted) in finished.into_iter().flatten() {
+        results[i] = accepted;
+    }
+
+    results
+}
@@ -3,33 +3,71 @@
 use std::collections::VecDeque;
 use hashbrown::HashMap;
 
-use crate::automaton::autom::{Autom, Transition};
+use crate::automaton::compiled::{Action, CompiledAutom, Tape};
 use crate::automaton::generic_autom::State;
-use crate::simulation::config::{Config, StrippedConfig, get_transitions, strip_config, next_nondeterm};
-use crate::parser::ast::{Readable, Input};
+use crate::simulation::config::{Config, StrippedConfig, get_transitions, strip_config, next_nondeterm, move_sources};
+use crate::simulation::stats::RunStats;
+use crate::simulation::budget::{Budget, Exceeded, Meter, Outcome};
 
 pub type StrIndex = i32;
 
+// A transition into a state, along with the state it comes from
+type InverseTransition = (State, Action);
+
 // Check if a string is accepted by a nondeterministic automaton using the Rytter procedure
 // This should run in O(|input|^3)
 // See https://www.sciencedirect.com/science/article/pii/S0019995885800243?via%3Dihub for more info
-pub fn rytter_procedure<'a>(autom : &'a Autom, input : &str) -> bool {
-    // Convert the input into a list of Readables
-    let readable_input = Readable::from_input_str(input);
+pub fn rytter_procedure(autom : &CompiledAutom, input : &str) -> bool {
+    rytter_procedure_with_stats(autom, input).0
+}
+
+// Same as rytter_procedure, but also reports what the simulator did
+pub fn rytter_procedure_with_stats(autom : &CompiledAutom, input : &str) -> (bool, RunStats) {
+    let (outcome, stats) = rytter_procedure_with_budget(autom, input, &Budget::default());
+    (outcome.accepted(), stats)
+}
+
+// Same as rytter_procedure_with_stats, but gives up once the budget runs out
+pub fn rytter_procedure_with_budget(autom : &CompiledAutom, input : &str, budget : &Budget) -> (Outcome, RunStats) {
+    // Convert the input into a tape of the automaton's symbols
+    let tape = autom.encode(input);
 
     // Declare the RytterSimulator object
-    let mut simulator = RytterSimulator::new(autom, readable_input);
+    let mut simulator = RytterSimulator::new(autom, tape, budget);
 
     // Return the result of simulating
-    simulator.simulate()
+    let outcome = Outcome::from(simulator.simulate());
+    (outcome, simulator.stats())
+}
+
+// The pairs of configs related by popping an empty counter, which leaves it empty
+// There are only any if the automaton can decrement on zero
+pub fn zero_pops(autom : &CompiledAutom, input : &Tape) -> Vec<(StrippedConfig, StrippedConfig)> {
+    let mut pops = Vec::new();
+
+    for state in 0..autom.state_total {
+        for read in 0..input.len() as StrIndex {
+            let config = Config { state, read, counter : 0 };
+
+            for &trans in get_transitions(autom, config, input) {
+                if trans.incr_by >= 0 { continue; }
+
+                if let Some(next_config) = next_nondeterm(config, trans, input, autom.decr_zero) {
+                    pops.push((strip_config(config), strip_config(next_config)));
+                }
+            }
+        }
+    }
+
+    pops
 }
 
 struct RytterSimulator<'a> {
     // The automaton being simulated
-    autom : &'a Autom,
+    autom : &'a CompiledAutom,
 
     // The input being simulated on
-    input : Input,
+    input : Tape,
 
     // The size of the input
     n : StrIndex,
@@ -47,12 +85,18 @@ struct RytterSimulator<'a> {
     conf_matrix : (HashMap<usize, Vec<usize>>, HashMap<usize, Vec<usize>>),
 
     // The inverse of the automaton's adjacency list
-    inverse_state_map : HashMap<State, Vec<Transition>>
+    inverse_state_map : HashMap<State, Vec<InverseTransition>>,
+
+    // The number of pairs in R
+    entries : usize,
+
+    // The steps and pairs taken so far against the budget
+    meter : Meter,
 }
 
 impl<'a> RytterSimulator<'a> {
     // Constructor
-    pub fn new(autom : &'a Autom, input : Input) -> Self {
+    pub fn new(autom : &'a CompiledAutom, input : Tape, budget : &Budget) -> Self {
         let n = input.len() as StrIndex;
 
         // Initialise configs list
@@ -60,7 +104,7 @@ impl<'a> RytterSimulator<'a> {
         for state in 0..autom.state_total {
             for index in 0..n {
                 for counter in [true, false] {
-                    configs.push((state, index as i32, counter));
+                    configs.push((state, index, counter));
                 }
             }
         }
@@ -93,30 +137,14 @@ impl<'a> RytterSimulator<'a> {
 
         // Populate the inverse state map
         for from_state in 0..autom.state_total {
-            for trans in autom.get_transitions(from_state) {
-                let to_state = trans.goto;
-
-                // Create the inverse transition
-                let inverse_trans = Transition {
-                    // swap to_state and from_state
-                    goto : from_state, 
-
-                    // leave everything else alone
-                    incr_by : trans.incr_by,
-                    move_by : trans.move_by,
-                    condition : trans.condition,
-                    //read_char : trans.read_char,
-                    //test_counter_zero : trans.test_counter_zero,
-                };
-
-                // Put this transition into the map
-                let search_map = inverse_state_map.get_mut(&to_state).unwrap();
-                search_map.push(inverse_trans);
+            for action in autom.all_transitions(from_state) {
+                // Keep the state the transition comes from next to it
+                let search_map = inverse_state_map.get_mut(&action.goto).unwrap();
+                search_map.push((from_state, action));
             }
         }
         
-        // Return
-        Self { 
+        let mut simulator = Self { 
             autom, 
             input, 
             n, 
@@ -125,15 +153,42 @@ impl<'a> RytterSimulator<'a> {
             queue, 
             conf_matrix, 
             inverse_state_map, 
+            entries : num_configs,
+            meter : Meter::new(budget),
+        };
+
+        // Popping an empty counter relates configs without a matching push
+        for (from, to) in zero_pops(autom, &simulator.input) {
+            let (i, j) = (simulator.get_index(from), simulator.get_index(to));
+
+            if !simulator.get_matrix(i, j) {
+                simulator.set_matrix_true(i, j);
+                simulator.queue.push_back((i, j));
+            }
+        }
+
+        // Return
+        simulator
+    }
+
+    // Statistics about the simulation so far
+    pub fn stats(&self) -> RunStats {
+        RunStats {
+            distinct_configs : Some(self.num_configs),
+            table_entries : Some(self.entries),
+            ..RunStats::default()
         }
     }
 
     // Run the simulator
-    pub fn simulate(&mut self) -> bool {
+    pub fn simulate(&mut self) -> Result<bool, Exceeded> {
         while !self.queue.is_empty() {
             // Take an element from the queue
             let (i, j) = self.queue.pop_front().unwrap();
 
+            // The size of R is checked once per step, so it can go over by the pairs a single step adds
+            self.meter.step()?;
+            self.meter.check_configs(self.entries)?;
 
             // 2: For each (k, l) in below(i, j), set to true in R and add to the queue
             for (k, l) in self.below(i, j) {                
@@ -186,11 +241,11 @@ impl<'a> RytterSimulator<'a> {
         for conf in end_confs {
             let (state, _, _counter) = self.configs[*conf];
             if let Some(true) = self.autom.check_if_halting(state) {
-                return true;
+                return Ok(true);
             }
         }
 
-        false
+        Ok(false)
     }
 
     // Get the index of a given configuration
@@ -207,7 +262,7 @@ impl<'a> RytterSimulator<'a> {
         let index = counter_offset + index_offset + state_offset;
 
         // Panic if this config is too big
-        if index > self.num_configs {
+        if index >= self.num_configs {
             panic!("Config {:?} doesn't exist!", conf);
         }
 
@@ -227,6 +282,7 @@ impl<'a> RytterSimulator<'a> {
         let vec1 = self.conf_matrix.1.get_mut(&j).unwrap();
         vec0.push(j);
         vec1.push(i);
+        self.entries += 1;
     }
 
     // Find all the configurations below a given configuration
@@ -245,35 +301,23 @@ impl<'a> RytterSimulator<'a> {
         // If we can decrement on zero, also include the transitions that decrement
         let i_transes = self.inverse_state_map.get(&i_state).unwrap();
         let mut i_push = Vec::new();
-        for trans in i_transes {            
-            if trans.incr_by > 0 { 
-                i_push.push(trans); 
+        for (from_state, action) in i_transes {            
+            if action.incr_by > 0 { 
+                i_push.push((*from_state, *action)); 
             }
         }
 
         // Turn these into configurations
         let mut k_configs = Vec::new();
-        for trans in i_push {
-            let new_state = trans.goto;
-            let new_read = i_index - trans.move_by;//.max(0).min(self.n-1);
-
-            // Invalidate this transition if the readhead has to be in an illegal position for it to work
-            if new_read < 0 || new_read >= self.n {
-                continue;
-            }
-
-            for counter_zero in [false, true] {
-                match &trans.condition {
-                    // Push if there's no condition to test
-                    None => k_configs.push((new_state, new_read, counter_zero)),
-    
-                    // Push iff the condition passes on the current read and counter values
-                    Some(cond) => { 
-                        if cond.check(self.input[new_read as usize], if counter_zero {0} else {1}) {
-                            k_configs.push((new_state, new_read, counter_zero));
-                        }
-                    }, 
-                };
+        for (new_state, action) in i_push {
+            // The read head could have come from any cell that the move takes to i's
+            for new_read in move_sources(i_index, action.move_by, self.n) {
+                for counter_zero in [false, true] {
+                    // Push iff the transition can fire on the current read and counter values
+                    if self.autom.transitions(new_state, self.input[new_read as usize], counter_zero).contains(&action) {
+                        k_configs.push((new_state, new_read, counter_zero));
+                    }
+                }
             }
         }
 
@@ -287,10 +331,10 @@ impl<'a> RytterSimulator<'a> {
         };
 
         // Get the legal transitions off of j that pop
-        let j_transes = get_transitions(self.autom, j_conf, self.input.clone());
+        let j_transes = get_transitions(self.autom, j_conf, &self.input);
         let mut j_pop = Vec::new();
         for trans in j_transes {
-            if trans.incr_by < 0 { j_pop.push(trans); }
+            if trans.incr_by < 0 { j_pop.push(*trans); }
         }
 
         // Turn these into configurations (minus the counter condition, as this will be dictated by k_configs)
@@ -1,32 +1,51 @@
-// Rytter's algorithm using a matrix to store the set R
+// Rytter's algorithm using a bit-packed matrix to store the set R
 
 use std::collections::{VecDeque, HashMap};
-use array2d::Array2D;
 
-use crate::automaton::autom::{Autom, Transition};
+use crate::automaton::compiled::{Action, CompiledAutom, Tape};
 use crate::automaton::generic_autom::State;
-use crate::simulation::config::{Config, StrippedConfig, get_transitions, strip_config, next_nondeterm};
-use crate::parser::ast::{Readable, Input};
+use crate::simulation::config::{Config, StrippedConfig, get_transitions, strip_config, next_nondeterm, move_sources};
+use crate::simulation::stats::RunStats;
+use crate::simulation::budget::{Budget, Exceeded, Meter, Outcome};
+use crate::simulation::bit_matrix::BitMatrix;
+use crate::simulation::rytter::zero_pops;
 
 pub type StrIndex = i32;
 
-type InverseTransition = Transition;
+// A transition into a state, along with the state it comes from
+type InverseTransition = (State, Action);
 
-pub fn rytter_procedure<'a>(autom : &'a Autom, input : &str) -> bool {
-    // Convert the input into a list of Readables
-    let readable_input = Readable::from_input_str(input);
+pub fn rytter_procedure(autom : &CompiledAutom, input : &str) -> bool {
+    rytter_procedure_with_stats(autom, input).0
+}
+
+// Same as rytter_procedure, but also reports what the simulator did
+pub fn rytter_procedure_with_stats(autom : &CompiledAutom, input : &str) -> (bool, RunStats) {
+    let (outcome, stats) = rytter_procedure_with_budget(autom, input, &Budget::default());
+    (outcome.accepted(), stats)
+}
+
+// Same as rytter_procedure_with_stats, but gives up once the budget runs out
+pub fn rytter_procedure_with_budget(autom : &CompiledAutom, input : &str, budget : &Budget) -> (Outcome, RunStats) {
+    // Convert the input into a tape of the automaton's symbols
+    let tape = autom.encode(input);
 
     // Declare the RytterSimulator object
-    let mut simulator = RytterSimulator::new(autom, readable_input);
+    // R has room for every pair of configs, so give up before making it if it's too big
+    let mut simulator = match RytterSimulator::new(autom, tape, budget) {
+        Ok(simulator) => simulator,
+        Err(exceeded) => return (Outcome::BudgetExceeded(exceeded), RunStats::default()),
+    };
 
     // Return the result of simulating
-    simulator.simulate()
+    let outcome = Outcome::from(simulator.simulate());
+    (outcome, simulator.stats())
 }
 
 struct RytterSimulator<'a> {
-    autom : &'a Autom,
+    autom : &'a CompiledAutom,
 
-    input : Input,
+    input : Tape,
 
     n : StrIndex,
 
@@ -36,14 +55,16 @@ struct RytterSimulator<'a> {
 
     queue : VecDeque<(usize, usize)>,
 
-    conf_matrix : Array2D<bool>,
+    conf_matrix : BitMatrix,
+
+    inverse_state_map : HashMap<State, Vec<InverseTransition>>,
 
-    inverse_state_map : HashMap<State, Vec<Transition>>
+    meter : Meter,
 }
 
 impl<'a> RytterSimulator<'a> {
     // Constructor
-    pub fn new(autom : &'a Autom, input : Input) -> Self {
+    pub fn new(autom : &'a CompiledAutom, input : Tape, budget : &Budget) -> Result<Self, Exceeded> {
         let n = input.len() as StrIndex;
 
         // Initialise configs list
@@ -51,23 +72,24 @@ impl<'a> RytterSimulator<'a> {
         for state in 0..autom.state_total {
             for index in 0..n {
                 for counter in [true, false] {
-                    configs.push((state, index as i32, counter));
+                    configs.push((state, index, counter));
                 }
             }
         }
 
         let num_configs = configs.len();
+        budget.check_configs(num_configs * num_configs)?;
         
         // Initialise queue
         let mut queue = VecDeque::new();
 
         // Initialise config matrix
-        let mut conf_matrix = Array2D::filled_with(false, num_configs, num_configs);
+        let mut conf_matrix = BitMatrix::new(num_configs);
 
         // Fill the queue and matrix appropriately
         for cfg in 0..num_configs {
             queue.push_back((cfg, cfg));
-            conf_matrix.set(cfg, cfg, true).unwrap();
+            conf_matrix.set(cfg, cfg);
         }
 
         // Construct inverse state map
@@ -78,27 +100,14 @@ impl<'a> RytterSimulator<'a> {
         }
 
         for from_state in 0..autom.state_total {
-            for trans in autom.get_transitions(from_state) {
-                let to_state = trans.goto;
-
-                let inverse_trans = InverseTransition {
-                    // swap to_state and from_state
-                    goto : from_state, 
-
-                    // leave everything else alone
-                    incr_by : trans.incr_by,
-                    move_by : trans.move_by,
-                    condition : trans.condition,
-                    //read_char : trans.read_char,
-                    //test_counter_zero : trans.test_counter_zero,
-                };
-
-                let search_map = inverse_state_map.get_mut(&to_state).unwrap();
-                search_map.push(inverse_trans);
+            for action in autom.all_transitions(from_state) {
+                // Keep the state the transition comes from next to it
+                let search_map = inverse_state_map.get_mut(&action.goto).unwrap();
+                search_map.push((from_state, action));
             }
         }
         
-        Self { 
+        let mut simulator = Self { 
             autom, 
             input, 
             n, 
@@ -107,66 +116,70 @@ impl<'a> RytterSimulator<'a> {
             queue, 
             conf_matrix, 
             inverse_state_map, 
+            meter : Meter::new(budget),
+        };
+
+        // Popping an empty counter relates configs without a matching push
+        for (from, to) in zero_pops(autom, &simulator.input) {
+            let (i, j) = (simulator.get_index(from), simulator.get_index(to));
+
+            if simulator.conf_matrix.set(i, j) {
+                simulator.queue.push_back((i, j));
+            }
+        }
+
+        Ok(simulator)
+    }
+
+    // Statistics about the simulation so far
+    pub fn stats(&self) -> RunStats {
+        RunStats {
+            distinct_configs : Some(self.num_configs),
+            table_entries : Some(self.conf_matrix.count()),
+            ..RunStats::default()
         }
     }
 
     // Run the simulator
-    pub fn simulate(&mut self) -> bool {
+    pub fn simulate(&mut self) -> Result<bool, Exceeded> {
         while !self.queue.is_empty() {
             let (i, j) = self.queue.pop_front().unwrap();
+            self.meter.step()?;
 
             for (k, l) in self.below(i, j) {
-                
-                if !self.conf_matrix.get(k, l).unwrap() {
-                    self.conf_matrix.set(k, l, true).unwrap();
+                if self.conf_matrix.set(k, l) {
                     self.queue.push_back((k, l));
                 }
             }
 
             // for each (k, i) in R such that (k, j) notin R do
-            for k in 0..self.num_configs {
-                let ki = *self.conf_matrix.get(k, i).unwrap();
-                let kj = *self.conf_matrix.get(k, j).unwrap();
-
-                if ki && !kj {
-                    self.conf_matrix.set(k, j, true).unwrap();
-                    self.queue.push_back((k, j));
-                }
+            // (column i is ORed into column j a word at a time)
+            for k in self.conf_matrix.or_col_into(i, j) {
+                self.queue.push_back((k, j));
             }
 
             // for each (j, k) in R such that (i, k) notin R do
-            for k in 0..self.num_configs {
-                let jk = *self.conf_matrix.get(j, k).unwrap();
-                let ik = *self.conf_matrix.get(i, k).unwrap();
-
-                if jk && !ik {
-                    self.conf_matrix.set(i, k, true).unwrap();
-                    self.queue.push_back((i, k));
-                }
+            // (row j is ORed into row i a word at a time)
+            for k in self.conf_matrix.or_row_into(j, i) {
+                self.queue.push_back((i, k));
             }
-            
         }
         
         // Find the start config
         let start_conf = self.get_index((0, 0, true));
 
         // Get the configs j such that (start_conf, j) in R
-        let mut end_confs = Vec::new();
-        for i in 0..self.num_configs {
-            if *self.conf_matrix.get(start_conf, i).unwrap() { 
-                end_confs.push(i); 
-            }    
-        }
+        let end_confs = self.conf_matrix.row(start_conf);
 
         // Accept if any of the end_confs are accepting
         for conf in end_confs {
             let (state, _, _counter) = self.configs[conf];
             if let Some(true) = self.autom.check_if_halting(state) {
-                return true;
+                return Ok(true);
             }
         }
 
-        false
+        Ok(false)
     }
 
     // Get the index of a given configuration
@@ -183,7 +196,7 @@ impl<'a> RytterSimulator<'a> {
         let index = counter_offset + index_offset + state_offset;
 
         // Panic if this config is too big
-        if index > self.num_configs {
+        if index >= self.num_configs {
             panic!("Config {:?} doesn't exist!", conf);
         }
 
@@ -213,35 +226,23 @@ impl<'a> RytterSimulator<'a> {
         // Get the transitions onto i that push
         let i_transes = self.inverse_state_map.get(&i_state).unwrap();
         let mut i_push = Vec::new();
-        for trans in i_transes {            
-            if trans.incr_by > 0 { 
-                i_push.push(trans); 
+        for (from_state, action) in i_transes {            
+            if action.incr_by > 0 { 
+                i_push.push((*from_state, *action)); 
             }
         }
 
         // Turn these into configurations
         let mut k_configs = Vec::new();
-        for trans in i_push {
-            let new_state = trans.goto;
-            let new_read = i_index - trans.move_by;//.max(0).min(self.n-1);
-
-            // Invalidate this transition if the readhead has to be in an illegal position for it to work
-            if new_read < 0 || new_read >= self.n {
-                continue;
-            }
-
-            for counter_zero in [false, true] {
-                match &trans.condition {
-                    // Push if there's no condition to test
-                    None => k_configs.push((new_state, new_read, counter_zero)),
-    
-                    // Push iff the condition passes on the current read and counter values
-                    Some(cond) => { 
-                        if cond.check(self.input[new_read as usize], if counter_zero {0} else {1}) {
-                            k_configs.push((new_state, new_read, counter_zero));
-                        }
-                    }, 
-                };
+        for (new_state, action) in i_push {
+            // The read head could have come from any cell that the move takes to i's
+            for new_read in move_sources(i_index, action.move_by, self.n) {
+                for counter_zero in [false, true] {
+                    // Push iff the transition can fire on the current read and counter values
+                    if self.autom.transitions(new_state, self.input[new_read as usize], counter_zero).contains(&action) {
+                        k_configs.push((new_state, new_read, counter_zero));
+                    }
+                }
             }
         }
 
@@ -255,10 +256,10 @@ impl<'a> RytterSimulator<'a> {
         };
 
         // Get the legal transitions off of j that pop
-        let j_transes = get_transitions(self.autom, j_conf, self.input.clone());
+        let j_transes = get_transitions(self.autom, j_conf, &self.input);
         let mut j_pop = Vec::new();
         for trans in j_transes {
-            if trans.incr_by < 0 { j_pop.push(trans); }
+            if trans.incr_by < 0 { j_pop.push(*trans); }
         }
 
         // Turn these into configurations (minus the counter condition, as this will be dictated by k_configs)
@@ -0,0 +1,186 @@
+// Rytter's algorithm over the reachable configurations, computing R on several threads
+
+use std::sync::{Mutex, OnceLock};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::thread;
+
+use crate::automaton::compiled::CompiledAutom;
+use crate::simulation::bit_matrix::AtomicBitMatrix;
+use crate::simulation::rytter_reachable::ReachableConfigs;
+use crate::simulation::stats::RunStats;
+use crate::simulation::budget::{Budget, Exceeded, Meter, Outcome};
+
+// Check if a string is accepted by a nondeterministic automaton using the Rytter procedure
+// R is shared between the threads, and pairs (i, j) are handed to the thread that owns row i
+// Idle threads take pairs from the other threads' queues, so no thread sits waiting on another
+pub fn rytter_procedure(autom : &CompiledAutom, input : &str, threads : usize) -> bool {
+    rytter_procedure_with_stats(autom, input, threads).0
+}
+
+// Same as rytter_procedure, but also reports what the simulator did
+pub fn rytter_procedure_with_stats(autom : &CompiledAutom, input : &str, threads : usize) -> (bool, RunStats) {
+    let (outcome, stats) = rytter_procedure_with_budget(autom, input, threads, &Budget::default());
+    (outcome.accepted(), stats)
+}
+
+// Same as rytter_procedure_with_stats, but gives up once the budget runs out
+// The threads share the budget, and all stop as soon as one of them runs into a limit
+pub fn rytter_procedure_with_budget(autom : &CompiledAutom, input : &str, threads : usize, budget : &Budget) -> (Outcome, RunStats) {
+    // Convert the input into a tape of the automaton's symbols
+    let tape = autom.encode(input);
+
+    // Find the configurations that R needs to cover
+    let mut meter = Meter::new(budget);
+    let reachable = match ReachableConfigs::new(autom, &tape, &mut meter) {
+        Ok(reachable) => reachable,
+        Err(exceeded) => return (Outcome::BudgetExceeded(exceeded), RunStats::default()),
+    };
+
+    // R has room for every pair of reachable configs, so check it fits first
+    if let Err(exceeded) = budget.check_configs(reachable.len() * reachable.len()) {
+        return (Outcome::BudgetExceeded(exceeded), RunStats { distinct_configs : Some(reachable.len()), ..RunStats::default() });
+    }
+
+    // Declare the RytterSimulator object
+    let simulator = RytterSimulator::new(&reachable, threads.max(1), budget, meter.steps());
+
+    // Run the threads until R stops changing
+    thread::scope(|scope| {
+        for t in 0..simulator.queues.len() {
+            let simulator = &simulator;
+            scope.spawn(move || simulator.work(t));
+        }
+    });
+
+    let stats = RunStats {
+        distinct_configs : Some(reachable.len()),
+        table_entries : Some(simulator.conf_matrix.count()),
+        ..RunStats::default()
+    };
+
+    // Accept if the start config reaches an accepting one
+    let result = match simulator.exceeded.get() {
+        Some(exceeded) => Err(*exceeded),
+        None => Ok(reachable.any_accepting(autom, &simulator.conf_matrix.row(0))),
+    };
+
+    (Outcome::from(result), stats)
+}
+
+struct RytterSimulator<'a> {
+    // The reachable configurations of the automaton on the input
+    reachable : &'a ReachableConfigs,
+
+    // One queue of config pairs per thread, holding the pairs whose source config the thread owns
+    queues : Vec<Mutex<Vec<(usize, usize)>>>,
+
+    // The number of pairs that have been added to R but not processed yet
+    // The closure is finished once this gets to zero
+    pending : AtomicUsize,
+
+    // The boolean matrix R over the reachable configurations
+    conf_matrix : AtomicBitMatrix,
+
+    // The budget shared by all the threads
+    budget : Budget,
+
+    // The number of steps taken so far by all the threads together
+    steps : AtomicU64,
+
+    // Set by the first thread to run into a limit of the budget, which stops every thread
+    exceeded : OnceLock<Exceeded>,
+}
+
+impl<'a> RytterSimulator<'a> {
+    // Constructor
+    pub fn new(reachable : &'a ReachableConfigs, threads : usize, budget : &Budget, steps : u64) -> Self {
+        let simulator = Self {
+            reachable,
+            queues : (0..threads).map(|_| Mutex::new(Vec::new())).collect(),
+            pending : AtomicUsize::new(0),
+            conf_matrix : AtomicBitMatrix::new(reachable.len()),
+            budget : *budget,
+            steps : AtomicU64::new(steps),
+            exceeded : OnceLock::new(),
+        };
+
+        // Initialise config matrix with the reflexive pairs and the pops from an empty counter
+        for cfg in 0..reachable.len() {
+            simulator.add(cfg, cfg);
+        }
+
+        for (i, j) in &reachable.zero_pops {
+            simulator.add(*i, *j);
+        }
+
+        simulator
+    }
+
+    // Process pairs until every thread runs out of them, or the budget does
+    fn work(&self, thread : usize) {
+        loop {
+            if self.exceeded.get().is_some() { return; }
+
+            match self.take(thread) {
+                Some((i, j)) => {
+                    let steps = self.steps.fetch_add(1, Ordering::Relaxed) + 1;
+                    if let Err(exceeded) = self.budget.check_progress(steps) {
+                        let _ = self.exceeded.set(exceeded);
+                        return;
+                    }
+
+                    self.process(i, j);
+                    self.pending.fetch_sub(1, Ordering::SeqCst);
+                },
+
+                // Nothing is left anywhere, and no other thread is going to add anything
+                None if self.pending.load(Ordering::SeqCst) == 0 => return,
+
+                // Other threads are still processing pairs, which might add more
+                None => thread::yield_now(),
+            }
+        }
+    }
+
+    // Apply the rules for R to a pair that's been added to it
+    fn process(&self, i : usize, j : usize) {
+        // For each (k, l) in below(i, j), set to true in R and add to the queue
+        for (k, l) in self.reachable.below(i, j) {
+            self.add(k, l);
+        }
+
+        // For (k, i) in R such that (k, j) not in R, set (k, j) to true in R and add to the queue
+        for k in self.conf_matrix.or_col_into(i, j) {
+            self.schedule(k, j);
+        }
+
+        // For each (j, k) in R such that (i, k) not in R, set (i, k) to true in R and add to the queue
+        for k in self.conf_matrix.or_row_into(j, i) {
+            self.schedule(i, k);
+        }
+    }
+
+    // Set (i, j) to true in R, and add it to the queue if it's new
+    fn add(&self, i : usize, j : usize) {
+        if self.conf_matrix.set(i, j) {
+            self.schedule(i, j);
+        }
+    }
+
+    // Add a pair that's new in R to the queue of the thread that owns its source config
+    fn schedule(&self, i : usize, j : usize) {
+        self.pending.fetch_add(1, Ordering::SeqCst);
+
+        let owner = i % self.queues.len();
+        self.queues[owner].lock().unwrap().push((i, j));
+    }
+
+    // Get the next pair for a thread, trying its own queue before the others
+    fn take(&self, thread : usize) -> Option<(usize, usize)> {
+        let threads = self.queues.len();
+
+        (0..threads)
+            .map(|offset| (thread + offset) % threads)
+            .find_map(|t| self.queues[t].lock().unwrap().pop())
+    }
+}
@@ -0,0 +1,247 @@
+// Rytter's algorithm restricted to the configurations reachable from the start configuration
+
+use std::collections::VecDeque;
+use hashbrown::HashMap;
+
+use crate::automaton::compiled::{CompiledAutom, Tape};
+use crate::automaton::generic_autom::State;
+use crate::simulation::config::{Config, StrippedConfig, get_transitions, next_nondeterm};
+use crate::simulation::stats::RunStats;
+use crate::simulation::budget::{Budget, Exceeded, Meter, Outcome};
+use crate::simulation::bit_matrix::BitMatrix;
+
+// Check if a string is accepted by a nondeterministic automaton using the Rytter procedure
+// Only configurations that can be reached from (0, 0, true) are given a row and column in R,
+// so the work done scales with the number of reachable configurations rather than all of them
+pub fn rytter_procedure(autom : &CompiledAutom, input : &str) -> bool {
+    rytter_procedure_with_stats(autom, input).0
+}
+
+// Same as rytter_procedure, but also reports what the simulator did
+pub fn rytter_procedure_with_stats(autom : &CompiledAutom, input : &str) -> (bool, RunStats) {
+    let (outcome, stats) = rytter_procedure_with_budget(autom, input, &Budget::default());
+    (outcome.accepted(), stats)
+}
+
+// Same as rytter_procedure_with_stats, but gives up once the budget runs out
+pub fn rytter_procedure_with_budget(autom : &CompiledAutom, input : &str, budget : &Budget) -> (Outcome, RunStats) {
+    // Convert the input into a tape of the automaton's symbols
+    let tape = autom.encode(input);
+
+    // Declare the RytterSimulator object
+    // Finding the reachable configs counts towards the budget too
+    let mut meter = Meter::new(budget);
+    let mut simulator = match RytterSimulator::new(autom, tape, &mut meter) {
+        Ok(simulator) => simulator,
+        Err(exceeded) => return (Outcome::BudgetExceeded(exceeded), RunStats::default()),
+    };
+
+    // Return the result of simulating
+    let outcome = Outcome::from(simulator.simulate(&mut meter));
+    (outcome, simulator.stats())
+}
+
+// The configurations reachable from the start configuration, and the pushes and pops between them
+pub struct ReachableConfigs {
+    // The reachable configurations (the start configuration always has index 0)
+    pub configs : Vec<StrippedConfig>,
+
+    // For each config, the configs that can push to it
+    pub push_sources : Vec<Vec<usize>>,
+
+    // For each config with a non-empty counter, the (state, index) pairs it can pop to
+    // (whether or not the counter is empty afterwards depends on where the matching push came from)
+    pub pop_targets : Vec<Vec<(State, i32)>>,
+
+    // Pairs of configs that are related without pushing, i.e. by popping an empty counter
+    pub zero_pops : Vec<(usize, usize)>,
+
+    // Maps reachable configurations to their index in configs
+    pub config_indices : HashMap<StrippedConfig, usize>,
+}
+
+impl ReachableConfigs {
+    // Search forwards from the start config
+    // Pops could leave the counter empty or not, so both possibilities count as reachable
+    // Each config visited is a step of the budget
+    pub fn new(autom : &CompiledAutom, input : &Tape, meter : &mut Meter) -> Result<Self, Exceeded> {
+        let mut reachable = Self {
+            configs : Vec::new(),
+            push_sources : Vec::new(),
+            pop_targets : Vec::new(),
+            zero_pops : Vec::new(),
+            config_indices : HashMap::new(),
+        };
+
+        let mut to_visit = Vec::new();
+        reachable.reach((0, 0, true), &mut to_visit);
+
+        while let Some(i) = to_visit.pop() {
+            meter.step()?;
+            meter.check_configs(reachable.len())?;
+
+            let (state, read, counter_zero) = reachable.configs[i];
+            let config = Config { state, read, counter : if counter_zero {0} else {1} };
+
+            for &trans in get_transitions(autom, config, input) {
+                let next_config = match next_nondeterm(config, trans, input, autom.decr_zero) {
+                    None => continue,
+                    Some(c) => c,
+                };
+
+                // Pushing always leaves the counter non-empty
+                if trans.incr_by > 0 {
+                    let k = reachable.reach((next_config.state, next_config.read, false), &mut to_visit);
+                    reachable.push_sources[k].push(i);
+                }
+
+                // Popping an empty counter leaves it empty, and relates the two configs directly
+                else if counter_zero {
+                    let k = reachable.reach((next_config.state, next_config.read, true), &mut to_visit);
+                    reachable.zero_pops.push((i, k));
+                }
+
+                else {
+                    reachable.pop_targets[i].push((next_config.state, next_config.read));
+                    reachable.reach((next_config.state, next_config.read, true), &mut to_visit);
+                    reachable.reach((next_config.state, next_config.read, false), &mut to_visit);
+                }
+            }
+        }
+
+        Ok(reachable)
+    }
+
+    // The number of reachable configurations
+    pub fn len(&self) -> usize {
+        self.configs.len()
+    }
+
+    // Always false, since the start configuration is reachable
+    pub fn is_empty(&self) -> bool {
+        self.configs.is_empty()
+    }
+
+    // Find all the pairs of configurations below a given pair
+    // i.e. (k, l) such that k pushes to i and j pops to l
+    pub fn below(&self, i : usize, j : usize) -> Vec<(usize, usize)> {
+        let mut out = Vec::new();
+
+        for k in &self.push_sources[i] {
+            let (_, _, k_counter_zero) = self.configs[*k];
+
+            for (l_state, l_index) in &self.pop_targets[j] {
+                // l is at the same counter level as k
+                // Every pop target was reached with both counter values, so it has an index
+                let l = self.config_indices[&(*l_state, *l_index, k_counter_zero)];
+                out.push((*k, l));
+            }
+        }
+
+        out
+    }
+
+    // Check if any of the given configs is in an accepting state
+    pub fn any_accepting(&self, autom : &CompiledAutom, confs : &[usize]) -> bool {
+        confs.iter().any(|conf| {
+            let (state, _, _) = self.configs[*conf];
+            autom.check_if_halting(state) == Some(true)
+        })
+    }
+
+    // Get the index of a given configuration
+    // If it hasn't been reached before, give it an index and schedule it to be visited
+    fn reach(&mut self, conf : StrippedConfig, to_visit : &mut Vec<usize>) -> usize {
+        if let Some(index) = self.config_indices.get(&conf) {
+            return *index;
+        }
+
+        let index = self.configs.len();
+        self.configs.push(conf);
+        self.push_sources.push(Vec::new());
+        self.pop_targets.push(Vec::new());
+        self.config_indices.insert(conf, index);
+
+        to_visit.push(index);
+        index
+    }
+}
+
+struct RytterSimulator<'a> {
+    // The automaton being simulated
+    autom : &'a CompiledAutom,
+
+    // The reachable configurations of autom on input
+    reachable : ReachableConfigs,
+
+    // The queue of config pairs being considered
+    queue : VecDeque<(usize, usize)>,
+
+    // The boolean matrix R over the reachable configurations
+    conf_matrix : BitMatrix,
+}
+
+impl<'a> RytterSimulator<'a> {
+    // Constructor
+    pub fn new(autom : &'a CompiledAutom, input : Tape, meter : &mut Meter) -> Result<Self, Exceeded> {
+        let reachable = ReachableConfigs::new(autom, &input, meter)?;
+
+        // Initialise config matrix with the reflexive pairs and the pops from an empty counter
+        // R has room for every pair of reachable configs, so check it fits first
+        let num_configs = reachable.len();
+        meter.check_configs(num_configs * num_configs)?;
+
+        let mut conf_matrix = BitMatrix::new(num_configs);
+        let mut queue = VecDeque::new();
+
+        for cfg in 0..num_configs {
+            conf_matrix.set(cfg, cfg);
+            queue.push_back((cfg, cfg));
+        }
+
+        for (i, j) in &reachable.zero_pops {
+            if conf_matrix.set(*i, *j) {
+                queue.push_back((*i, *j));
+            }
+        }
+
+        Ok(Self { autom, reachable, queue, conf_matrix })
+    }
+
+    // Statistics about the simulation so far
+    pub fn stats(&self) -> RunStats {
+        RunStats {
+            distinct_configs : Some(self.reachable.len()),
+            table_entries : Some(self.conf_matrix.count()),
+            ..RunStats::default()
+        }
+    }
+
+    // Run the simulator
+    pub fn simulate(&mut self, meter : &mut Meter) -> Result<bool, Exceeded> {
+        while let Some((i, j)) = self.queue.pop_front() {
+            meter.step()?;
+
+            // For each (k, l) in below(i, j), set to true in R and add to the queue
+            for (k, l) in self.reachable.below(i, j) {
+                if self.conf_matrix.set(k, l) {
+                    self.queue.push_back((k, l));
+                }
+            }
+
+            // For (k, i) in R such that (k, j) not in R, set (k, j) to true in R and add to the queue
+            for k in self.conf_matrix.or_col_into(i, j) {
+                self.queue.push_back((k, j));
+            }
+
+            // For each (j, k) in R such that (i, k) not in R, set (i, k) to true in R and add to the queue
+            for k in self.conf_matrix.or_row_into(j, i) {
+                self.queue.push_back((i, k));
+            }
+        }
+
+        // Accept if the start config reaches an accepting one
+        // (everything related to it has an empty counter)
+        Ok(self.reachable.any_accepting(self.autom, &self.conf_matrix.row(0)))
+    }
+}
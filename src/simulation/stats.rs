@@ -0,0 +1,94 @@
+// Statistics about the resources a simulation used
+
+use crate::simulation::config::Config;
+
+// What a simulator did while deciding a word
+// Fields that a simulator can't measure (e.g. steps for a nondeterministic automaton) are None.
+// Glueck's procedures jump over the parts of the run they've already memoized, so their steps,
+// max_counter and head_reversals only cover the transitions they actually followed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunStats {
+    // The number of transitions followed
+    pub steps : Option<u64>,
+
+    // The largest counter value seen
+    pub max_counter : Option<i32>,
+
+    // The number of times the read head changed direction
+    pub head_reversals : Option<u64>,
+
+    // The number of distinct configurations visited
+    // (naive_determ only counts them when asked to, since it doesn't need them to run)
+    pub distinct_configs : Option<usize>,
+
+    // The number of entries in the simulator's memo table
+    // (terminators in Glueck's config_table, or pairs in Rytter's R)
+    pub table_entries : Option<usize>,
+}
+
+impl RunStats {
+    // Print the statistics to the terminal
+    pub fn print(&self) {
+        println!("Stats:");
+
+        if let Some(steps) = self.steps {
+            println!("  Steps: {}", steps);
+        }
+
+        if let Some(max_counter) = self.max_counter {
+            println!("  Max counter: {}", max_counter);
+        }
+
+        if let Some(head_reversals) = self.head_reversals {
+            println!("  Head reversals: {}", head_reversals);
+        }
+
+        if let Some(distinct_configs) = self.distinct_configs {
+            println!("  Distinct configs: {}", distinct_configs);
+        }
+
+        if let Some(table_entries) = self.table_entries {
+            println!("  Table entries: {}", table_entries);
+        }
+    }
+}
+
+// Keeps track of the steps, counter values and head reversals of a deterministic run as it goes
+#[derive(Debug, Default)]
+pub struct StepTracker {
+    // Transitions followed so far
+    steps : u64,
+
+    // Largest counter value so far
+    max_counter : i32,
+
+    // Head reversals so far
+    head_reversals : u64,
+
+    // The direction the head last moved in (-1, 1, or 0 if it hasn't moved yet)
+    direction : i32,
+}
+
+impl StepTracker {
+    // Record a transition from one config to another
+    pub fn step(&mut self, from : Config, to : Config) {
+        self.steps += 1;
+        self.max_counter = self.max_counter.max(to.counter);
+
+        let direction = (to.read - from.read).signum();
+        if direction != 0 {
+            if self.direction != 0 && direction != self.direction {
+                self.head_reversals += 1;
+            }
+
+            self.direction = direction;
+        }
+    }
+
+    // Fill in the fields of a RunStats that this tracks
+    pub fn finish(&self, stats : &mut RunStats) {
+        stats.steps = Some(self.steps);
+        stats.max_counter = Some(self.max_counter);
+        stats.head_reversals = Some(self.head_reversals);
+    }
+}
@@ -0,0 +1,135 @@
+// Simulation of one-way automata in a single left-to-right sweep over the input
+
+use hashbrown::HashSet;
+
+use crate::automaton::compiled::{CompiledAutom, Symbol, Tape};
+use crate::automaton::generic_autom::State;
+use crate::simulation::config::next_counter;
+use crate::simulation::stats::RunStats;
+use crate::simulation::budget::{Budget, Fault, Meter, Outcome, Stop};
+
+// Check if a string is accepted by a one-way automaton (see one_way::is_one_way)
+// Works for deterministic and nondeterministic automata alike, by keeping track of every
+// (state, counter) pair the automaton can be in at each cell of the input.
+// Stationary transitions can't pump the counter up in a one-way automaton, so each cell raises
+// the counter by less than the number of states, and the pairs at each cell are bounded by |input| * |states|^2
+pub fn streaming_procedure(autom : &CompiledAutom, input : &str) -> bool {
+    streaming_procedure_with_stats(autom, input).0
+}
+
+// Same as streaming_procedure, but also reports what the simulator did
+pub fn streaming_procedure_with_stats(autom : &CompiledAutom, input : &str) -> (bool, RunStats) {
+    let (outcome, stats) = streaming_procedure_with_budget(autom, input, &Budget::default());
+    (outcome.accepted(), stats)
+}
+
+// Same as streaming_procedure_with_stats, but gives up once the budget runs out
+pub fn streaming_procedure_with_budget(autom : &CompiledAutom, input : &str, budget : &Budget) -> (Outcome, RunStats) {
+    // Convert the input into a tape of the automaton's symbols
+    let tape = autom.encode(input);
+
+    let mut stats = RunStats {
+        steps : autom.is_deterministic().then_some(0),
+        max_counter : Some(0),
+        distinct_configs : Some(0),
+        ..RunStats::default()
+    };
+    let mut meter = Meter::new(budget);
+
+    let outcome = Outcome::from(sweep(autom, &tape, &mut stats, &mut meter));
+    (outcome, stats)
+}
+
+// Sweep over the tape, filling in stats along the way
+fn sweep(autom : &CompiledAutom, tape : &Tape, stats : &mut RunStats, meter : &mut Meter) -> Result<bool, Stop> {
+    // The counter can never get above this
+    let counter_bound = (tape.len() * autom.state_total as usize) as i32;
+
+    // The (state, counter) pairs that arrive at each cell by moving right
+    // Automaton always starts in state zero from lend with c=0
+    let mut arrivals : Vec<Vec<(State, i32)>> = vec![Vec::new(); tape.len()];
+    arrivals[0].push((0, 0));
+
+    let last = tape.len() - 1;
+
+    for read in 0..tape.len() {
+        let here = std::mem::take(&mut arrivals[read]);
+
+        // Moves past rend leave the read head on it
+        let accepted = visit_cell(autom, tape[read], read, read == last, here, counter_bound, stats, meter, |cell, pair| {
+            arrivals[cell.min(last)].push(pair);
+        })?;
+
+        // Accept as soon as some run gets to an accept state with an empty counter
+        if accepted {
+            return Ok(true);
+        }
+    }
+
+    // Every run halted, got stuck or looped without accepting
+    Ok(false)
+}
+
+// Follow every run from the pairs that arrive at the cell read (which holds symbol) until it moves off of it,
+// handing each pair that moves right on to hand_on along with the cell it moves to.
+// Nothing moves off of the last cell, so the cells after it don't need to be known yet.
+// Returns true if some run reaches an accept state with an empty counter on this cell, and fails if the
+// counter goes past counter_bound, which only happens if the automaton isn't one-way after all
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn visit_cell(
+    autom : &CompiledAutom,
+    symbol : Symbol,
+    read : usize,
+    last : bool,
+    arrivals : Vec<(State, i32)>,
+    counter_bound : i32,
+    stats : &mut RunStats,
+    meter : &mut Meter,
+    mut hand_on : impl FnMut(usize, (State, i32)),
+) -> Result<bool, Stop> {
+    // The pairs the automaton can be in on this cell
+    let mut seen : HashSet<(State, i32)> = HashSet::new();
+
+    let mut to_visit = arrivals;
+
+    while let Some((state, counter)) = to_visit.pop() {
+        if !seen.insert((state, counter)) { continue; }
+
+        meter.step()?;
+        let distinct_configs = stats.distinct_configs.get_or_insert(0);
+        *distinct_configs += 1;
+        meter.check_configs(*distinct_configs)?;
+        stats.max_counter = stats.max_counter.max(Some(counter));
+
+        if autom.check_if_halting(state) == Some(true) && counter == 0 {
+            return Ok(true);
+        }
+
+        for trans in autom.transitions(state, symbol, counter == 0) {
+            let next_counter = match next_counter(counter, trans.incr_by, autom.decr_zero) {
+                None => continue,
+                Some(c) => c,
+            };
+
+            // Only a deterministic automaton's transitions form a single run to count the steps of
+            if let Some(steps) = &mut stats.steps {
+                *steps += 1;
+            }
+
+            if next_counter > counter_bound {
+                return Err(Fault::CounterBound(counter_bound).into());
+            }
+
+            // Stay on this cell, or hand the pair on to the cell it moves to
+            let next_read = read + trans.move_by.max(0) as usize;
+
+            if next_read == read || last {
+                to_visit.push((trans.goto, next_counter));
+            } else {
+                hand_on(next_read, (trans.goto, next_counter));
+            }
+        }
+    }
+
+    Ok(false)
+}
@@ -0,0 +1,46 @@
+// Examples embedded in a program's comments, e.g.
+//   // @accept 0011 01
+//   // @reject 110 ""
+// Each annotation holds one or more words separated by whitespace, with "" for the empty word
+
+use crate::words::word_list::{ListedWord, EMPTY_WORD};
+
+// Find the examples in a program's source code
+// Annotations have to be on a line of their own, and are given the line they're on (starting from 1)
+pub fn parse_annotations(source : &str) -> Result<Vec<ListedWord>, String> {
+    let mut words = Vec::new();
+
+    for (i, line) in source.lines().enumerate() {
+        let line_number = i + 1;
+
+        let annotation = match line.trim().strip_prefix("//").map(|comment| comment.trim_start()) {
+            Some(annotation) if annotation.starts_with('@') => annotation,
+            _ => continue,
+        };
+
+        let mut fields = annotation.split_whitespace();
+
+        let expected = match fields.next().unwrap() {
+            "@accept" => true,
+            "@reject" => false,
+            other => return Err(format!("Line {}: expected @accept or @reject, found {:?}!", line_number, other)),
+        };
+
+        let before = words.len();
+
+        for word in fields {
+            let word = match word {
+                EMPTY_WORD => String::new(),
+                word => word.to_string(),
+            };
+
+            words.push(ListedWord { word, expected : Some(expected), line : line_number });
+        }
+
+        if words.len() == before {
+            return Err(format!("Line {}: no words after the annotation!", line_number));
+        }
+    }
+
+    Ok(words)
+}
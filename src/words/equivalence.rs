@@ -0,0 +1,61 @@
+// Bounded checks that two programs accept the same words
+
+use std::collections::HashSet;
+
+use crate::automaton::compiled::CompiledAutom;
+use crate::simulation::parallel::{Procedure, simulate_words};
+
+// An automaton along with the procedure to simulate it with
+pub type Simulator<'a> = (&'a CompiledAutom, Procedure);
+
+// A word that one automaton accepts and the other doesn't
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Distinction {
+    pub word : String,
+
+    // Whether it's the first automaton that accepts the word
+    pub first_accepts : bool,
+}
+
+// Check that two programs have the same alphabet, listing the letters only one of them has if not
+pub fn check_alphabets(first : &HashSet<char>, second : &HashSet<char>) -> Result<(), String> {
+    let only_in = |a : &HashSet<char>, b : &HashSet<char>| {
+        let mut letters : Vec<char> = a.difference(b).copied().collect();
+        letters.sort();
+        letters
+    };
+
+    let (only_first, only_second) = (only_in(first, second), only_in(second, first));
+
+    if only_first.is_empty() && only_second.is_empty() {
+        return Ok(());
+    }
+
+    Err(format!(
+        "The programs have different alphabets! Only the first has {:?} and only the second has {:?}",
+        only_first, only_second
+    ))
+}
+
+// Find the first of a list of words that two automata give different verdicts on
+// Words of the same length are simulated together, using up to threads threads, and the words after
+// a distinguishing one are never simulated. So if the words are sorted by length, this finds a shortest
+// distinguishing word without going any further than it
+pub fn find_distinction(first : Simulator, second : Simulator, words : &[String], threads : usize) -> Option<Distinction> {
+    for same_length in words.chunk_by(|a, b| a.chars().count() == b.chars().count()) {
+        let word_refs : Vec<&str> = same_length.iter().map(|word| word.as_str()).collect();
+
+        let first_results = simulate_words(first.0, &word_refs, first.1, threads);
+        let second_results = simulate_words(second.0, &word_refs, second.1, threads);
+
+        let distinction = same_length.iter()
+            .zip(first_results.iter().zip(second_results))
+            .find(|(_, (a, b))| **a != *b);
+
+        if let Some((word, (first_accepts, _))) = distinction {
+            return Some(Distinction { word : word.clone(), first_accepts : *first_accepts });
+        }
+    }
+
+    None
+}
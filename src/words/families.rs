@@ -0,0 +1,98 @@
+// Families of words for testing and benchmarking programs
+
+use crate::words::pattern::Pattern;
+
+// A small seeded random number generator (splitmix64), so that random families can be reproduced
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state : u64,
+}
+
+impl Rng {
+    // Constructor
+    pub fn new(seed : u64) -> Self {
+        Self { state : seed }
+    }
+
+    // The next random number
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    // A random number in 0..bound
+    pub fn below(&mut self, bound : usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+// The words a pattern describes for each n in ns, along with the n they came from
+// Random letters are picked from alphabet, and the same seed always gives the same family
+pub fn pattern_family(pattern : &Pattern, ns : &[usize], alphabet : &[char], seed : u64) -> Vec<(usize, String)> {
+    let mut rng = Rng::new(seed);
+
+    ns.iter()
+        .map(|n| (*n, pattern.instantiate(*n, alphabet, &mut rng)))
+        .collect()
+}
+
+// count random words of length len over alphabet
+pub fn random_words(alphabet : &[char], len : usize, count : usize, seed : u64) -> Vec<String> {
+    if alphabet.is_empty() && len > 0 {
+        panic!("Can't pick a random letter from an empty alphabet!");
+    }
+
+    let mut rng = Rng::new(seed);
+
+    (0..count)
+        .map(|_| (0..len).map(|_| alphabet[rng.below(alphabet.len())]).collect())
+        .collect()
+}
+
+// Every word over alphabet of length at most max_len, shortest first and in alphabetical order otherwise
+pub fn all_words(alphabet : &[char], max_len : usize) -> Vec<String> {
+    let mut alphabet = alphabet.to_vec();
+    alphabet.sort();
+
+    let mut words = vec![String::new()];
+    let mut last_length = vec![String::new()];
+
+    for _ in 0..max_len {
+        last_length = last_length.iter()
+            .flat_map(|word| alphabet.iter().map(move |c| format!("{}{}", word, c)))
+            .collect();
+
+        words.extend(last_length.iter().cloned());
+    }
+
+    words
+}
+
+// Parse a range of values of n, stepping through it step at a time
+// Ranges are written like Rust's: a..b leaves out b, a..=b includes it, and a on its own is just a
+pub fn parse_range(text : &str, step : usize) -> Result<Vec<usize>, String> {
+    let parse = |bound : &str| bound.trim().parse::<usize>()
+        .map_err(|_| format!("Invalid bound {:?} in range {:?}!", bound, text));
+
+    if step == 0 {
+        return Err("The step of a range can't be 0!".to_string());
+    }
+
+    let (start, end) = match text.split_once("..") {
+        None => {
+            let n = parse(text)?;
+            (n, n + 1)
+        },
+
+        Some((start, end)) => match end.strip_prefix('=') {
+            Some(end) => (parse(start)?, parse(end)? + 1),
+            None => (parse(start)?, parse(end)?),
+        },
+    };
+
+    Ok((start..end).step_by(step).collect())
+}
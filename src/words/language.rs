@@ -0,0 +1,171 @@
+// Enumerating the words a program accepts, up to a length bound
+
+use crate::automaton::compiled::{CompiledAutom, Symbol};
+use crate::automaton::generic_autom::State;
+use crate::simulation::budget::{Budget, Fault, Meter, Stop};
+use crate::simulation::parallel::{Procedure, simulate_words};
+use crate::simulation::stats::RunStats;
+use crate::simulation::streaming::visit_cell;
+use crate::words::families::all_words;
+
+// How many of the words of one length are accepted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthCount {
+    pub length : usize,
+    pub words : usize,
+    pub accepted : usize,
+}
+
+// The words of length at most some bound that an automaton accepts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Language {
+    // The accepted words, shortest first and in alphabetical order otherwise
+    pub accepted : Vec<String>,
+
+    // The counts for each length, from 0 up to the bound
+    pub counts : Vec<LengthCount>,
+}
+
+impl Language {
+    // Group words by length, given them in length-lexicographic order
+    fn from_accepted(alphabet_size : usize, max_len : usize, accepted : Vec<String>) -> Self {
+        let counts = (0..=max_len)
+            .map(|length| LengthCount {
+                length,
+                words : alphabet_size.pow(length as u32),
+                accepted : accepted.iter().filter(|word| word.chars().count() == length).count(),
+            })
+            .collect();
+
+        Self { accepted, counts }
+    }
+}
+
+// Run procedure on every word over alphabet of length at most max_len, using up to threads threads
+pub fn enumerate(autom : &CompiledAutom, alphabet : &[char], max_len : usize, procedure : Procedure, threads : usize) -> Language {
+    let words = all_words(alphabet, max_len);
+    let word_refs : Vec<&str> = words.iter().map(|word| word.as_str()).collect();
+
+    let results = simulate_words(autom, &word_refs, procedure, threads);
+
+    let accepted = words.into_iter()
+        .zip(results)
+        .filter_map(|(word, accepted)| accepted.then_some(word))
+        .collect();
+
+    Language::from_accepted(alphabet.len(), max_len, accepted)
+}
+
+// Same as enumerate, but for one-way automata (see one_way::is_one_way)
+// The runs on a prefix don't depend on what comes after it, so they're followed once and shared by every word
+// that starts with it, making this a single sweep over the tree of words rather than a sweep per word
+// Fails if the counter goes past the bound a one-way automaton keeps it under
+pub fn enumerate_one_way(autom : &CompiledAutom, alphabet : &[char], max_len : usize) -> Result<Language, Fault> {
+    let mut alphabet = alphabet.to_vec();
+    alphabet.sort();
+
+    let mut sweep = PrefixSweep {
+        autom,
+        letters : alphabet.iter().map(|c| (*c, autom.encode(&c.to_string())[1])).collect(),
+        max_len,
+        counter_bound : ((max_len + 2) * autom.state_total as usize) as i32,
+        accepted : vec![Vec::new(); max_len + 1],
+        stats : RunStats::default(),
+        meter : Meter::new(&Budget::default()),
+    };
+
+    // Follow the runs on lend, which every word starts with
+    // Automaton always starts in state zero from lend with c=0
+    let mut pending = Vec::new();
+    let accepted = sweep.visit(0, 0, false, vec![(0, 0)], &mut pending)?;
+
+    let mut word = String::new();
+    match accepted {
+        true  => sweep.accept_extensions(&mut word),
+        false => sweep.extend(&mut word, pending)?,
+    }
+
+    Ok(Language::from_accepted(alphabet.len(), max_len, sweep.accepted.concat()))
+}
+
+// The state of enumerate_one_way's walk over the tree of words
+struct PrefixSweep<'a> {
+    autom : &'a CompiledAutom,
+
+    // The sorted alphabet, along with the symbol each letter is read as
+    letters : Vec<(char, Symbol)>,
+
+    max_len : usize,
+
+    // The counter can never get above this on any of the words
+    counter_bound : i32,
+
+    // The accepted words of each length, in alphabetical order
+    accepted : Vec<Vec<String>>,
+
+    stats : RunStats,
+    meter : Meter,
+}
+
+impl PrefixSweep<'_> {
+    // Given the runs on a prefix, with the pairs that have moved past its end and the cell they moved to,
+    // find which of the words starting with the prefix are accepted
+    fn extend(&mut self, word : &mut String, pending : Vec<(usize, (State, i32))>) -> Result<(), Fault> {
+        // The cell after the prefix, which is rend if the word ends here
+        let next = word.chars().count() + 1;
+
+        // Moves past rend leave the read head on it, so every pending pair ends up there
+        let arrivals = pending.iter().map(|(_, pair)| *pair).collect();
+        if self.visit(1, next, true, arrivals, &mut Vec::new())? {
+            self.accepted[next - 1].push(word.clone());
+        }
+
+        if next - 1 == self.max_len { return Ok(()); }
+
+        for i in 0..self.letters.len() {
+            let (letter, symbol) = self.letters[i];
+
+            // Pairs that have moved further than the next cell carry on waiting
+            let (arrivals, mut still_pending) : (Vec<_>, Vec<_>) = pending.iter().partition(|(cell, _)| *cell == next);
+            let arrivals = arrivals.into_iter().map(|(_, pair)| pair).collect();
+
+            word.push(letter);
+
+            match self.visit(symbol, next, false, arrivals, &mut still_pending)? {
+                true  => self.accept_extensions(word),
+                false => self.extend(word, still_pending)?,
+            }
+
+            word.pop();
+        }
+
+        Ok(())
+    }
+
+    // Some run on the prefix accepts before reading past it, so every word that starts with it is accepted
+    fn accept_extensions(&mut self, word : &mut String) {
+        self.accepted[word.chars().count()].push(word.clone());
+
+        if word.chars().count() == self.max_len { return; }
+
+        for i in 0..self.letters.len() {
+            word.push(self.letters[i].0);
+            self.accept_extensions(word);
+            word.pop();
+        }
+    }
+
+    // Follow the runs on a cell, adding the pairs that move past it to pending
+    fn visit(&mut self, symbol : Symbol, read : usize, last : bool, arrivals : Vec<(State, i32)>, pending : &mut Vec<(usize, (State, i32))>) -> Result<bool, Fault> {
+        let result = visit_cell(
+            self.autom, symbol, read, last, arrivals, self.counter_bound, &mut self.stats, &mut self.meter,
+            |cell, pair| pending.push((cell, pair)),
+        );
+
+        match result {
+            Ok(accepted) => Ok(accepted),
+            Err(Stop::Fault(fault)) => Err(fault),
+            Err(Stop::Exceeded(_)) => unreachable!("Enumeration has no budget to run out of!"),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+pub mod word_list;
+
+pub mod pattern;
+
+pub mod families;
+
+pub mod language;
+
+pub mod equivalence;
+
+pub mod annotations;
+
+pub mod suite;
@@ -0,0 +1,228 @@
+// A small pattern language for describing families of words parameterised by n
+//
+// A pattern is a sequence of items, each of which can be followed by an exponent:
+//   0          the letter 0 (letters are the same as in alphabets, i.e. [a-zA-Z0-9])
+//   .          a letter picked at random from the alphabet
+//   (01)       a group of items
+//   x^3        x repeated 3 times
+//   x^{2n+1}   x repeated 2n + 1 times (the braces can hold any sum of multiples of n and constants)
+// e.g. 0^{n}1^{n}, (01)^{n}, 0^{n}1^{2n}, .^{n}
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::words::families::Rng;
+
+// A parsed pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pattern {
+    // A letter of the alphabet
+    Letter(char),
+
+    // A letter picked at random from the alphabet
+    AnyLetter,
+
+    // Patterns one after another
+    Concat(Vec<Pattern>),
+
+    // A pattern repeated a number of times that depends on n
+    Repeat(Box<Pattern>, Exponent),
+}
+
+// The number of times a pattern is repeated, times_n * n + plus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Exponent {
+    pub times_n : i64,
+    pub plus : i64,
+}
+
+impl Exponent {
+    // The number of repetitions for a given n
+    // Exponents that would be negative (e.g. n-1 when n = 0) give no repetitions
+    pub fn eval(&self, n : usize) -> usize {
+        (self.times_n * n as i64 + self.plus).max(0) as usize
+    }
+}
+
+impl Pattern {
+    // Parse a pattern
+    pub fn parse(text : &str) -> Result<Self, String> {
+        let mut chars = text.chars().peekable();
+        let pattern = parse_concat(&mut chars)?;
+
+        match chars.next() {
+            None => Ok(pattern),
+            Some(c) => Err(format!("Unexpected {:?} in pattern {:?}!", c, text)),
+        }
+    }
+
+    // Build the word the pattern describes for a given n
+    // Random letters are picked from alphabet using rng, so the same seed always gives the same word
+    pub fn instantiate(&self, n : usize, alphabet : &[char], rng : &mut Rng) -> String {
+        let mut word = String::new();
+        self.write(n, alphabet, rng, &mut word);
+        word
+    }
+
+    // Append the word the pattern describes to out
+    fn write(&self, n : usize, alphabet : &[char], rng : &mut Rng, out : &mut String) {
+        match self {
+            Pattern::Letter(c) => out.push(*c),
+
+            Pattern::AnyLetter => {
+                if alphabet.is_empty() {
+                    panic!("Can't pick a random letter from an empty alphabet!");
+                }
+
+                out.push(alphabet[rng.below(alphabet.len())]);
+            },
+
+            Pattern::Concat(patterns) => {
+                for pattern in patterns {
+                    pattern.write(n, alphabet, rng, out);
+                }
+            },
+
+            Pattern::Repeat(pattern, exponent) => {
+                for _ in 0..exponent.eval(n) {
+                    pattern.write(n, alphabet, rng, out);
+                }
+            },
+        }
+    }
+}
+
+// Parse items until the end of the pattern or a closing bracket
+fn parse_concat(chars : &mut Peekable<Chars>) -> Result<Pattern, String> {
+    let mut items = Vec::new();
+
+    while let Some(&c) = chars.peek() {
+        let item = match c {
+            ')' => break,
+
+            '(' => {
+                chars.next();
+                let group = parse_concat(chars)?;
+
+                if chars.next() != Some(')') {
+                    return Err("Unclosed bracket in pattern!".to_string());
+                }
+
+                group
+            },
+
+            '.' => {
+                chars.next();
+                Pattern::AnyLetter
+            },
+
+            c if c.is_ascii_alphanumeric() => {
+                chars.next();
+                Pattern::Letter(c)
+            },
+
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            },
+
+            c => return Err(format!("Unexpected {:?} in pattern!", c)),
+        };
+
+        // Check for an exponent
+        if chars.peek() == Some(&'^') {
+            chars.next();
+            let exponent = parse_exponent(chars)?;
+            items.push(Pattern::Repeat(Box::new(item), exponent));
+        } else {
+            items.push(item);
+        }
+    }
+
+    match items.len() {
+        1 => Ok(items.pop().unwrap()),
+        _ => Ok(Pattern::Concat(items)),
+    }
+}
+
+// Parse an exponent, either a single number or a sum in braces
+fn parse_exponent(chars : &mut Peekable<Chars>) -> Result<Exponent, String> {
+    match chars.peek() {
+        Some('{') => {
+            chars.next();
+
+            let mut body = String::new();
+            loop {
+                match chars.next() {
+                    None => return Err("Unclosed brace in pattern!".to_string()),
+                    Some('}') => break,
+                    Some(c) => body.push(c),
+                }
+            }
+
+            parse_sum(&body)
+        },
+
+        Some(c) if c.is_ascii_digit() => {
+            let mut digits = String::new();
+            while let Some(&c) = chars.peek() {
+                if !c.is_ascii_digit() { break; }
+                digits.push(c);
+                chars.next();
+            }
+
+            match digits.parse() {
+                Ok(plus) => Ok(Exponent { times_n : 0, plus }),
+                Err(_) => Err(format!("Exponent {} is too big!", digits)),
+            }
+        },
+
+        _ => Err("Expected a number or {...} after ^ in pattern!".to_string()),
+    }
+}
+
+// Parse a sum of terms like 2n, 3*n, n or 4, e.g. 2n+1 or n - 1
+fn parse_sum(text : &str) -> Result<Exponent, String> {
+    let text : String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if text.is_empty() {
+        return Err("Empty exponent in pattern!".to_string());
+    }
+
+    let mut exponent = Exponent { times_n : 0, plus : 0 };
+
+    // Split before each sign, keeping the sign with its term
+    let mut terms = Vec::new();
+    let mut term = String::new();
+    for c in text.chars() {
+        if (c == '+' || c == '-') && !term.is_empty() {
+            terms.push(std::mem::take(&mut term));
+        }
+        term.push(c);
+    }
+    terms.push(term);
+
+    for term in terms {
+        let (sign, body) = match term.strip_prefix('-') {
+            Some(body) => (-1, body),
+            None => (1, term.strip_prefix('+').unwrap_or(&term)),
+        };
+
+        let invalid = || format!("Invalid term {:?} in exponent {:?}!", term, text);
+
+        match body.strip_suffix('n') {
+            Some(coefficient) => {
+                let coefficient = coefficient.strip_suffix('*').unwrap_or(coefficient);
+                let coefficient : i64 = match coefficient {
+                    "" => 1,
+                    _ => coefficient.parse().map_err(|_| invalid())?,
+                };
+
+                exponent.times_n += sign * coefficient;
+            },
+
+            None => exponent.plus += sign * body.parse::<i64>().map_err(|_| invalid())?,
+        }
+    }
+
+    Ok(exponent)
+}
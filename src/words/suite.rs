@@ -0,0 +1,142 @@
+// Checking programs against the examples in their annotations, with every simulator that applies
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::compiler::compile;
+use crate::fuzz::differential::{Simulators, Verdict};
+use crate::simulation::budget::Outcome;
+use crate::words::annotations::parse_annotations;
+use crate::words::word_list::EMPTY_WORD;
+
+// An example that some simulator got wrong
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+    pub line : usize,
+    pub word : String,
+    pub expected : bool,
+
+    // The simulators that gave the wrong verdict
+    pub wrong : Vec<Verdict>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        let word = match self.word.is_empty() {
+            true  => EMPTY_WORD,
+            false => &self.word,
+        };
+
+        let names : Vec<&str> = self.wrong.iter().map(|(name, _)| name.as_str()).collect();
+
+        write!(
+            f, "line {}: expected {} to be {}, but {} {} it",
+            self.line, word, if self.expected {"accepted"} else {"rejected"},
+            names.join(", "), if self.expected {"rejected"} else {"accepted"},
+        )
+    }
+}
+
+// The results of checking a single program
+#[derive(Debug, Clone)]
+pub struct FileReport {
+    pub file : String,
+
+    // How many examples the program has
+    pub examples : usize,
+
+    // The examples that some simulator got wrong
+    pub mismatches : Vec<Mismatch>,
+
+    // How many (example, simulator) runs ran out of budget, and so weren't checked
+    pub undecided : usize,
+
+    // Why the program couldn't be checked at all, e.g. because it doesn't compile
+    pub error : Option<String>,
+}
+
+impl FileReport {
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty() && self.error.is_none()
+    }
+}
+
+// Every .twoc file in a directory and its subdirectories, in order
+pub fn discover(dir : &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(dir).map_err(|err| format!("Couldn't read {:?}: {}", dir, err))?;
+
+    let mut paths : Vec<PathBuf> = entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect();
+    paths.sort();
+
+    let mut files = Vec::new();
+
+    for path in paths {
+        if path.is_dir() {
+            files.extend(discover(&path)?);
+        } else if path.extension().is_some_and(|extension| extension == "twoc") {
+            files.push(path);
+        }
+    }
+
+    Ok(files)
+}
+
+// Check a program against its examples
+// Returns None if the program doesn't have any, without compiling it
+pub fn check_file(path : &Path) -> Option<FileReport> {
+    let file = path.to_string_lossy().to_string();
+
+    let report = |examples, error| Some(FileReport { file : file.clone(), examples, mismatches : Vec::new(), undecided : 0, error });
+
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(err) => return report(0, Some(format!("Couldn't read the file: {}", err))),
+    };
+
+    let examples = match parse_annotations(&source) {
+        Ok(examples) if examples.is_empty() => return None,
+        Ok(examples) => examples,
+        Err(err) => return report(0, Some(err)),
+    };
+
+    let compiled = match compile(&source) {
+        Ok(compiled) => compiled,
+        Err(diagnostics) => return report(examples.len(), Some(diagnostics.render(&source, &file))),
+    };
+
+    if let Some(listed) = examples.iter().find(|listed| !compiled.desugared.check_if_input_in_alphabet(&listed.word)) {
+        return report(examples.len(), Some(format!("line {}: {:?} contains characters that aren't in the program's alphabet", listed.line, listed.word)));
+    }
+
+    let simulators = Simulators::new(&compiled.desugared);
+    let mut mismatches = Vec::new();
+    let mut undecided = 0;
+
+    for listed in &examples {
+        let expected = listed.expected.unwrap();
+        let verdicts = simulators.verdicts(&listed.word);
+
+        undecided += verdicts.iter().filter(|(_, outcome)| matches!(outcome, Outcome::BudgetExceeded(_))).count();
+
+        let wrong : Vec<Verdict> = verdicts.into_iter()
+            .filter(|(_, outcome)| *outcome == Outcome::decided(!expected) || matches!(outcome, Outcome::Failed(_)))
+            .collect();
+
+        if !wrong.is_empty() {
+            mismatches.push(Mismatch { line : listed.line, word : listed.word.clone(), expected, wrong });
+        }
+    }
+
+    Some(FileReport { file, examples : examples.len(), mismatches, undecided, error : None })
+}
+
+// Check every program in a directory that has examples
+// Returns the reports for those programs and how many programs didn't have any
+pub fn check_dir(dir : &Path) -> Result<(Vec<FileReport>, usize), String> {
+    let files = discover(dir)?;
+    let reports : Vec<FileReport> = files.iter().filter_map(|path| check_file(path)).collect();
+    let skipped = files.len() - reports.len();
+
+    Ok((reports, skipped))
+}
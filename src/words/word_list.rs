@@ -0,0 +1,53 @@
+// Lists of words to run a program on, one word per line
+
+// An empty line can't hold the empty word, so it's written like this instead
+pub const EMPTY_WORD : &str = "\"\"";
+
+// A word from a list, along with the verdict it's expected to get
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListedWord {
+    // The word itself
+    pub word : String,
+
+    // Whether the word should be accepted, if the list says
+    pub expected : Option<bool>,
+
+    // The line of the list the word is on (starting from 1)
+    pub line : usize,
+}
+
+// Parse a word list
+// Each line holds a word, optionally followed by "accept" or "reject".
+// Blank lines and lines starting with # are skipped
+pub fn parse_word_list(text : &str) -> Result<Vec<ListedWord>, String> {
+    let mut words = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') { continue; }
+
+        let mut fields = line.split_whitespace();
+
+        let word = match fields.next().unwrap() {
+            EMPTY_WORD => String::new(),
+            word => word.to_string(),
+        };
+
+        let expected = match fields.next() {
+            None => None,
+            Some("accept") => Some(true),
+            Some("reject") => Some(false),
+            Some(other) => return Err(format!("Line {}: expected \"accept\" or \"reject\" after the word, found {:?}!", line_number, other)),
+        };
+
+        if let Some(extra) = fields.next() {
+            return Err(format!("Line {}: unexpected {:?} after the verdict!", line_number, extra));
+        }
+
+        words.push(ListedWord { word, expected, line : line_number });
+    }
+
+    Ok(words)
+}
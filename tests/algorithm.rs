@@ -0,0 +1,41 @@
+// Tests of the table of simulators that the command line, the C API and the fuzzer pick from
+
+#[cfg(test)]
+mod algorithm_tests {
+    use twoc::compile;
+    use twoc::simulation::algorithm::Algorithm;
+    use twoc::simulation::budget::{Budget, Outcome};
+
+    #[test]
+    pub fn names() {
+        for algorithm in Algorithm::ALL {
+            assert_eq!(Algorithm::from_name(algorithm.name()), Ok(algorithm));
+        }
+
+        assert_eq!(Algorithm::from_name("bogus"), Err("There's no algorithm called \"bogus\"".to_string()));
+    }
+
+    #[test]
+    pub fn applicability() {
+        let determ = compile(&std::fs::read_to_string("./twocprogs/determ/evens.twoc").unwrap()).unwrap();
+        let nondeterm = compile(&std::fs::read_to_string("./twocprogs/nondeterm/branch_while.twoc").unwrap()).unwrap();
+
+        assert!(Algorithm::Glueck.check(&determ).is_ok());
+        assert_eq!(Algorithm::Glueck.check(&nondeterm), Err("glueck can only simulate deterministic programs!".to_string()));
+        assert!(Algorithm::Rytter.check(&nondeterm).is_ok());
+
+        assert!(Algorithm::Streaming.check(&determ).is_ok());
+        assert_eq!(Algorithm::Streaming.check(&nondeterm), Err("streaming can only simulate one-way programs!".to_string()));
+
+        assert_eq!(Algorithm::pick(&determ, 4), Algorithm::Streaming);
+        assert_eq!(Algorithm::pick(&nondeterm, 1), Algorithm::Rytter);
+        assert_eq!(Algorithm::pick(&nondeterm, 4), Algorithm::RytterParallel);
+
+        // Every algorithm that can simulate a program agrees on it, on the automaton it's given
+        for algorithm in Algorithm::ALL.iter().filter(|algorithm| algorithm.check(&determ).is_ok()) {
+            let autom = algorithm.automaton(&determ);
+            assert_eq!(algorithm.run(&autom, "00", &Budget::default(), 2, false).0, Outcome::Accepted, "{}", algorithm.name());
+            assert_eq!(algorithm.run(&autom, "000", &Budget::default(), 2, false).0, Outcome::Rejected, "{}", algorithm.name());
+        }
+    }
+}
@@ -0,0 +1,76 @@
+// Tests of the examples embedded in programs' comments, and checking programs against them
+
+#[cfg(test)]
+mod annotations_tests {
+    use std::fs;
+    use std::path::Path;
+    use twoc::words::annotations::parse_annotations;
+    use twoc::words::suite::{check_dir, check_file, discover};
+    use twoc::words::word_list::ListedWord;
+
+    fn listed(word : &str, expected : bool, line : usize) -> ListedWord {
+        ListedWord { word : word.to_string(), expected : Some(expected), line }
+    }
+
+    #[test]
+    pub fn parsing() {
+        let source = "// @accept 0011 \"\"\ndecr_on_zero = false;\n  //@reject 1\n// @ignored\n// not an annotation @accept 0";
+
+        assert_eq!(
+            parse_annotations(&source.replace("\n// @ignored", "")),
+            Ok(vec![listed("0011", true, 1), listed("", true, 1), listed("1", false, 3)])
+        );
+
+        assert_eq!(parse_annotations(source), Err("Line 4: expected @accept or @reject, found \"@ignored\"!".to_string()));
+        assert_eq!(parse_annotations("// @reject"), Err("Line 1: no words after the annotation!".to_string()));
+        assert_eq!(parse_annotations("decr_on_zero = false;"), Ok(vec![]));
+    }
+
+    #[test]
+    pub fn example_programs() {
+        let (reports, skipped) = check_dir(Path::new("./twocprogs")).unwrap();
+
+        assert!(!reports.is_empty());
+        assert_eq!(reports.len() + skipped, discover(Path::new("./twocprogs")).unwrap().len());
+
+        for report in reports {
+            assert!(report.passed(), "{}: {:?} {:?}", report.file, report.error, report.mismatches.first().map(|m| m.to_string()));
+        }
+    }
+
+    #[test]
+    pub fn failures() {
+        let dir = std::env::temp_dir().join(format!("twoc_annotations_{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+
+        // The program without its own examples
+        let evens : String = fs::read_to_string("./twocprogs/determ/evens.twoc").unwrap()
+            .lines()
+            .filter(|line| !line.starts_with("// @"))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        fs::write(dir.join("nested/wrong.twoc"), format!("// @reject 00 000\n// @accept 0\n{}", evens)).unwrap();
+        fs::write(dir.join("broken.twoc"), "// @accept 0\ndecr_on_zero = false;").unwrap();
+        fs::write(dir.join("plain.twoc"), evens).unwrap();
+
+        let (reports, skipped) = check_dir(&dir).unwrap();
+        assert_eq!(skipped, 1);
+        assert_eq!(reports.len(), 2);
+
+        // Programs that don't compile can't be checked
+        assert!(reports[0].file.ends_with("broken.twoc"));
+        assert!(reports[0].error.is_some());
+
+        // Every simulator that applies gets both words wrong
+        let wrong = &reports[1];
+        assert_eq!(wrong.examples, 3);
+        assert_eq!(wrong.mismatches.len(), 2);
+        assert_eq!((wrong.mismatches[0].line, wrong.mismatches[0].word.as_str()), (1, "00"));
+        assert!(wrong.mismatches[0].wrong.iter().any(|(name, _)| *name == "glueck_array"));
+        assert!(wrong.mismatches[1].to_string().starts_with("line 2: expected 0 to be accepted, but streaming, glueck_nondeterm, "));
+
+        assert!(check_file(&dir.join("plain.twoc")).is_none());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,59 @@
+// Tests of the benchmarking helpers
+
+#[cfg(test)]
+mod bench_tests {
+    use twoc::bench::measure::{Timing, measure, summarise};
+    use twoc::bench::fit::fit_growth;
+    use twoc::bench::plot::{Series, render_svg};
+
+    #[test]
+    pub fn timings() {
+        assert_eq!(summarise(&[3.0, 1.0, 2.0]), Timing { median : 2.0, min : 1.0, max : 3.0 });
+        assert_eq!(summarise(&[4.0, 1.0, 2.0, 8.0]), Timing { median : 3.0, min : 1.0, max : 8.0 });
+        assert_eq!(summarise(&[5.0]), Timing { median : 5.0, min : 5.0, max : 5.0 });
+
+        // Every warm-up and timed run happens, and the last result is returned
+        let mut runs = 0;
+        let (timing, result) = measure(|| { runs += 1; runs }, 2, 3);
+        assert_eq!(runs, 5);
+        assert_eq!(result, 5);
+        assert!(timing.min <= timing.median && timing.median <= timing.max);
+    }
+
+    #[test]
+    pub fn growth_fits() {
+        let linear : Vec<(f64, f64)> = (1..=20).map(|n| (n as f64 * 10.0, n as f64 * 0.5)).collect();
+        let fit = fit_growth(&linear).unwrap();
+        assert!((fit.exponent - 1.0).abs() < 1e-9);
+        assert!((fit.constant - 0.05).abs() < 1e-9);
+        assert!((fit.r_squared - 1.0).abs() < 1e-9);
+
+        let cubic : Vec<(f64, f64)> = (1..=20).map(|n| (n as f64, 2.0 * (n as f64).powi(3))).collect();
+        let fit = fit_growth(&cubic).unwrap();
+        assert!((fit.exponent - 3.0).abs() < 1e-9);
+        assert!((fit.constant - 2.0).abs() < 1e-9);
+
+        // Points without logarithms are left out, and a single length can't be fitted
+        assert_eq!(fit_growth(&[(0.0, 1.0), (5.0, 0.0), (3.0, 2.0)]), None);
+        assert_eq!(fit_growth(&[(3.0, 1.0), (3.0, 2.0)]), None);
+        assert_eq!(fit_growth(&[]), None);
+    }
+
+    #[test]
+    pub fn svg_plots() {
+        let series = [
+            Series { name : "glueck".to_string(), points : vec![(10.0, 0.1), (20.0, 0.2)] },
+            Series { name : "rytter & co".to_string(), points : vec![(10.0, 1.0), (20.0, 8.0)] },
+        ];
+
+        let svg = render_svg("a < b", "Input length", "Median runtime (s)", &series);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert!(svg.contains("a &lt; b"));
+        assert!(svg.contains("rytter &amp; co"));
+
+        // Nothing to plot still gives a valid image
+        assert!(render_svg("empty", "x", "y", &[]).contains("</svg>"));
+    }
+}
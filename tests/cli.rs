@@ -0,0 +1,99 @@
+// Tests of the command line interface's exit codes and JSON output
+
+#[cfg(test)]
+mod cli_tests {
+    use std::io::Write;
+    use std::process::{Command, Output, Stdio};
+
+    fn twoc(args : &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_twoc")).args(args).output().expect("Couldn't run twoc")
+    }
+
+    // Run twoc with a word list on stdin
+    fn twoc_with_words(args : &[&str], words : &str) -> Output {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_twoc"))
+            .args(args)
+            .args(["--words", "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Couldn't run twoc");
+
+        child.stdin.take().unwrap().write_all(words.as_bytes()).unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    fn stdout(output : &Output) -> String {
+        String::from_utf8(output.stdout.clone()).unwrap()
+    }
+
+    #[test]
+    pub fn verdict_exit_codes() {
+        let accepted = twoc(&["run", "-f", "./twocprogs/determ/evens.twoc", "-w", "00"]);
+        assert_eq!(accepted.status.code(), Some(0));
+        assert!(stdout(&accepted).contains("\"00\" is accepted"));
+
+        let rejected = twoc(&["run", "-f", "./twocprogs/determ/evens.twoc", "-w", "000"]);
+        assert_eq!(rejected.status.code(), Some(1));
+
+        let exceeded = twoc(&["run", "-f", "./twocprogs/nondeterm/while_choose.twoc", "-w", "000", "--max-steps", "1"]);
+        assert_eq!(exceeded.status.code(), Some(4));
+    }
+
+    #[test]
+    pub fn batch_runs() {
+        let listed = twoc_with_words(&["run", "-f", "./twocprogs/determ/evens.twoc", "--algorithm", "glueck"], "00\n\"\"\n");
+        let lines : Vec<String> = stdout(&listed).lines().map(|line| line.to_string()).collect();
+        assert_eq!(lines[0], "word,verdict,algorithm,time_ms");
+        assert!(lines[1].starts_with("00,accept,glueck,"), "{}", lines[1]);
+        assert!(lines[2].starts_with(",accept,glueck,"), "{}", lines[2]);
+
+        let expected = twoc_with_words(&["run", "-f", "./twocprogs/determ/evens.twoc"], "00 accept\n000 reject\n");
+        assert_eq!(expected.status.code(), Some(0));
+
+        let mismatched = twoc_with_words(&["run", "-f", "./twocprogs/determ/evens.twoc"], "00 reject\n");
+        assert_eq!(mismatched.status.code(), Some(1));
+
+        // Words that couldn't be decided are runtime errors, even when the list doesn't say what to expect
+        let undecided = twoc_with_words(&["run", "-f", "./twocprogs/nondeterm/while_choose.twoc", "--max-steps", "1"], "000\n");
+        assert_eq!(undecided.status.code(), Some(4));
+        assert!(stdout(&undecided).contains("000,unknown,"));
+    }
+
+    #[test]
+    pub fn error_exit_codes() {
+        let missing = twoc(&["check", "-f", "./twocprogs/missing.twoc"]);
+        assert_eq!(missing.status.code(), Some(4));
+
+        // Deterministic simulators can't run nondeterministic programs
+        let wrong_algorithm = twoc(&["run", "-f", "./twocprogs/nondeterm/super_simple.twoc", "-w", "", "--algorithm", "glueck"]);
+        assert_eq!(wrong_algorithm.status.code(), Some(4));
+
+        let compile_error = twoc(&["check", "-f", "./README.md", "--format", "json"]);
+        assert_eq!(compile_error.status.code(), Some(3));
+        assert!(stdout(&compile_error).starts_with(r#"{"error":"compile","file":"./README.md","diagnostics":[{"severity":"error""#));
+
+        // Timing needs at least one run
+        let no_reps = twoc(&["bench", "-f", "./twocprogs/determ/evens.twoc", "--pattern", "0^{n}", "--n", "1..3", "--algorithms", "glueck", "--reps", "0"]);
+        assert_eq!(no_reps.status.code(), Some(2));
+    }
+
+    #[test]
+    pub fn json_output() {
+        let run = twoc(&["run", "-f", "./twocprogs/determ/evens.twoc", "-w", "00", "--algorithm", "glueck", "--format", "json"]);
+        let run = stdout(&run);
+        assert!(run.starts_with(r#"{"word":"00","verdict":"accept","algorithm":"glueck","time_ms":"#), "{}", run);
+        assert!(run.contains(r#""deterministic":true"#), "{}", run);
+
+        let list = twoc(&["run", "-f", "./twocprogs/determ/evens.twoc", "--all-up-to", "1", "--format", "json"]);
+        assert_eq!(stdout(&list).lines().count(), 2);
+
+        let summary = twoc(&["compile", "-f", "./twocprogs/nondeterm/super_simple.twoc", "--format", "json"]);
+        assert!(stdout(&summary).starts_with(r#"{"deterministic":false,"#));
+
+        for stage in ["sugared", "desugared", "contracted"] {
+            let ast = twoc(&["print-ast", "-f", "./twocprogs/sugar/goto.twoc", "--stage", stage, "--format", "json"]);
+            assert!(stdout(&ast).starts_with(r#"{"alphabet":"#), "{}", stage);
+        }
+    }
+}
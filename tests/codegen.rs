@@ -0,0 +1,130 @@
+// Tests of the Rust recognizers generated from deterministic automata, which are compiled with rustc
+// and checked against glueck_array
+
+#[cfg(test)]
+mod codegen_tests {
+    use std::fs;
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+    use twoc::automaton::autom::Autom;
+    use twoc::automaton::compiled::CompiledAutom;
+    use twoc::automaton::determ_construction;
+    use twoc::codegen::rust_recognizer;
+    use twoc::compile;
+    use twoc::fuzz::generate::{GenConfig, random_program};
+    use twoc::simulation::glueck_array::glueck_procedure;
+    use twoc::words::families::{Rng, random_words};
+    use twoc::words::suite::discover;
+
+    // The automata to generate recognizers for, with their alphabets
+    fn automata() -> Vec<(String, Autom, Vec<char>)> {
+        let mut automata = Vec::new();
+
+        for path in discover(std::path::Path::new("./twocprogs")).unwrap() {
+            let compiled = match compile(&fs::read_to_string(&path).unwrap()) {
+                Ok(compiled) if compiled.deterministic => compiled,
+                _ => continue,
+            };
+
+            let mut alphabet : Vec<char> = compiled.desugared.alpha.iter().copied().collect();
+            alphabet.sort();
+            automata.push((path.to_string_lossy().to_string(), compiled.autom, alphabet));
+        }
+
+        let config = GenConfig { nondeterminism : false, max_move : 2, ..GenConfig::default() };
+        let mut rng = Rng::new(49);
+
+        for i in 0..20 {
+            let mut prog = random_program(&config, &mut rng);
+            prog.contract();
+            automata.push((format!("random program {}", i), determ_construction::construct_from_prog(prog), config.alphabet.clone()));
+        }
+
+        automata
+    }
+
+    #[test]
+    pub fn generated_recognizers() {
+        let dir = std::env::temp_dir().join(format!("twoc_codegen_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        // Put every recognizer in its own module of one program, which runs the one each input line asks for
+        let mut names = Vec::new();
+        let mut frozen = Vec::new();
+        let mut alphabets = Vec::new();
+        let mut main = String::from("use std::io::BufRead;\n\n");
+
+        for (name, autom, alphabet) in automata() {
+            let module = match rust_recognizer(&autom) {
+                Ok(module) => module,
+
+                // Random programs can have overlapping guards
+                Err(err) => {
+                    assert!(name.starts_with("random"), "{}: {}", name, err);
+                    continue;
+                },
+            };
+
+            let i = names.len();
+            fs::write(dir.join(format!("recognizer{}.rs", i)), module).unwrap();
+            main.push_str(&format!("mod recognizer{};\n", i));
+
+            names.push(name);
+            frozen.push(CompiledAutom::new(&autom));
+            alphabets.push(alphabet);
+        }
+
+        assert!(names.iter().filter(|name| name.starts_with("random")).count() >= 10);
+
+        main.push_str("\nfn main() {\n    for line in std::io::stdin().lock().lines() {\n        let line = line.unwrap();\n");
+        main.push_str("        let (i, word) = line.split_once(' ').unwrap();\n        let accepted = match i {\n");
+        for i in 0..names.len() {
+            main.push_str(&format!("            \"{}\" => recognizer{}::accepts(word),\n", i, i));
+        }
+        main.push_str("            _ => unreachable!(),\n        };\n        println!(\"{}\", accepted);\n    }\n}\n");
+        fs::write(dir.join("main.rs"), main).unwrap();
+
+        let rustc = std::env::var("RUSTC").unwrap_or("rustc".to_string());
+        let compiled = Command::new(rustc)
+            .args(["--edition", "2021", "-O", "-D", "warnings", "main.rs", "-o", "recognizers"])
+            .current_dir(&dir)
+            .output()
+            .expect("Couldn't run rustc");
+        assert!(compiled.status.success(), "{}", String::from_utf8_lossy(&compiled.stderr));
+
+        // Run every recognizer on random words, and on the empty word
+        let mut cases = Vec::new();
+        for (i, alphabet) in alphabets.iter().enumerate() {
+            cases.push((i, String::new()));
+
+            for len in [1, 2, 3, 5, 8, 13, 40] {
+                cases.extend(random_words(alphabet, len, 8, (i * 100 + len) as u64).into_iter().map(|word| (i, word)));
+            }
+        }
+
+        let mut child = Command::new(dir.join("recognizers"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let input : String = cases.iter().map(|(i, word)| format!("{} {}\n", i, word)).collect();
+        child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+
+        let output = child.wait_with_output().unwrap();
+        let verdicts : Vec<String> = String::from_utf8(output.stdout).unwrap().lines().map(|line| line.to_string()).collect();
+        assert_eq!(verdicts.len(), cases.len());
+
+        for ((i, word), verdict) in cases.iter().zip(verdicts) {
+            assert_eq!(verdict, glueck_procedure(&frozen[*i], word).to_string(), "{} on {:?}", names[*i], word);
+        }
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    pub fn nondeterministic_automata() {
+        let compiled = compile(&fs::read_to_string("./twocprogs/nondeterm/while_choose.twoc").unwrap()).unwrap();
+        assert!(rust_recognizer(&compiled.autom).unwrap_err().starts_with("The automaton isn't deterministic: State"));
+    }
+}
@@ -0,0 +1,89 @@
+// Tests of the compiler pipeline exposed by the library
+
+#[cfg(test)]
+mod compiler_tests {
+    use std::fs;
+    use twoc::compile;
+    use twoc::compiler::{Severity, parse};
+    use twoc::simulation::{glueck_array, rytter};
+
+    // Every file in a directory of example programs
+    fn sources(dir : &str) -> Vec<(String, String)> {
+        let mut paths : Vec<_> = fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+        paths.sort();
+
+        paths.into_iter()
+            .map(|path| (path.to_str().unwrap().to_string(), fs::read_to_string(&path).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    pub fn example_programs() {
+        for dir in ["./twocprogs/determ", "./twocprogs/nondeterm", "./twocprogs/sugar"] {
+            for (filename, source) in sources(dir) {
+                let compiled = match compile(&source) {
+                    Ok(compiled) => compiled,
+                    Err(diagnostics) => panic!("{}:\n{}", filename, diagnostics),
+                };
+
+                assert_eq!(compiled.deterministic, !dir.ends_with("nondeterm"), "{}", filename);
+                assert!(compiled.warnings.is_empty(), "{}", filename);
+            }
+        }
+    }
+
+    #[test]
+    pub fn general_construction() {
+        let source = fs::read_to_string("./twocprogs/determ/evens.twoc").unwrap();
+        let compiled = compile(&source).unwrap();
+        let general = compiled.general();
+
+        // Both constructions accept the same words
+        for word in ["", "0", "00", "000", "0000"] {
+            assert_eq!(glueck_array::glueck_procedure(&compiled.frozen, word), rytter::rytter_procedure(&general, word), "{:?}", word);
+        }
+    }
+
+    #[test]
+    pub fn parse_errors() {
+        let source = "decr_on_zero = false;\nalphabet = ['a'];\n\ntwoc (string) {\n    move(1)\n}";
+        let diagnostics = compile(source).err().unwrap();
+
+        assert_eq!(diagnostics.0.len(), 1);
+        let diagnostic = &diagnostics.0[0];
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.line_col(source), Some((6, 1)));
+        assert!(diagnostic.message.contains("\"}\""), "{}", diagnostic.message);
+        assert_eq!(diagnostics.render(source, "prog.twoc"), format!("prog.twoc:6:1: error: {}", diagnostic.message));
+
+        assert!(parse("decr_on_zero = false;\nalphabet = ['a'];\n\ntwoc (string) {").is_err());
+    }
+
+    #[test]
+    pub fn parameters() {
+        // There has to be a parameter for each letter
+        let source = "decr_on_zero = false;\nalphabet = ['a', 'b'];\n\ntwoc (int A) { accept; }";
+        let diagnostics = compile(source).err().unwrap();
+        assert!(diagnostics.0[0].message.contains("parameters"), "{}", diagnostics);
+        assert_eq!(diagnostics.0[0].span.map(|(start, end)| &source[start..end]), Some("int A"));
+
+        // And only declared parameters can be used
+        let source = "decr_on_zero = false;\nalphabet = ['a'];\n\ntwoc (int A) { c += B; }";
+        let diagnostics = compile(source).err().unwrap();
+        assert_eq!(diagnostics.0[0].message, "Parameter \"B\" isn't declared");
+        assert_eq!(diagnostics.0[0].span.map(|(start, end)| &source[start..end]), Some("B"));
+        assert_eq!(diagnostics.render(source, "prog.twoc"), "prog.twoc:4:21: error: Parameter \"B\" isn't declared");
+    }
+
+    #[test]
+    pub fn warnings() {
+        let source = "decr_on_zero = false;\nalphabet = ['a'];\n\ntwoc (string) { if (read == 'a') { c++; } else if (read != 'b' && c != 0) { accept; } }";
+        let compiled = compile(source).unwrap();
+
+        // Warnings about a letter are put on the condition it's read in
+        assert_eq!(compiled.warnings.len(), 1);
+        assert_eq!(compiled.warnings[0].severity, Severity::Warning);
+        assert!(compiled.warnings[0].message.contains("'b'"));
+        assert_eq!(compiled.warnings[0].span.map(|(start, end)| &source[start..end]), Some("read != 'b' && c != 0"));
+    }
+}
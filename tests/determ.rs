@@ -1,22 +1,27 @@
 // Deterministic test bracket
 
-// Import grammar
-#[macro_use] extern crate lalrpop_util;
-lalrpop_mod!(pub grammar_rules, "/parser/sugar/sugar_grammar.rs");
-
 #[cfg(test)]
 mod determ_tests {
-    use std::fs;
-    use crate::grammar_rules::TwocParser;
+    use std::{fs, thread};
+    use std::time::{Duration, Instant};
+    use twoc::parser::sugar::sugar_grammar::TwocParser;
     use twoc::parser::sugar::convert_sugar::convert_sugar;
     use twoc::automaton::determ_construction::construct_from_prog; 
+    use twoc::automaton::autom::Autom;
+    use twoc::automaton::compiled::CompiledAutom;
+    use twoc::automaton::one_way::is_one_way;
+    use twoc::simulation::streaming::{self, streaming_procedure};
 
     // Swap these two lines to change algorithms
     use twoc::simulation::glueck_array::glueck_procedure;
     //use twoc::simulation::glueck::glueck_procedure;
+    use twoc::simulation::glueck;
+    use twoc::simulation::glueck_array;
+    use twoc::simulation::naive_determ;
+    use twoc::simulation::budget::{Budget, BudgetedProcedure, Exceeded, Fault, Outcome};
 
-    // Generic test function that runs a program on a single word and compares the outputs
-    fn generic_test(filename : &str, examples : &[(&str, bool)]) {
+    // Load, desugar and construct the automaton for a program
+    fn load_autom(filename : &str) -> Autom {
         // Declare parser for Twoc rule
         let parser = TwocParser::new();
 
@@ -38,12 +43,23 @@ mod determ_tests {
         prog.contract();
 
         // Construct the automaton from the program
-        let autom = construct_from_prog(prog);
+        construct_from_prog(prog)
+    }
+
+    // Generic test function that runs a program on a single word and compares the outputs
+    fn generic_test(filename : &str, examples : &[(&str, bool)]) {
+        let autom = load_autom(filename);
+        let compiled = CompiledAutom::new(&autom);
 
         // Check that each of the words gives the correct answer
         for (word, expected) in examples {
-            let glueck_output = glueck_procedure(&autom, word);
+            let glueck_output = glueck_procedure(&compiled, word);
             assert_eq!(glueck_output, *expected);
+
+            // One-way automata can also be checked in a single sweep
+            if is_one_way(&autom) {
+                assert_eq!(streaming_procedure(&compiled, word), *expected, "streaming on {:?}", word);
+            }
         }
     }
 
@@ -78,6 +94,25 @@ mod determ_tests {
         generic_test("./twocprogs/determ/zeros_then_ones.twoc", &test_words);
     }
 
+    // naive_determ has to accept as soon as it reaches an accept state with an empty counter, even when
+    // decr_on_zero is false and so the accept state's decrement can't fire
+    #[test]
+    pub fn naive_halting() {
+        let programs = [
+            ("./twocprogs/determ/evens.twoc", vec![("", true), ("00", true), ("000", false), ("0000000", false), ("00000000", true)]),
+            ("./twocprogs/determ/zeros_then_ones.twoc", vec![("", true), ("0011", true), ("0001111", false), ("10", false)]),
+            ("./twocprogs/determ/equal_zeros_ones.twoc", vec![("", true), ("0101101010", true), ("11001", false)]),
+        ];
+
+        for (filename, words) in programs {
+            let compiled = CompiledAutom::new(&load_autom(filename));
+
+            for (word, expected) in words {
+                assert_eq!(naive_determ::naive(&compiled, word), expected, "{} on {:?}", filename, word);
+            }
+        }
+    }
+
     #[test]
     pub fn x_plus_y_is_z() {
         let test_words = [
@@ -172,4 +207,181 @@ mod determ_tests {
 
         generic_test("./twocprogs/determ/upower.twoc", &test_words);
     }
-}
\ No newline at end of file
+
+    // Both glueck procedures should run on long inputs without growing the native stack
+    #[test]
+    pub fn long_input() {
+        let autom = load_autom("./twocprogs/determ/equal_zeros_ones.twoc");
+        let compiled = CompiledAutom::new(&autom);
+
+        let n = 100000;
+        let accepted = "0".repeat(n) + &"1".repeat(n);
+        let rejected = "0".repeat(n) + &"1".repeat(n + 1);
+
+        // Run on a thread with a small stack so that any recursion would overflow
+        std::thread::Builder::new()
+            .stack_size(1 << 16)
+            .spawn(move || {
+                assert!(glueck_procedure(&compiled, &accepted));
+                assert!(!glueck_procedure(&compiled, &rejected));
+
+                assert!(glueck::glueck_procedure(&compiled, &accepted));
+                assert!(!glueck::glueck_procedure(&compiled, &rejected));
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    // Programs that never move left and can't pump the counter in place are one-way
+    #[test]
+    pub fn one_way_analysis() {
+        let programs = [
+            ("./twocprogs/determ/evens.twoc", true),
+            ("./twocprogs/determ/zeros_then_ones.twoc", true),
+            ("./twocprogs/determ/equal_zeros_ones.twoc", false),
+            ("./twocprogs/determ/upower.twoc", false),
+        ];
+
+        for (filename, expected) in programs {
+            assert_eq!(is_one_way(&load_autom(filename)), expected, "{}", filename);
+        }
+
+        // A single sweep handles long inputs without any of the two-way machinery
+        let autom = load_autom("./twocprogs/determ/zeros_then_ones.twoc");
+        let compiled = CompiledAutom::new(&autom);
+        let n = 100000;
+        assert!(streaming_procedure(&compiled, &("0".repeat(n) + &"1".repeat(n))));
+        assert!(!streaming_procedure(&compiled, &("0".repeat(n) + &"1".repeat(n - 1))));
+    }
+
+    // Run statistics should match what the programs obviously do
+    #[test]
+    pub fn run_stats() {
+        let autom = load_autom("./twocprogs/determ/evens.twoc");
+        let compiled = CompiledAutom::new(&autom);
+
+        // evens counts the zeros in one sweep, then empties the counter
+        let (accepted, stats) = naive_determ::naive_with_stats(&compiled, "000000");
+        assert!(accepted);
+        assert_eq!(stats.max_counter, Some(6));
+        assert_eq!(stats.head_reversals, Some(0));
+        assert_eq!(stats.distinct_configs.unwrap() as u64, stats.steps.unwrap() + 1);
+        assert_eq!(stats.table_entries, None);
+
+        // Glueck can only ever follow fewer transitions than the plain run
+        let (accepted, glueck_stats) = glueck_array::glueck_procedure_with_stats(&compiled, "000000");
+        assert!(accepted);
+        assert!(glueck_stats.steps <= stats.steps);
+        assert!(glueck_stats.table_entries.unwrap() <= glueck_stats.distinct_configs.unwrap());
+        assert_eq!(glueck::glueck_procedure_with_stats(&compiled, "000000").1, glueck_stats);
+
+        // upower sweeps back and forth over the input
+        let autom = load_autom("./twocprogs/determ/upower.twoc");
+        let compiled = CompiledAutom::new(&autom);
+
+        let (accepted, stats) = naive_determ::naive_with_stats(&compiled, "00000000");
+        assert!(accepted);
+        assert_eq!(stats.max_counter, Some(8));
+        assert!(stats.head_reversals.unwrap() >= 4);
+
+        // The single sweep of a deterministic one-way automaton follows the same transitions as the plain run
+        let autom = load_autom("./twocprogs/determ/zeros_then_ones.twoc");
+        let compiled = CompiledAutom::new(&autom);
+
+        for word in ["", "00001111", "0001111", "1010"] {
+            let (_, stats) = streaming::streaming_procedure_with_stats(&compiled, word);
+            assert!(stats.steps.is_some());
+            assert_eq!(stats.steps, naive_determ::naive_with_stats(&compiled, word).1.steps, "{:?}", word);
+        }
+    }
+
+    // naive_determ finds loops without keeping the configs it's been through, unless it's asked to count them
+    #[test]
+    pub fn naive_loops() {
+        let source = "decr_on_zero = false;\nalphabet = ['0'];\ntwoc (string) {\n    move(1);\n    while (read == '0') { move(1); move(-1); }\n}\n";
+        let compiled = twoc::compile(source).unwrap().frozen;
+
+        assert!(!naive_determ::naive(&compiled, ""));
+        assert!(!naive_determ::naive(&compiled, "0"));
+
+        let (outcome, stats) = naive_determ::naive_with_budget(&compiled, "000", &Budget::default());
+        assert_eq!(outcome, Outcome::Rejected);
+        assert_eq!(stats.distinct_configs, None);
+
+        let (accepted, stats) = naive_determ::naive_with_stats(&compiled, "000");
+        assert!(!accepted);
+        assert!(stats.distinct_configs.is_some());
+    }
+
+    // Simulators should give up as soon as they run into any part of a budget
+    #[test]
+    pub fn budgets() {
+        let autom = load_autom("./twocprogs/determ/upower.twoc");
+        let compiled = CompiledAutom::new(&autom);
+        let word = "0".repeat(64);
+
+        // Running out of steps stops the run with exactly that many steps taken
+        let budget = Budget { max_steps : Some(100), ..Budget::default() };
+        let (outcome, stats) = naive_determ::naive_with_budget(&compiled, &word, &budget);
+        assert_eq!(outcome, Outcome::BudgetExceeded(Exceeded::Steps));
+        assert_eq!(stats.steps, Some(100));
+
+        let (outcome, stats) = glueck::glueck_procedure_with_budget(&compiled, &word, &budget);
+        assert_eq!(outcome, Outcome::BudgetExceeded(Exceeded::Steps));
+        assert_eq!(stats.steps, Some(100));
+
+        // The array tables are too big to make at all
+        let budget = Budget { max_configs : Some(100), ..Budget::default() };
+        let (outcome, _) = glueck_array::glueck_procedure_with_budget(&compiled, &word, &budget);
+        assert_eq!(outcome, Outcome::BudgetExceeded(Exceeded::Configs));
+
+        let (outcome, stats) = glueck::glueck_procedure_with_budget(&compiled, &word, &budget);
+        assert_eq!(outcome, Outcome::BudgetExceeded(Exceeded::Configs));
+        assert_eq!(stats.distinct_configs, Some(101));
+
+        // A deadline that has already passed stops the run straight away
+        let budget = Budget { deadline : Some(Instant::now()), ..Budget::default() };
+        thread::sleep(Duration::from_millis(1));
+        let (outcome, _) = glueck_array::glueck_procedure_with_budget(&compiled, &word, &budget);
+        assert_eq!(outcome, Outcome::BudgetExceeded(Exceeded::Deadline));
+
+        // The single sweep of a one-way automaton runs under a budget too
+        let one_way_autom = load_autom("./twocprogs/determ/zeros_then_ones.twoc");
+        let one_way_compiled = CompiledAutom::new(&one_way_autom);
+        let budget = Budget { max_steps : Some(100), ..Budget::default() };
+        let (outcome, stats) = streaming::streaming_procedure_with_budget(&one_way_compiled, &word, &budget);
+        assert_eq!(outcome, Outcome::BudgetExceeded(Exceeded::Steps));
+        assert_eq!(stats.distinct_configs, Some(100));
+
+        // A big enough budget doesn't change anything
+        let budget = Budget {
+            max_steps : Some(1_000_000),
+            max_configs : Some(1_000_000),
+            deadline : Some(Instant::now() + Duration::from_secs(60)),
+        };
+        let (outcome, stats) = glueck_array::glueck_procedure_with_budget(&compiled, &word, &budget);
+        assert_eq!(outcome, Outcome::Accepted);
+        assert_eq!(stats, glueck_array::glueck_procedure_with_stats(&compiled, &word).1);
+    }
+
+    // The deterministic simulators fail, rather than panicking, on an automaton where more than one
+    // transition can fire
+    #[test]
+    pub fn nondeterministic_automata() {
+        let source = fs::read_to_string("./twocprogs/nondeterm/while_choose.twoc").unwrap();
+        let compiled = twoc::compile(&source).unwrap().general();
+        let budget = Budget::default();
+
+        let procedures : [(&str, BudgetedProcedure); 3] = [
+            ("glueck", glueck::glueck_procedure_with_budget),
+            ("glueck_array", glueck_array::glueck_procedure_with_budget),
+            ("naive_determ", naive_determ::naive_with_budget),
+        ];
+
+        for (name, procedure) in procedures {
+            let (outcome, _) = procedure(&compiled, "0010", &budget);
+            assert!(matches!(outcome, Outcome::Failed(Fault::Nondeterministic(_))), "{}: {:?}", name, outcome);
+        }
+    }
+}
@@ -2,7 +2,7 @@
 
 // Import grammar
 #[macro_use] extern crate lalrpop_util;
-lalrpop_mod!(pub grammar_rules, "/parser/grammar_rules.rs");
+lalrpop_mod!(#[allow(clippy::all)] pub grammar_rules, "/parser/grammar_rules.rs");
 
 #[cfg(test)]
 mod determ_bench {
@@ -12,18 +12,27 @@ mod determ_bench {
 
     use crate::grammar_rules::TwocParser;
     use twoc::automaton::determ_construction; 
-    use twoc::automaton::autom::Autom;
+    use twoc::automaton::compiled::CompiledAutom;
     use twoc::simulation::glueck::glueck_procedure;
     use twoc::simulation::naive_determ::naive;
 
+    // The number of sizes to run a benchmark on
+    // The test suite only runs the first few, so set TWOC_BENCH_FULL to run the whole sweep and record its timings
+    fn sweep_length(tests : usize) -> usize {
+        match std::env::var_os("TWOC_BENCH_FULL") {
+            Some(_) => tests,
+            None => tests.min(3),
+        }
+    }
+
     // Functions used by threads
 
-    pub fn thread_function(autom : &Autom, word : String) {
-        assert_eq!(glueck_procedure(&autom, &word), true);
+    pub fn thread_function(autom : &CompiledAutom, word : String) {
+        assert!(glueck_procedure(autom, &word));
     }
 
-    pub fn naive_thread_function(autom : &Autom, word : String) {
-        assert_eq!(naive(&autom, &word), true);
+    pub fn naive_thread_function(autom : &CompiledAutom, word : String) {
+        assert!(naive(autom, &word));
     }
 
     #[test]
@@ -31,7 +40,7 @@ mod determ_bench {
         // Loop params
         let start = 10000;
         let step = 500;
-        let tests = 100;
+        let tests = sweep_length(100);
 
         // Initialise last
         let mut last = 0.0f32;
@@ -59,6 +68,7 @@ mod determ_bench {
 
         // Construct the automaton from the program
         let autom = determ_construction::construct_from_prog(prog);
+        let compiled = CompiledAutom::new(&autom);
 
         for n in (start..(start + step*tests + 1)).step_by(step) {
             // Generate a string of n 0s and n 1s
@@ -70,7 +80,7 @@ mod determ_bench {
             // Run test with a massive stack
             thread::scope(|s| {
                 thread::Builder::new().stack_size(0xFFFF * n)
-                .spawn_scoped(s, || thread_function(&autom, test_word))
+                .spawn_scoped(s, || thread_function(&compiled, test_word))
                 .unwrap();
             });
 
@@ -92,7 +102,7 @@ mod determ_bench {
         // Loop params
         let start = 0;
         let step = 30;
-        let tests = 400;
+        let tests = sweep_length(400);
 
         // Initialise last
         let mut last = 0.0f32;
@@ -120,6 +130,7 @@ mod determ_bench {
 
         // Construct the automaton from the program
         let autom = determ_construction::construct_from_prog(prog);
+        let compiled = CompiledAutom::new(&autom);
 
         for n in (start..(start + step*tests + 1)).step_by(step) {
             // Generate a string of n 0s and n 1s
@@ -131,7 +142,7 @@ mod determ_bench {
             // Run test with a massive stack
             thread::scope(|s| {
                 thread::Builder::new().stack_size(0xFFFF * n)
-                .spawn_scoped(s, || thread_function(&autom, test_word))
+                .spawn_scoped(s, || thread_function(&compiled, test_word))
                 .unwrap();
             });
 
@@ -153,7 +164,7 @@ mod determ_bench {
         // Loop params
         let start = 0;
         let step = 30;
-        let tests = 400;
+        let tests = sweep_length(400);
 
         // Initialise last
         let mut last = 0.0f32;
@@ -181,6 +192,7 @@ mod determ_bench {
 
         // Construct the automaton from the program
         let autom = determ_construction::construct_from_prog(prog);
+        let compiled = CompiledAutom::new(&autom);
 
         for n in (start..(start + step*tests + 1)).step_by(step) {
             // Generate a string of n 0s and n 1s
@@ -192,7 +204,7 @@ mod determ_bench {
             // Run test with a massive stack
             thread::scope(|s| {
                 thread::Builder::new().stack_size(0xFFFF * n)
-                .spawn_scoped(s, || naive_thread_function(&autom, test_word))
+                .spawn_scoped(s, || naive_thread_function(&compiled, test_word))
                 .unwrap();
             });
 
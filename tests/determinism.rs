@@ -0,0 +1,93 @@
+// Tests of the static determinism check
+
+#[cfg(test)]
+mod determinism_tests {
+    use std::fs;
+    use twoc::parser::sugar::sugar_grammar::TwocParser;
+    use twoc::parser::ast::{Cond, Readable};
+    use twoc::parser::program::Program;
+    use twoc::parser::sugar::convert_sugar::convert_sugar;
+    use twoc::automaton::{construction, determ_construction};
+    use twoc::automaton::autom::{Autom, Transition};
+    use twoc::automaton::generic_autom::TransitionTrait;
+    use twoc::automaton::determinism::find_conflicts;
+
+    fn load_program(filename : &str) -> Program {
+        let text = fs::read_to_string(filename).expect("File not found");
+
+        match TwocParser::new().parse(&text) {
+            Err(ref err) => panic!("Parse Error:\n{:?}", err),
+            Ok(prog) => {
+                let mut prog = convert_sugar(prog);
+                prog.contract();
+                prog
+            },
+        }
+    }
+
+    // Every file in a directory of example programs
+    fn programs(dir : &str) -> Vec<(String, Program)> {
+        let mut paths : Vec<_> = fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+        paths.sort();
+
+        paths.into_iter()
+            .map(|path| path.to_str().unwrap().to_string())
+            .map(|filename| { let prog = load_program(&filename); (filename, prog) })
+            .collect()
+    }
+
+    #[test]
+    pub fn deterministic_constructions() {
+        for dir in ["./twocprogs/determ", "./twocprogs/sugar"] {
+            for (filename, prog) in programs(dir) {
+                let conflicts = find_conflicts(&determ_construction::construct_from_prog(prog));
+                assert!(conflicts.is_empty(), "{}:\n{}", filename, conflicts[0]);
+            }
+        }
+    }
+
+    #[test]
+    pub fn nondeterministic_programs() {
+        for (filename, prog) in programs("./twocprogs/nondeterm") {
+            let conflicts = find_conflicts(&construction::construct_from_prog(prog));
+            assert!(!conflicts.is_empty(), "{}", filename);
+
+            // Every conflict is between transitions into a branch or a while-choose statement
+            for conflict in conflicts {
+                for (_, source) in [conflict.first, conflict.second] {
+                    let source = source.unwrap_or_default();
+                    assert!(source.starts_with("branch") || source.starts_with("while (choose)"), "{}: {}", filename, source);
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn overlapping_guards() {
+        let mut autom = Autom::new(['a', 'b'].into_iter().collect(), false);
+        let start = autom.introduce();
+        let first = autom.introduce();
+        let second = autom.introduce();
+
+        autom.add_transition(start, Transition::new_cond_trans(first, Cond::Read(Readable::Char('a'))));
+        autom.add_transition(start, Transition::new_cond_trans(second, Cond::CheckZero()));
+
+        // The same transition twice isn't a conflict
+        autom.add_transition(first, Transition::new_basic_block_trans(second, 1, 0));
+        autom.add_transition(first, Transition::new_basic_block_trans(second, 1, 0));
+
+        autom.sources.insert(start, "if (Read(Char('a')))".to_string());
+
+        let conflicts = find_conflicts(&autom);
+        assert_eq!(conflicts.len(), 1);
+
+        let conflict = &conflicts[0];
+        assert_eq!(conflict.state, start);
+        assert_eq!(conflict.overlap, vec![(Readable::Char('a'), true)]);
+        assert_eq!((conflict.first.0.goto, conflict.second.0.goto), (first, second));
+
+        let report = conflict.to_string();
+        assert!(report.contains("reading 'a' with c == 0"), "{}", report);
+        assert!(report.contains("from if (Read(Char('a')))"), "{}", report);
+    }
+}
@@ -0,0 +1,100 @@
+// Differential tests of the simulators on random programs
+
+#[cfg(test)]
+mod differential_tests {
+    use twoc::fuzz::generate::{GenConfig, random_program, random_sugar_program};
+    use twoc::compile;
+    use twoc::fuzz::differential::{Simulators, differential_test};
+    use twoc::fuzz::shrink::shrink;
+    use twoc::parser::ast::{Cond, Readable, Stmt};
+    use twoc::parser::program::Program;
+    use twoc::parser::sugar::convert_sugar::convert_sugar;
+    use twoc::simulation::algorithm::Algorithm;
+    use twoc::simulation::budget::Outcome;
+    use twoc::words::families::Rng;
+
+    // Check random programs made with config, panicking with the shrunk disagreement if there is one
+    fn check(config : &GenConfig, programs : usize, seed : u64) {
+        if let Some(disagreement) = differential_test(config, programs, 8, 5, seed) {
+            panic!("{}", disagreement);
+        }
+    }
+
+    #[test]
+    pub fn deterministic_core_programs() {
+        check(&GenConfig { nondeterminism : false, sugar : false, ..GenConfig::default() }, 100, 1);
+    }
+
+    #[test]
+    pub fn nondeterministic_core_programs() {
+        check(&GenConfig { sugar : false, ..GenConfig::default() }, 100, 2);
+    }
+
+    #[test]
+    pub fn sugared_programs() {
+        check(&GenConfig { nondeterminism : false, ..GenConfig::default() }, 60, 3);
+        check(&GenConfig::default(), 60, 4);
+    }
+
+    #[test]
+    pub fn long_moves_and_increments() {
+        check(&GenConfig { max_move : 3, max_incr : 3, sugar : false, ..GenConfig::default() }, 60, 5);
+    }
+
+    #[test]
+    pub fn generator_knobs() {
+        let mut rng = Rng::new(6);
+
+        for _ in 0..100 {
+            let deterministic = GenConfig { nondeterminism : false, alphabet : vec!['x', 'y', 'z'], ..GenConfig::default() };
+
+            let prog = random_program(&GenConfig { sugar : false, ..deterministic.clone() }, &mut rng);
+            assert!(prog.deterministic());
+            assert_eq!(prog.alpha, ['x', 'y', 'z'].into_iter().collect());
+
+            let prog = convert_sugar(random_sugar_program(&deterministic, &mut rng));
+            assert!(prog.deterministic());
+
+            let prog = random_program(&GenConfig { decr_zero : Some(false), ..GenConfig::default() }, &mut rng);
+            assert!(!prog.decr_zero);
+        }
+    }
+
+    #[test]
+    pub fn shrinking() {
+        let prog = Program::new(
+            vec![
+                Stmt::Move(3),
+                Stmt::While(
+                    Cond::And(Box::new(Cond::NotRead(Readable::REnd())), Box::new(Cond::CheckZero())),
+                    vec![Stmt::Incr(2), Stmt::If(Cond::Read(Readable::Char('b')), vec![Stmt::Reject()], vec![Stmt::Accept()])],
+                ),
+                Stmt::Branch(vec![vec![Stmt::Accept()], vec![Stmt::Move(-1)], vec![Stmt::Reject()]]),
+            ],
+            vec!['a', 'b'],
+            true,
+        );
+
+        // Anything with a reject statement on a word with a b in it "fails"
+        let has_reject = |stmts : &[Stmt]| format!("{:?}", stmts).contains("Reject");
+        let (shrunk, word) = shrink(prog, "aabab".to_string(), |prog, word| has_reject(&prog.stmts) && word.contains('b'));
+
+        assert_eq!(format!("{:?}", shrunk.stmts), "[Reject]");
+        assert_eq!(word, "b");
+    }
+
+    // Every simulator in the table is fuzzed, so a new one is checked without being listed here
+    #[test]
+    pub fn every_simulator() {
+        let compiled = compile(&std::fs::read_to_string("./twocprogs/determ/evens.twoc").unwrap()).unwrap();
+        assert!(compiled.deterministic && compiled.one_way);
+
+        let verdicts = Simulators::new(&compiled.desugared).verdicts("00");
+        for algorithm in Algorithm::ALL {
+            assert!(verdicts.iter().any(|(name, _)| name == algorithm.name()), "{} wasn't run", algorithm.name());
+        }
+
+        assert!(verdicts.iter().any(|(name, _)| name == "streaming (deterministic construction)"));
+        assert!(verdicts.iter().all(|(_, outcome)| *outcome == Outcome::Accepted));
+    }
+}
@@ -0,0 +1,104 @@
+// Tests of bounded equivalence checking between programs
+
+#[cfg(test)]
+mod equivalence_tests {
+    use std::fs;
+    use std::collections::HashSet;
+    use twoc::parser::sugar::sugar_grammar::TwocParser;
+    use twoc::parser::sugar::convert_sugar::convert_sugar;
+    use twoc::parser::program::Program;
+    use twoc::automaton::{construction, determ_construction};
+    use twoc::automaton::compiled::CompiledAutom;
+    use twoc::simulation::{glueck_array, naive_nondeterm, rytter_reachable};
+    use twoc::words::families::{all_words, random_words};
+    use twoc::words::equivalence::{Distinction, check_alphabets, find_distinction};
+
+    // Load and desugar a program
+    fn load_program(filename : &str) -> Program {
+        let text = fs::read_to_string(filename).expect("File not found");
+
+        match TwocParser::new().parse(&text) {
+            Err(ref err) => panic!("Parse Error:\n{:?}", err),
+            Ok(prog) => convert_sugar(prog),
+        }
+    }
+
+    // Load a program and compile its automaton, using the deterministic construction if it's deterministic
+    fn load_compiled(filename : &str) -> CompiledAutom {
+        let mut prog = load_program(filename);
+        prog.contract();
+
+        let autom = match prog.deterministic() {
+            true  => determ_construction::construct_from_prog(prog),
+            false => construction::construct_from_prog(prog),
+        };
+
+        CompiledAutom::new(&autom)
+    }
+
+    // Find the shortest word of length at most max_len that two deterministic programs disagree on
+    fn compare(first : &str, second : &str, max_len : usize) -> Option<Distinction> {
+        let first = load_compiled(first);
+        let second = load_compiled(second);
+
+        let words = all_words(&first.alpha.iter().copied().collect::<Vec<_>>(), max_len);
+
+        find_distinction(
+            (&first, glueck_array::glueck_procedure),
+            (&second, glueck_array::glueck_procedure),
+            &words,
+            3,
+        )
+    }
+
+    #[test]
+    pub fn desugaring_keeps_languages() {
+        assert_eq!(compare("./twocprogs/determ/evens.twoc", "./twocprogs/determ/evens_sugar.twoc", 12), None);
+    }
+
+    #[test]
+    pub fn shortest_distinctions() {
+        // The sugared version reads its arguments in order, but the other counts letters wherever they are
+        assert_eq!(
+            compare("./twocprogs/determ/x_plus_y_is_z.twoc", "./twocprogs/determ/x_plus_y_is_z_sugar.twoc", 4),
+            Some(Distinction { word : "zx".to_string(), first_accepts : true }),
+        );
+
+        assert_eq!(
+            compare("./twocprogs/determ/zeros_then_ones.twoc", "./twocprogs/determ/equal_zeros_ones.twoc", 4),
+            Some(Distinction { word : "10".to_string(), first_accepts : false }),
+        );
+
+        // Nothing is simulated past the bound
+        assert_eq!(compare("./twocprogs/determ/zeros_then_ones.twoc", "./twocprogs/determ/equal_zeros_ones.twoc", 1), None);
+    }
+
+    #[test]
+    pub fn random_samples() {
+        // Different simulators of the same program always agree
+        let compiled = load_compiled("./twocprogs/nondeterm/equal_or_double_zeros_ones.twoc");
+
+        let mut words = random_words(&['0', '1'], 8, 30, 7);
+        words.sort();
+
+        assert_eq!(
+            find_distinction((&compiled, rytter_reachable::rytter_procedure), (&compiled, naive_nondeterm::naive), &words, 2),
+            None,
+        );
+    }
+
+    #[test]
+    pub fn alphabets() {
+        let first : HashSet<char> = ['0', '1'].into_iter().collect();
+        let second : HashSet<char> = ['1', '2', '3'].into_iter().collect();
+
+        assert_eq!(check_alphabets(&first, &first.clone()), Ok(()));
+
+        let err = check_alphabets(&first, &second).unwrap_err();
+        assert!(err.contains("['0']") && err.contains("['2', '3']"), "{}", err);
+
+        let evens = load_program("./twocprogs/determ/evens.twoc");
+        let equal = load_program("./twocprogs/determ/equal_zeros_ones.twoc");
+        assert!(check_alphabets(&evens.alpha, &equal.alpha).is_err());
+    }
+}
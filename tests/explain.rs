@@ -0,0 +1,130 @@
+// Tests of explaining why deterministic runs reject
+
+#[cfg(test)]
+mod explain_tests {
+    use std::fs;
+    use twoc::parser::sugar::sugar_grammar::TwocParser;
+    use twoc::parser::ast::{Cond, Readable, Stmt};
+    use twoc::parser::program::Program;
+    use twoc::parser::sugar::convert_sugar::convert_sugar;
+    use twoc::automaton::determ_construction;
+    use twoc::automaton::autom::{Autom, Transition};
+    use twoc::automaton::generic_autom::TransitionTrait;
+    use twoc::automaton::compiled::CompiledAutom;
+    use twoc::simulation::budget::{Budget, Exceeded, Fault, Stop};
+    use twoc::simulation::explain::{Cause, explain, explain_with_budget};
+
+    fn compile(mut prog : Program) -> CompiledAutom {
+        prog.contract();
+        CompiledAutom::new(&determ_construction::construct_from_prog(prog))
+    }
+
+    fn load_compiled(filename : &str) -> CompiledAutom {
+        let text = fs::read_to_string(filename).expect("File not found");
+
+        match TwocParser::new().parse(&text) {
+            Err(ref err) => panic!("Parse Error:\n{:?}", err),
+            Ok(prog) => compile(convert_sugar(prog)),
+        }
+    }
+
+    #[test]
+    pub fn explicit_reject() {
+        let compiled = load_compiled("./twocprogs/determ/x_plus_y_is_z_sugar.twoc");
+
+        // The letters are out of order, so the parameter check rejects
+        let explanation = explain(&compiled, "zx", 4).unwrap().unwrap();
+        assert_eq!(explanation.cause, Cause::ExplicitReject);
+        assert_eq!(explanation.source.as_deref(), Some("reject"));
+        assert_eq!(explanation.last.read, Readable::Char('x'));
+
+        assert_eq!(explain(&compiled, "xyzz", 4), Ok(None));
+    }
+
+    #[test]
+    pub fn decrement_at_zero() {
+        let compiled = load_compiled("./twocprogs/determ/evens.twoc");
+
+        let explanation = explain(&compiled, "000", 3).unwrap().unwrap();
+        assert_eq!(explanation.cause, Cause::DecrementAtZero);
+        assert_eq!(explanation.last.config.counter, 0);
+        assert!(explanation.source.unwrap().contains("c += -"));
+
+        // The last few steps end with the last configuration
+        assert_eq!(explanation.steps.len(), 3);
+        assert_eq!(explanation.steps.last(), Some(&explanation.last));
+    }
+
+    #[test]
+    pub fn no_transition() {
+        // Runs that get past the last statement have nowhere to go
+        let compiled = compile(Program::new(
+            vec![Stmt::Move(1), Stmt::If(Cond::Read(Readable::REnd()), vec![Stmt::Accept()], vec![])],
+            vec!['a'],
+            true,
+        ));
+
+        let explanation = explain(&compiled, "a", 8).unwrap().unwrap();
+        assert_eq!(explanation.cause, Cause::NoTransition);
+        assert_eq!(explanation.last.read, Readable::Char('a'));
+        assert!(explanation.source.unwrap().starts_with("if"));
+
+        assert_eq!(explain(&compiled, "", 8), Ok(None));
+    }
+
+    #[test]
+    pub fn loops() {
+        // The run comes back to exactly the same configuration
+        let compiled = compile(Program::new(vec![Stmt::While(Cond::T(), vec![Stmt::Move(1)])], vec!['a'], true));
+
+        let explanation = explain(&compiled, "aa", 8).unwrap().unwrap();
+        assert_eq!(explanation.cause, Cause::Loop);
+        assert_eq!(explanation.last.read, Readable::REnd());
+
+        // The counter keeps growing, so no configuration ever repeats
+        let compiled = load_compiled("./twocprogs/determ/loops_forever.twoc");
+
+        let explanation = explain(&compiled, "00", 8).unwrap().unwrap();
+        assert_eq!(explanation.cause, Cause::Loop);
+        assert_eq!(explanation.last.read, Readable::LEnd());
+    }
+
+    #[test]
+    pub fn accept_with_counter() {
+        // An accept state without the transitions that empty the counter
+        let mut autom = Autom::new(['a'].into_iter().collect(), false);
+        let start = autom.introduce();
+        let accept = autom.introduce();
+
+        autom.add_transition(start, Transition::new_basic_block_trans(accept, 0, 1));
+        autom.make_accept_state(accept);
+
+        let explanation = explain(&CompiledAutom::new(&autom), "a", 8).unwrap().unwrap();
+        assert_eq!(explanation.cause, Cause::AcceptWithCounter);
+        assert_eq!(explanation.last.config.counter, 1);
+        assert_eq!(explanation.source, None);
+    }
+
+    // Nondeterministic automata can't be explained, and say so instead of panicking
+    #[test]
+    pub fn nondeterministic() {
+        let mut autom = Autom::new(['a'].into_iter().collect(), false);
+        let start = autom.introduce();
+        let left = autom.introduce();
+        let right = autom.introduce();
+
+        autom.add_transition(start, Transition::new_basic_block_trans(left, 1, 0));
+        autom.add_transition(start, Transition::new_basic_block_trans(right, 1, 0));
+        autom.make_accept_state(left);
+
+        assert_eq!(explain(&CompiledAutom::new(&autom), "a", 8), Err(Stop::Fault(Fault::Nondeterministic(start))));
+    }
+
+    #[test]
+    pub fn budgets() {
+        let compiled = load_compiled("./twocprogs/determ/evens.twoc");
+        let budget = Budget { max_steps : Some(2), ..Budget::default() };
+
+        assert_eq!(explain_with_budget(&compiled, "0000000", 8, &budget), Err(Stop::Exceeded(Exceeded::Steps)));
+    }
+}
@@ -0,0 +1,160 @@
+// A C program that uses twoc through its C API, which tests/ffi.rs compiles against the cdylib and runs
+// Exits with 0 if every check passes, and prints the ones that fail otherwise
+
+#include <pthread.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "twoc.h"
+
+// Checks can fail on several threads at once, so the count is behind a lock
+static int failures = 0;
+static pthread_mutex_t failures_lock = PTHREAD_MUTEX_INITIALIZER;
+
+#define CHECK(cond) do { \
+    if (!(cond)) { \
+        fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #cond); \
+        pthread_mutex_lock(&failures_lock); \
+        failures++; \
+        pthread_mutex_unlock(&failures_lock); \
+    } \
+} while (0)
+
+// The last error contains some text
+static int error_contains(const char *text) {
+    const char *error = twoc_last_error();
+    return error != NULL && strstr(error, text) != NULL;
+}
+
+static const char *EVENS =
+    "decr_on_zero = false;\n"
+    "alphabet = ['0'];\n"
+    "twoc (string) {\n"
+    "    move(1);\n"
+    "    while (read == '0') { c++; move(1); }\n"
+    "    while (c != 0) { c -= 2; }\n"
+    "    if (c == 0) { accept; }\n"
+    "}\n";
+
+static const char *WHILE_CHOOSE =
+    "decr_on_zero = false;\n"
+    "alphabet = ['0', '1'];\n"
+    "twoc (string) {\n"
+    "    while (choose) {\n"
+    "        if (read == '1') { c++; }\n"
+    "        if (read == '0') { if (c != 0) { c--; } }\n"
+    "        move(1);\n"
+    "    }\n"
+    "    if (c != 0) { accept; }\n"
+    "}\n";
+
+static void deterministic(void) {
+    TwocAutomaton *evens = twoc_compile(EVENS);
+    CHECK(evens != NULL);
+
+    TwocStats stats;
+    CHECK(twoc_stats(evens, &stats) == 0);
+    CHECK(stats.states > 0);
+    CHECK(stats.transitions > 0);
+    CHECK(stats.alphabet_size == 1);
+    CHECK(stats.deterministic);
+    CHECK(stats.one_way);
+
+    CHECK(twoc_run(evens, "", NULL, 0, 0) == TWOC_ACCEPTED);
+    CHECK(twoc_run(evens, "0000", NULL, 0, 0) == TWOC_ACCEPTED);
+    CHECK(twoc_run(evens, "000", NULL, 0, 0) == TWOC_REJECTED);
+
+    // Every simulator gives the same verdicts
+    const char *algorithms[] = {"streaming", "glueck", "glueck_array", "naive_determ", "rytter", "rytter_matrix", "rytter_reachable", "glueck_nondeterm"};
+    for (size_t i = 0; i < sizeof(algorithms) / sizeof(algorithms[0]); i++) {
+        CHECK(twoc_run(evens, "000000", algorithms[i], 0, 0) == TWOC_ACCEPTED);
+        CHECK(twoc_run(evens, "00000", algorithms[i], 0, 0) == TWOC_REJECTED);
+    }
+
+    CHECK(twoc_run(evens, "01", NULL, 0, 0) == TWOC_ERROR);
+    CHECK(error_contains("alphabet"));
+
+    CHECK(twoc_run(evens, "00", "bogus", 0, 0) == TWOC_ERROR);
+    CHECK(error_contains("no algorithm called \"bogus\""));
+
+    CHECK(twoc_run(evens, "0000000000", "naive_determ", 3, 0) == TWOC_ERROR);
+    CHECK(error_contains("gave up"));
+
+    twoc_free(evens);
+}
+
+static void nondeterministic(void) {
+    TwocAutomaton *while_choose = twoc_compile(WHILE_CHOOSE);
+    CHECK(while_choose != NULL);
+
+    TwocStats stats;
+    CHECK(twoc_stats(while_choose, &stats) == 0);
+    CHECK(!stats.deterministic);
+    CHECK(stats.alphabet_size == 2);
+
+    CHECK(twoc_run(while_choose, "0010", NULL, 0, 0) == TWOC_ACCEPTED);
+    CHECK(twoc_run(while_choose, "000", NULL, 0, 0) == TWOC_REJECTED);
+    CHECK(twoc_run(while_choose, "0010", "rytter_parallel", 0, 0) == TWOC_ACCEPTED);
+    CHECK(twoc_run(while_choose, "0010", "rytter_parallel", 0, 3) == TWOC_ACCEPTED);
+    CHECK(twoc_run(while_choose, "000", "rytter_parallel", 0, 3) == TWOC_REJECTED);
+
+    CHECK(twoc_run(while_choose, "0010", "glueck_array", 0, 0) == TWOC_ERROR);
+    CHECK(error_contains("deterministic"));
+
+    twoc_free(while_choose);
+}
+
+// Runs a nondeterministic automaton that several threads share, which makes them race to build its general construction
+static void *run_shared(void *automaton) {
+    for (int i = 0; i < 10; i++) {
+        CHECK(twoc_run(automaton, "0010", NULL, 0, 1) == TWOC_ACCEPTED);
+        CHECK(twoc_run(automaton, "000", "rytter_matrix", 0, 1) == TWOC_REJECTED);
+    }
+
+    // Each thread has its own last error
+    CHECK(twoc_run(automaton, "2", NULL, 0, 1) == TWOC_ERROR);
+    CHECK(error_contains("alphabet"));
+    return NULL;
+}
+
+static void threads(void) {
+    TwocAutomaton *while_choose = twoc_compile(WHILE_CHOOSE);
+    CHECK(while_choose != NULL);
+
+    pthread_t workers[4];
+    for (size_t i = 0; i < 4; i++) {
+        CHECK(pthread_create(&workers[i], NULL, run_shared, while_choose) == 0);
+    }
+    for (size_t i = 0; i < 4; i++) {
+        CHECK(pthread_join(workers[i], NULL) == 0);
+    }
+
+    twoc_free(while_choose);
+}
+
+static void errors(void) {
+    CHECK(twoc_compile("decr_on_zero = false;") == NULL);
+    CHECK(error_contains("source:1:"));
+
+    CHECK(twoc_compile(NULL) == NULL);
+    CHECK(twoc_stats(NULL, NULL) == TWOC_ERROR);
+    CHECK(twoc_run(NULL, "0", NULL, 0, 0) == TWOC_ERROR);
+    CHECK(error_contains("null"));
+
+    twoc_free(NULL);
+}
+
+int main(void) {
+    deterministic();
+    nondeterministic();
+    threads();
+    errors();
+
+    if (failures > 0) {
+        fprintf(stderr, "%d checks failed\n", failures);
+        return 1;
+    }
+
+    printf("All checks passed\n");
+    return 0;
+}
@@ -0,0 +1,37 @@
+// Tests of the C API: compiles the C program in tests/ffi.c against the header and the cdylib, and runs it
+
+#[cfg(test)]
+mod ffi_tests {
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    // The directory cargo puts the cdylib in, which is the one above the test's executable
+    fn library_dir() -> PathBuf {
+        let exe = std::env::current_exe().unwrap();
+        exe.parent().unwrap().parent().unwrap().to_path_buf()
+    }
+
+    #[test]
+    pub fn c_program() {
+        let lib = library_dir();
+        let program = lib.join(format!("twoc_ffi_test_{}", std::process::id()));
+
+        let cc = std::env::var("CC").unwrap_or("cc".to_string());
+        let compiled = Command::new(cc)
+            .args(["-std=c99", "-Wall", "-Wextra", "-Werror", "-pthread", "-Iinclude", "tests/ffi.c", "-o"])
+            .arg(&program)
+            .arg("-L")
+            .arg(&lib)
+            .arg(format!("-Wl,-rpath,{}", lib.display()))
+            .arg("-ltwoc")
+            .output()
+            .expect("Couldn't run the C compiler");
+        assert!(compiled.status.success(), "{}", String::from_utf8_lossy(&compiled.stderr));
+
+        let output = Command::new(&program).output().unwrap();
+        std::fs::remove_file(&program).unwrap();
+
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "All checks passed\n");
+    }
+}
@@ -0,0 +1,138 @@
+// Tests of the reference interpreters against each other and the simulators
+
+#[cfg(test)]
+mod interpret_tests {
+    use std::fs;
+    use twoc::parser::sugar::sugar_grammar::TwocParser;
+    use twoc::parser::ast::{Cond, Readable, Stmt};
+    use twoc::parser::program::Program;
+    use twoc::parser::sugar::program::Program as SugarProgram;
+    use twoc::parser::sugar::convert_sugar::convert_sugar;
+    use twoc::automaton::construction;
+    use twoc::automaton::compiled::CompiledAutom;
+    use twoc::simulation::naive_nondeterm;
+    use twoc::simulation::budget::{Budget, Exceeded, Outcome};
+    use twoc::interpret::{desugared, sugared};
+    use twoc::words::families::all_words;
+
+    // Parse a program without desugaring it
+    fn load_sugared(filename : &str) -> SugarProgram {
+        let text = fs::read_to_string(filename).expect("File not found");
+
+        match TwocParser::new().parse(&text) {
+            Err(ref err) => panic!("Parse Error:\n{:?}", err),
+            Ok(prog) => prog,
+        }
+    }
+
+    fn unlimited() -> Budget {
+        Budget::default()
+    }
+
+    #[test]
+    pub fn agrees_on_example_programs() {
+        let budget = Budget { max_steps : Some(50_000_000), ..Budget::default() };
+
+        // twocprogs/nontrivial is left out, since its program is still being written and doesn't parse yet
+        for dir in ["./twocprogs/determ", "./twocprogs/nondeterm", "./twocprogs/sugar"] {
+            let mut paths : Vec<_> = fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+            paths.sort();
+
+            for path in paths {
+                let filename = path.to_str().unwrap();
+
+                let sugared_prog = load_sugared(filename);
+                let prog = convert_sugar(load_sugared(filename));
+
+                let mut contracted = prog.clone();
+                contracted.contract();
+                let compiled = CompiledAutom::new(&construction::construct_from_prog(contracted.clone()));
+
+                let mut alphabet : Vec<char> = prog.alpha.iter().copied().collect();
+                alphabet.sort();
+
+                // Keep the number of words small for programs with big alphabets
+                let max_len = if alphabet.len() > 2 { 3 } else { 5 };
+
+                for word in all_words(&alphabet, max_len) {
+                    let simulated = naive_nondeterm::naive_with_budget(&compiled, &word, &budget).0;
+                    assert!(!matches!(simulated, Outcome::BudgetExceeded(_)), "{} on {:?}", filename, word);
+
+                    assert_eq!(sugared::interpret(&sugared_prog, &word, &budget), simulated, "{} on {:?} with sugar", filename, word);
+                    assert_eq!(desugared::interpret(&prog, &word, &budget), simulated, "{} on {:?} desugared", filename, word);
+                    assert_eq!(desugared::interpret(&contracted, &word, &budget), simulated, "{} on {:?} contracted", filename, word);
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn parameters() {
+        let prog = load_sugared("./twocprogs/determ/x_plus_y_is_z_sugar.twoc");
+
+        // Letters have to come in the order of the parameters
+        assert_eq!(sugared::interpret(&prog, "xyzz", &unlimited()), Outcome::Accepted);
+        assert_eq!(sugared::interpret(&prog, "xz", &unlimited()), Outcome::Accepted);
+        assert_eq!(sugared::interpret(&prog, "zx", &unlimited()), Outcome::Rejected);
+        assert_eq!(sugared::interpret(&prog, "xyz", &unlimited()), Outcome::Rejected);
+        assert_eq!(sugared::interpret(&prog, "", &unlimited()), Outcome::Accepted);
+    }
+
+    #[test]
+    pub fn loops_that_never_halt() {
+        // The counter grows forever without the run halting
+        let prog = convert_sugar(load_sugared("./twocprogs/determ/loops_forever.twoc"));
+        assert_eq!(desugared::interpret(&prog, "00", &unlimited()), Outcome::Rejected);
+
+        // The same, but with a run that accepts after the one that doesn't halt
+        let prog = Program::new(
+            vec![
+                Stmt::Branch(vec![
+                    vec![Stmt::While(Cond::T(), vec![Stmt::Incr(1), Stmt::Move(1)])],
+                    vec![Stmt::Incr(-1), Stmt::Accept()],
+                ]),
+            ],
+            vec!['a'],
+            false,
+        );
+
+        // Without decr_on_zero the accepting run gets stuck
+        assert_eq!(desugared::interpret(&prog, "aaa", &unlimited()), Outcome::Rejected);
+
+        let prog = Program { decr_zero : true, ..prog };
+        assert_eq!(desugared::interpret(&prog, "aaa", &unlimited()), Outcome::Accepted);
+    }
+
+    #[test]
+    pub fn nondeterminism() {
+        // Every choice of how many times to decrement is tried
+        let prog = load_sugared("./twocprogs/nondeterm/while_chooses.twoc");
+        assert_eq!(sugared::interpret(&prog, "xx", &unlimited()), Outcome::Accepted);
+
+        let prog = Program::new(
+            vec![
+                Stmt::Branch(vec![vec![Stmt::Incr(2)], vec![Stmt::Incr(3)]]),
+                Stmt::WhileChoose(vec![Stmt::Move(1), Stmt::Incr(-1)]),
+                Stmt::If(Cond::And(Box::new(Cond::CheckZero()), Box::new(Cond::Read(Readable::REnd()))), vec![Stmt::Accept()], vec![]),
+            ],
+            vec!['a'],
+            false,
+        );
+
+        // Accepted if the right endmarker is at most 3 moves away, by picking 2 or 3 and decrementing that many times
+        assert_eq!(desugared::interpret(&prog, "", &unlimited()), Outcome::Accepted);
+        assert_eq!(desugared::interpret(&prog, "aa", &unlimited()), Outcome::Accepted);
+        assert_eq!(desugared::interpret(&prog, "aaa", &unlimited()), Outcome::Rejected);
+    }
+
+    #[test]
+    pub fn budgets() {
+        let prog = convert_sugar(load_sugared("./twocprogs/determ/loops_forever.twoc"));
+        let budget = Budget { max_steps : Some(10), ..Budget::default() };
+
+        assert_eq!(desugared::interpret(&prog, "0000", &budget), Outcome::BudgetExceeded(Exceeded::Steps));
+
+        let prog = load_sugared("./twocprogs/determ/loops_forever.twoc");
+        assert_eq!(sugared::interpret(&prog, "0000", &budget), Outcome::BudgetExceeded(Exceeded::Steps));
+    }
+}
@@ -0,0 +1,101 @@
+// Tests of the JSON forms of programs, automata and diagnostics
+
+#[cfg(test)]
+mod json_tests {
+    use twoc::compile;
+    use twoc::json::{self, Json, ToJson};
+    use twoc::parser::ast::{Cond, Readable, Stmt};
+    use twoc::parser::program::Program;
+
+    #[test]
+    pub fn values() {
+        let value = Json::object(vec![
+            ("null", Json::Null),
+            ("bool", true.into()),
+            ("int", (-3).into()),
+            ("float", 0.5.into()),
+            ("nan", f64::NAN.into()),
+            ("str", "a \"quoted\"\\line\n\u{1}".into()),
+            ("array", vec![1, 2].into()),
+            ("option", Option::<i32>::None.into()),
+        ]);
+
+        assert_eq!(
+            value.to_string(),
+            r#"{"null":null,"bool":true,"int":-3,"float":0.5,"nan":null,"str":"a \"quoted\"\\line\n\u0001","array":[1,2],"option":null}"#
+        );
+
+        assert_eq!(value.get("int"), Some(&Json::Int(-3)));
+        assert_eq!(value.get("missing"), None);
+    }
+
+    #[test]
+    pub fn surrogates() {
+        assert_eq!(json::parse(r#""\ud83d\ude00""#), Ok(Json::Str("😀".to_string())));
+
+        // Surrogates that aren't part of a pair become replacement characters
+        assert_eq!(json::parse(r#""\ud800\u0030""#), Ok(Json::Str("\u{fffd}0".to_string())));
+        assert_eq!(json::parse(r#""\ud800\ud83d\ude00""#), Ok(Json::Str("\u{fffd}😀".to_string())));
+        assert_eq!(json::parse(r#""\ude00x""#), Ok(Json::Str("\u{fffd}x".to_string())));
+        assert_eq!(json::parse(r#""\ud800""#), Ok(Json::Str("\u{fffd}".to_string())));
+    }
+
+    #[test]
+    pub fn programs() {
+        let prog = Program::new(
+            vec![Stmt::While(Cond::NotRead(Readable::REnd()), vec![Stmt::Move(1)]), Stmt::Accept()],
+            vec!['b', 'a'],
+            true,
+        );
+
+        assert_eq!(
+            prog.to_json().to_string(),
+            concat!(
+                r#"{"alphabet":["a","b"],"decr_on_zero":true,"stmts":["#,
+                r#"{"stmt":"while","cond":{"cond":"not_read","symbol":"rend"},"body":[{"stmt":"move","by":1}]},"#,
+                r#"{"stmt":"accept"}]}"#,
+            )
+        );
+    }
+
+    #[test]
+    pub fn sugared_programs() {
+        let source = "decr_on_zero = false;\nalphabet = ['x', 'y'];\n\ntwoc (int X, int Y) { c = X; c -= Y; goto(rend); }";
+        let compiled = compile(source).unwrap();
+        let json = compiled.sugared.to_json();
+
+        // Parameters are listed in the order they're declared in
+        assert_eq!(json.get("parameters").unwrap().to_string(), r#"["X","Y"]"#);
+        assert_eq!(
+            json.get("stmts").unwrap().to_string(),
+            r#"[{"stmt":"assign","value":{"par":"X"}},{"stmt":"incr","value":{"neg_par":"Y"}},{"stmt":"goto","to":"rend"}]"#
+        );
+    }
+
+    #[test]
+    pub fn automata() {
+        let compiled = compile("decr_on_zero = false;\nalphabet = ['a'];\n\ntwoc (string) { move(1); accept; }").unwrap();
+        let json = compiled.autom.to_json();
+
+        let transitions = match json.get("transitions") {
+            Some(Json::Array(transitions)) => transitions.len(),
+            _ => panic!("No transitions in {}", json),
+        };
+        let total : usize = compiled.autom.state_map.values().map(|transitions| transitions.len()).sum();
+
+        assert_eq!(transitions, total);
+        assert_eq!(json.get("states"), Some(&Json::from(compiled.autom.state_total)));
+        assert_eq!(json.get("rejecting"), Some(&Json::Array(vec![])));
+        assert!(matches!(json.get("accepting"), Some(Json::Array(states)) if states.len() == 1));
+    }
+
+    #[test]
+    pub fn diagnostics() {
+        let source = "decr_on_zero = false;\nalphabet = ['a'];\n\ntwoc (string) {\n    move(1)\n}";
+        let diagnostics = compile(source).err().unwrap();
+
+        let json = diagnostics.to_json(source).to_string();
+        assert!(json.starts_with(r#"[{"severity":"error","message":"#), "{}", json);
+        assert!(json.ends_with(r#""line":6,"col":1}]"#), "{}", json);
+    }
+}
@@ -0,0 +1,91 @@
+// Tests of enumerating the words programs accept
+
+#[cfg(test)]
+mod language_tests {
+    use std::fs;
+    use twoc::parser::sugar::sugar_grammar::TwocParser;
+    use twoc::parser::sugar::convert_sugar::convert_sugar;
+    use twoc::automaton::{construction, determ_construction};
+    use twoc::automaton::autom::Autom;
+    use twoc::automaton::compiled::CompiledAutom;
+    use twoc::automaton::one_way::is_one_way;
+    use twoc::simulation::{glueck_array, naive_nondeterm, rytter_reachable};
+    use twoc::words::families::all_words;
+    use twoc::words::language::{LengthCount, enumerate, enumerate_one_way};
+
+    // Load and desugar a program, then construct its automaton with the general construction,
+    // or the deterministic one if determ is set. Also returns the program's alphabet
+    fn load_autom(filename : &str, determ : bool) -> (Autom, Vec<char>) {
+        let text = fs::read_to_string(filename).expect("File not found");
+
+        let sugared_prog = match TwocParser::new().parse(&text) {
+            Err(ref err) => panic!("Parse Error:\n{:?}", err),
+            Ok(prog) => prog,
+        };
+
+        let mut prog = convert_sugar(sugared_prog);
+        prog.contract();
+
+        let mut alphabet : Vec<char> = prog.alpha.iter().copied().collect();
+        alphabet.sort();
+
+        let autom = match determ {
+            true  => determ_construction::construct_from_prog(prog),
+            false => construction::construct_from_prog(prog),
+        };
+
+        (autom, alphabet)
+    }
+
+    #[test]
+    pub fn equal_zeros_ones() {
+        let (autom, alphabet) = load_autom("./twocprogs/determ/equal_zeros_ones.twoc", true);
+        let compiled = CompiledAutom::new(&autom);
+
+        let language = enumerate(&compiled, &alphabet, 4, glueck_array::glueck_procedure, 3);
+
+        let expected : Vec<String> = all_words(&alphabet, 4).into_iter()
+            .filter(|word| word.matches('0').count() == word.matches('1').count())
+            .collect();
+        assert_eq!(language.accepted, expected);
+        assert_eq!(&language.accepted[..4], ["", "01", "10", "0011"]);
+
+        assert_eq!(language.counts, vec![
+            LengthCount { length : 0, words : 1, accepted : 1 },
+            LengthCount { length : 1, words : 2, accepted : 0 },
+            LengthCount { length : 2, words : 4, accepted : 2 },
+            LengthCount { length : 3, words : 8, accepted : 0 },
+            LengthCount { length : 4, words : 16, accepted : 6 },
+        ]);
+    }
+
+    // Every example program gives the same language whichever way it's enumerated
+    #[test]
+    pub fn enumerations_agree() {
+        let mut one_way_programs = 0;
+
+        for dir in ["./twocprogs/determ", "./twocprogs/nondeterm"] {
+            let mut paths : Vec<_> = fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+            paths.sort();
+
+            for path in paths {
+                let filename = path.to_str().unwrap();
+                let (autom, alphabet) = load_autom(filename, false);
+                let compiled = CompiledAutom::new(&autom);
+
+                // Keep the number of words small for programs with big alphabets
+                let max_len = if alphabet.len() > 2 { 3 } else { 5 };
+
+                let naive = enumerate(&compiled, &alphabet, max_len, naive_nondeterm::naive, 4);
+                assert_eq!(enumerate(&compiled, &alphabet, max_len, rytter_reachable::rytter_procedure, 4), naive, "{}", filename);
+
+                if is_one_way(&autom) {
+                    one_way_programs += 1;
+                    assert_eq!(enumerate_one_way(&compiled, &alphabet, max_len).unwrap(), naive, "{} in one sweep", filename);
+                }
+            }
+        }
+
+        assert!(one_way_programs > 0);
+    }
+}
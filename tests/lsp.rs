@@ -0,0 +1,210 @@
+// Tests of the language server: formatting, the editor features and a session over stdio
+
+#[cfg(test)]
+mod lsp_tests {
+    use std::io::{BufReader, Write};
+    use std::process::{Command, Stdio};
+    use twoc::compiler::parse;
+    use twoc::json::{self, Json};
+    use twoc::lsp::analysis::{code_lens, definition, diagnostics, hover};
+    use twoc::lsp::format::format;
+    use twoc::lsp::protocol::{offset, position, read_message, write_message};
+    use twoc::lsp::server::Server;
+    use twoc::words::suite::discover;
+
+    const PROGRAM : &str = "decr_on_zero = false;\nalphabet = ['0', '1'];\n\ntwoc (int X, int Y) {\n    c = 2;\n    repeat (2) {\n        c += X;\n    }\n    c -= Y;\n    if (c == 0) {\n        accept;\n    }\n}\n";
+
+    // The byte offset of the nth occurrence of some text in the program
+    fn find(text : &str, n : usize) -> usize {
+        PROGRAM.match_indices(text).nth(n).unwrap().0
+    }
+
+    #[test]
+    pub fn parsing_json() {
+        let text = r#"{"a":[1,-2.5,true,null],"b":"\"é😀\n"}"#;
+        let value = json::parse(text).unwrap();
+
+        assert_eq!(value.at(&["a"]).and_then(Json::as_array).map(|a| a.len()), Some(4));
+        assert_eq!(value.get("b").and_then(Json::as_str), Some("\"é😀\n"));
+        assert_eq!(json::parse(&value.to_string()), Ok(value));
+
+        assert!(json::parse("{\"a\" 1}").is_err());
+        assert!(json::parse("[1] 2").is_err());
+    }
+
+    #[test]
+    pub fn positions() {
+        let source = "ab\n'é'😀x\n";
+        let x = source.find('x').unwrap();
+
+        assert_eq!(position(source, x), (1, 5));
+        assert_eq!(offset(source, 1, 5), x);
+        assert_eq!(offset(source, 0, 10), 2);
+        assert_eq!(offset(source, 5, 0), source.len());
+    }
+
+    #[test]
+    pub fn formatting() {
+        let messy = "// a comment\ndecr_on_zero=false; alphabet=[ '0','1' ];\ntwoc(string){\n\n\n  if(read=='0'&&c!=0){c--;move(-1);}else{ /* inline */ reject;}\n\n  accept; // done\n}";
+
+        assert_eq!(
+            format(messy).unwrap(),
+            "// a comment\ndecr_on_zero = false;\nalphabet = ['0', '1'];\ntwoc (string) {\n    if (read == '0' && c != 0) {\n        c--;\n        move(-1);\n    } else {\n        /* inline */ reject;\n    }\n\n    accept; // done\n}\n"
+        );
+
+        assert_eq!(format("decr_on_zero = false;"), None);
+
+        // Formatting every example program that parses keeps what it means, and formatting it again changes nothing
+        for path in discover(std::path::Path::new("./twocprogs")).unwrap() {
+            let source = std::fs::read_to_string(&path).unwrap();
+            if parse(&source).is_err() {
+                continue;
+            }
+
+            let formatted = match format(&source) {
+                Some(formatted) => formatted,
+                None => panic!("{:?} couldn't be formatted", path),
+            };
+
+            assert_eq!(format(&formatted).as_ref(), Some(&formatted), "{:?}", path);
+        }
+    }
+
+    #[test]
+    pub fn features() {
+        // Hovering over sugar shows what it expands to
+        let asgn = hover(PROGRAM, find("c = 2", 0) + 1).unwrap();
+        assert_eq!(asgn.span, (find("c = 2", 0), find("c = 2", 0) + 6));
+        assert!(asgn.contents.starts_with("`c = 2;` desugars to\n```\nwhile (CheckNotZero)\n"));
+
+        let repeat = hover(PROGRAM, find("repeat", 0)).unwrap();
+        assert_eq!(repeat.span, (find("repeat", 0), find("}", 0) + 1));
+        assert!(repeat.contents.contains("c += 1"));
+
+        assert_eq!(hover(PROGRAM, find("accept", 0)), None);
+        assert_eq!(hover(PROGRAM, find("alphabet", 0)), None);
+
+        // Parameters go to their declarations
+        assert_eq!(definition(PROGRAM, find("X", 1)), Some((find("X", 0), find("X", 0) + 1)));
+        assert_eq!(definition(PROGRAM, find("Y", 1)), Some((find("Y", 0), find("Y", 0) + 1)));
+        assert_eq!(definition(PROGRAM, find("c -= ", 0)), None);
+
+        let lens = code_lens(PROGRAM).unwrap();
+        assert_eq!(lens.span, (find("twoc", 0), find("twoc", 0) + 4));
+        assert!(lens.title.starts_with("Deterministic (two-way, "));
+
+        // Diagnostics are put on the part of the program they're about
+        let broken = PROGRAM.replace("c -= Y", "c -= Z");
+        let found = diagnostics(&broken);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].span, Some((find("c -= ", 0) + 5, find("c -= ", 0) + 6)));
+        assert_eq!(code_lens(&broken), None);
+
+        assert!(diagnostics(PROGRAM).is_empty());
+    }
+
+    fn request(id : i64, method : &str, params : Json) -> Json {
+        Json::object(vec![("jsonrpc", "2.0".into()), ("id", id.into()), ("method", method.into()), ("params", params)])
+    }
+
+    fn notification(method : &str, params : Json) -> Json {
+        Json::object(vec![("jsonrpc", "2.0".into()), ("method", method.into()), ("params", params)])
+    }
+
+    fn document(uri : &str, line : usize, character : usize) -> Json {
+        Json::object(vec![
+            ("textDocument", Json::object(vec![("uri", uri.into())])),
+            ("position", Json::object(vec![("line", line.into()), ("character", character.into())])),
+        ])
+    }
+
+    #[test]
+    pub fn server() {
+        let mut server = Server::new();
+
+        let initialized = server.handle(&request(1, "initialize", Json::object(vec![])));
+        assert_eq!(initialized[0].at(&["result", "capabilities", "hoverProvider"]), Some(&Json::Bool(true)));
+
+        let opened = server.handle(&notification("textDocument/didOpen", Json::object(vec![
+            ("textDocument", Json::object(vec![("uri", "file:///a.twoc".into()), ("text", PROGRAM.replace("c -= Y", "c -= Z").into())])),
+        ])));
+        assert_eq!(opened[0].at(&["params", "diagnostics"]).and_then(Json::as_array).map(|d| d.len()), Some(1));
+
+        let changed = server.handle(&notification("textDocument/didChange", Json::object(vec![
+            ("textDocument", Json::object(vec![("uri", "file:///a.twoc".into())])),
+            ("contentChanges", Json::Array(vec![Json::object(vec![("text", PROGRAM.into())])])),
+        ])));
+        assert_eq!(changed[0].at(&["params", "diagnostics"]), Some(&Json::Array(vec![])));
+
+        let hovered = server.handle(&request(2, "textDocument/hover", document("file:///a.twoc", 4, 5)));
+        assert_eq!(hovered[0].at(&["result", "range", "start", "line"]), Some(&Json::Int(4)));
+
+        let defined = server.handle(&request(3, "textDocument/definition", document("file:///a.twoc", 6, 13)));
+        assert_eq!(defined[0].at(&["result", "range", "start"]).map(Json::to_string), Some("{\"line\":3,\"character\":10}".to_string()));
+
+        let formatted = server.handle(&request(4, "textDocument/formatting", document("file:///a.twoc", 0, 0)));
+        assert_eq!(formatted[0].get("result"), Some(&Json::Array(vec![])));
+
+        let lenses = server.handle(&request(5, "textDocument/codeLens", document("file:///a.twoc", 0, 0)));
+        assert_eq!(lenses[0].at(&["result"]).and_then(Json::as_array).map(|l| l.len()), Some(1));
+
+        let unknown = server.handle(&request(6, "workspace/symbol", Json::Null));
+        assert_eq!(unknown[0].at(&["error", "code"]), Some(&Json::Int(-32601)));
+
+        assert_eq!(server.exit_code(&notification("exit", Json::Null)), Some(1));
+        server.handle(&request(7, "shutdown", Json::Null));
+        assert_eq!(server.exit_code(&notification("exit", Json::Null)), Some(0));
+    }
+
+    // Non-ASCII text doesn't split a character when it's turned into positions
+    #[test]
+    pub fn non_ascii_documents() {
+        let mut server = Server::new();
+        server.handle(&request(1, "initialize", Json::object(vec![])));
+
+        let source = "decr_on_zero = false;\nalphabet = [ é ];\ntwoc (string) { accept; }\n";
+        let opened = server.handle(&notification("textDocument/didOpen", Json::object(vec![
+            ("textDocument", Json::object(vec![("uri", "file:///b.twoc".into()), ("text", source.into())])),
+        ])));
+
+        let found = opened[0].at(&["params", "diagnostics"]).and_then(Json::as_array).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].at(&["range", "start"]).map(Json::to_string), Some("{\"line\":1,\"character\":13}".to_string()));
+        assert_eq!(found[0].at(&["range", "end"]).map(Json::to_string), Some("{\"line\":1,\"character\":14}".to_string()));
+
+        // Offsets in the middle of a character are moved back to its start
+        let e = source.find('é').unwrap();
+        assert_eq!(position(source, e + 1), position(source, e));
+
+        let changed = server.handle(&notification("textDocument/didChange", Json::object(vec![
+            ("textDocument", Json::object(vec![("uri", "file:///b.twoc".into())])),
+            ("contentChanges", Json::Array(vec![Json::object(vec![("text", "// ünïcödé 😀\n".into())])])),
+        ])));
+        assert_eq!(changed[0].at(&["params", "diagnostics"]).and_then(Json::as_array).map(|d| d.len()), Some(1));
+    }
+
+    #[test]
+    pub fn stdio() {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_twoc-lsp"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Couldn't run twoc-lsp");
+
+        let mut stdin = child.stdin.take().unwrap();
+        write_message(&mut stdin, &request(1, "initialize", Json::object(vec![]))).unwrap();
+        write_message(&mut stdin, &request(2, "shutdown", Json::Null)).unwrap();
+        write_message(&mut stdin, &notification("exit", Json::Null)).unwrap();
+        stdin.flush().unwrap();
+
+        let mut stdout = BufReader::new(child.stdout.take().unwrap());
+        let initialized = read_message(&mut stdout).unwrap().unwrap();
+        assert_eq!(initialized.at(&["result", "serverInfo", "name"]).and_then(Json::as_str), Some("twoc-lsp"));
+
+        let shut_down = read_message(&mut stdout).unwrap().unwrap();
+        assert_eq!(shut_down.get("id"), Some(&Json::Int(2)));
+        assert_eq!(read_message(&mut stdout), Ok(None));
+
+        assert_eq!(child.wait().unwrap().code(), Some(0));
+    }
+}
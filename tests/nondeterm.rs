@@ -2,24 +2,35 @@
 
 #![allow(unused_imports)]
 
-// Import grammar
-#[macro_use] extern crate lalrpop_util;
-lalrpop_mod!(pub grammar_rules, "/parser/sugar/sugar_grammar.rs");
-
 #[cfg(test)]
 mod nondeterm_tests {
     use std::fs;
-    use crate::grammar_rules::TwocParser;
+    use twoc::parser::sugar::sugar_grammar::TwocParser;
     use twoc::parser::sugar::convert_sugar::convert_sugar;
     use twoc::automaton::construction::construct_from_prog; 
+    use twoc::automaton::autom::Autom;
+    use twoc::automaton::compiled::{CompiledAutom, Symbol};
+    use twoc::automaton::generic_autom::State;
+    use twoc::automaton::one_way::is_one_way;
+    use twoc::simulation::streaming::{streaming_procedure, streaming_procedure_with_budget};
 
     use twoc::simulation::glueck_nondeterm::glueck_procedure;
     use twoc::simulation::rytter;
     use twoc::simulation::rytter_matrix;
+    use twoc::simulation::rytter_reachable;
+    use twoc::simulation::rytter_parallel;
+    use twoc::simulation::naive_nondeterm;
+    use twoc::simulation::parallel::simulate_words;
     use twoc::simulation::glueck_nondeterm;
+    use twoc::simulation::budget::{Budget, BudgetedProcedure, Exceeded, Fault, Outcome};
+    use twoc::parser::ast::{Cond, Readable, Stmt};
+    use twoc::parser::program::Program;
 
-    // Generic test function that runs a program on a single word and compares the outputs
-    fn generic_test(filename : &str, examples : &[(&str, bool)]) {
+    // The longest words that get checked against the naive procedure
+    const NAIVE_MAX_LEN : usize = 10;
+
+    // Load, desugar and construct the automaton for a program
+    fn load_autom(filename : &str) -> Autom {
         // Declare parser for Twoc rule
         let parser = TwocParser::new();
 
@@ -41,12 +52,53 @@ mod nondeterm_tests {
         prog.contract();
 
         // Construct the automaton from the program
-        let autom = construct_from_prog(prog);
+        construct_from_prog(prog)
+    }
+
+    // Generic test function that runs a program on a single word and compares the outputs
+    fn generic_test(filename : &str, examples : &[(&str, bool)]) {
+        let autom = load_autom(filename);
+        let compiled = CompiledAutom::new(&autom);
 
         // Check that each of the words gives the correct answer
         for (word, expected) in examples {
-            let output = rytter::rytter_procedure(&autom, word);
+            let output = rytter::rytter_procedure(&compiled, word);
             assert_eq!(output, *expected);
+
+            // The bit-packed matrix version should always agree
+            let matrix_output = rytter_matrix::rytter_procedure(&compiled, word);
+            assert_eq!(matrix_output, *expected, "rytter_matrix on {:?}", word);
+
+            // So should the version that only looks at reachable configs
+            let reachable_output = rytter_reachable::rytter_procedure(&compiled, word);
+            assert_eq!(reachable_output, *expected, "rytter_reachable on {:?}", word);
+
+            // And the one that computes R on several threads
+            let parallel_output = rytter_parallel::rytter_procedure(&compiled, word, 4);
+            assert_eq!(parallel_output, *expected, "rytter_parallel on {:?}", word);
+
+            // The naive search always terminates, so it's used as a reference
+            // Runs that pump the counter forever are searched up to a cutoff quadratic in the word length,
+            // so only short words are checked
+            if word.len() <= NAIVE_MAX_LEN {
+                assert_eq!(naive_nondeterm::naive(&compiled, word), *expected, "naive_nondeterm on {:?}", word);
+            }
+
+            // One-way automata can also be checked in a single sweep
+            if is_one_way(&autom) {
+                assert_eq!(streaming_procedure(&compiled, word), *expected, "streaming on {:?}", word);
+            }
+        }
+    }
+
+    // Same as generic_test, but checks the nondeterministic glueck procedure against rytter too
+    fn glueck_nondeterm_test(filename : &str, examples : &[(&str, bool)]) {
+        let autom = load_autom(filename);
+        let compiled = CompiledAutom::new(&autom);
+
+        for (word, expected) in examples {
+            assert_eq!(glueck_nondeterm::glueck_procedure(&compiled, word), *expected, "glueck_nondeterm on {:?}", word);
+            assert_eq!(rytter::rytter_procedure(&compiled, word), *expected, "rytter on {:?}", word);
         }
     }
 
@@ -299,4 +351,202 @@ mod nondeterm_tests {
 
         generic_test("./twocprogs/nondeterm/while_choose.twoc", &test_words);
     }
-}
\ No newline at end of file
+
+
+    // GLUECK REGRESSION TESTS
+    // These programs loop back to the same stripped configuration before all of its terminators are known
+
+    #[test]
+    pub fn glueck_nondeterm_potential_loop() {
+        let test_words = [
+            ("", false),
+            ("0", false),
+            ("00", false),
+            ("0000000000", false),
+
+            ("1", true),
+            ("01", true),
+            ("0010", true),
+            ("000100", true),
+            ("00100000", true),
+            ("000000110000", true),
+        ];
+
+        glueck_nondeterm_test("./twocprogs/nondeterm/potential_loop.twoc", &test_words);
+    }
+
+    #[test]
+    pub fn glueck_nondeterm_while_chooses() {
+        let test_words = [
+            ("", true),
+            ("x", true),
+            ("xx", true),
+            ("xxxxx", true),
+            ("xxxxxxxxxxxxxxxx", true),
+        ];
+
+        glueck_nondeterm_test("./twocprogs/nondeterm/while_chooses.twoc", &test_words);
+
+        // The shorter the word, the more times the loop has to go round before the guess is right,
+        // and the longer the fixpoint takes to reach the accepting run
+        let test_words = [
+            ("", true),
+            ("x", true),
+            ("xx", true),
+            ("xxxxx", true),
+            ("xxxxxxxxxx", true),
+
+            ("xxxxxxxxxxx", false),
+            ("xxxxxxxxxxxx", false),
+            ("xxxxxxxxxxxxxxxx", false),
+        ];
+
+        glueck_nondeterm_test("./twocprogs/nondeterm/at_most_ten.twoc", &test_words);
+    }
+
+
+    // PARALLEL SIMULATION
+
+    #[test]
+    pub fn simulate_words_shared_autom() {
+        let autom = load_autom("./twocprogs/nondeterm/equal_or_double_zeros_ones.twoc");
+        let compiled = CompiledAutom::new(&autom);
+
+        let words = ["", "01", "0011", "100", "110000", "0", "00011", "0001111", "111000000", "0011110011101111"];
+        let expected : Vec<bool> = words.iter().map(|word| rytter_matrix::rytter_procedure(&compiled, word)).collect();
+
+        // Results come back in the same order as the words, however many threads are used
+        for threads in [1, 3, 16] {
+            assert_eq!(simulate_words(&compiled, &words, rytter_reachable::rytter_procedure, threads), expected, "{} threads", threads);
+        }
+    }
+
+    // COMPILED AUTOMATA
+
+    // The precomputed guard tables should pick out exactly the transitions whose conditions hold
+    #[test]
+    pub fn compiled_guards_match_conditions() {
+        let programs = [
+            "./twocprogs/determ/brackets.twoc",
+            "./twocprogs/determ/upower.twoc",
+            "./twocprogs/nondeterm/equal_or_double_zeros_ones.twoc",
+            "./twocprogs/nondeterm/potential_loop.twoc",
+        ];
+
+        for filename in programs {
+            let autom = load_autom(filename);
+            let compiled = CompiledAutom::new(&autom);
+
+            for state in 0..autom.state_total {
+                for symbol in 0..compiled.num_symbols() as Symbol {
+                    for counter_zero in [true, false] {
+                        let read = compiled.readable(symbol);
+
+                        let enabled : Vec<(State, i32, i32)> = autom.get_transitions(state)
+                            .into_iter()
+                            .filter(|trans| trans.condition.as_ref().is_none_or(|cond| cond.check(read, if counter_zero {0} else {1})))
+                            .map(|trans| (trans.goto, trans.incr_by, trans.move_by))
+                            .collect();
+
+                        // Transitions that do the same thing only get compiled once
+                        let mut expected = Vec::new();
+                        for action in enabled {
+                            if !expected.contains(&action) { expected.push(action); }
+                        }
+
+                        let actual : Vec<(State, i32, i32)> = compiled.transitions(state, symbol, counter_zero)
+                            .iter()
+                            .map(|action| (action.goto, action.incr_by, action.move_by))
+                            .collect();
+
+                        assert_eq!(actual, expected, "{} from state {} on {:?}", filename, state, read);
+                    }
+                }
+            }
+        }
+    }
+
+    // BUDGETS
+
+    // Every procedure should stop once it runs out of budget, and not notice a budget it stays within
+    #[test]
+    pub fn budgets() {
+        let autom = load_autom("./twocprogs/nondeterm/equal_or_double_zeros_ones.twoc");
+        let compiled = CompiledAutom::new(&autom);
+        let word = "000000111111";
+
+        let procedures : [(&str, BudgetedProcedure); 6] = [
+            ("rytter", rytter::rytter_procedure_with_budget),
+            ("rytter_matrix", rytter_matrix::rytter_procedure_with_budget),
+            ("rytter_reachable", rytter_reachable::rytter_procedure_with_budget),
+            ("rytter_parallel", |autom, input, budget| rytter_parallel::rytter_procedure_with_budget(autom, input, 4, budget)),
+            ("glueck_nondeterm", glueck_nondeterm::glueck_procedure_with_budget),
+            ("naive", naive_nondeterm::naive_with_budget),
+        ];
+
+        let tight = Budget { max_steps : Some(10), ..Budget::default() };
+        let small = Budget { max_configs : Some(10), ..Budget::default() };
+        let big = Budget { max_steps : Some(100_000_000), max_configs : Some(100_000_000), deadline : None };
+
+        let expected = match rytter_matrix::rytter_procedure(&compiled, word) {
+            true  => Outcome::Accepted,
+            false => Outcome::Rejected,
+        };
+
+        for (name, procedure) in procedures {
+            assert_eq!(procedure(&compiled, word, &tight).0, Outcome::BudgetExceeded(Exceeded::Steps), "{}", name);
+            assert_eq!(procedure(&compiled, word, &small).0, Outcome::BudgetExceeded(Exceeded::Configs), "{}", name);
+            assert_eq!(procedure(&compiled, word, &big).0, expected, "{}", name);
+        }
+
+        // The bit matrix is never allocated if it would be too big
+        let huge_word = "0".repeat(100_000);
+        let budget = Budget { max_configs : Some(1_000_000), ..Budget::default() };
+        let (outcome, stats) = rytter_matrix::rytter_procedure_with_budget(&compiled, &huge_word, &budget);
+        assert_eq!(outcome, Outcome::BudgetExceeded(Exceeded::Configs));
+        assert_eq!(stats.table_entries, None);
+    }
+
+    // The streaming procedure fails, rather than panicking, if it's run on an automaton that pumps the
+    // counter without moving, which isn't one-way
+    #[test]
+    pub fn streaming_counter_bound() {
+        let mut prog = Program::new(vec![Stmt::While(Cond::T(), vec![Stmt::Incr(1)])], vec!['0'], false);
+        prog.contract();
+
+        let autom = construct_from_prog(prog);
+        assert!(!is_one_way(&autom));
+
+        let compiled = CompiledAutom::new(&autom);
+        let (outcome, _) = streaming_procedure_with_budget(&compiled, "00", &Budget::default());
+        assert!(matches!(outcome, Outcome::Failed(Fault::CounterBound(_))));
+    }
+
+    // Programs the random program generator found the Rytter procedures getting wrong
+    #[test]
+    pub fn clamped_moves_and_empty_pops() {
+        let cases = [
+            // Moving off of either end of the tape leaves the read head on the endmarker
+            (vec![Stmt::Move(-1), Stmt::Accept()], false, ["", "a", "ab"], true),
+            (vec![Stmt::Move(3), Stmt::If(Cond::Read(Readable::REnd()), vec![Stmt::Accept()], vec![Stmt::Reject()])], false, ["", "a", "ab"], true),
+
+            // Decrementing an empty counter leaves it empty, without a push to match
+            (vec![Stmt::Incr(-1), Stmt::Accept()], true, ["", "a", "ab"], true),
+            (vec![Stmt::Incr(-1), Stmt::Accept()], false, ["", "a", "ab"], false),
+            (vec![Stmt::Incr(-1), Stmt::If(Cond::CheckZero(), vec![Stmt::Accept()], vec![Stmt::Reject()])], true, ["", "a", "ab"], true),
+        ];
+
+        for (stmts, decr_zero, words, expected) in cases {
+            let mut prog = Program::new(stmts, vec!['a', 'b'], decr_zero);
+            prog.contract();
+
+            let compiled = CompiledAutom::new(&construct_from_prog(prog));
+
+            for word in words {
+                assert_eq!(rytter::rytter_procedure(&compiled, word), expected, "rytter on {:?}", word);
+                assert_eq!(rytter_matrix::rytter_procedure(&compiled, word), expected, "rytter_matrix on {:?}", word);
+                assert_eq!(naive_nondeterm::naive(&compiled, word), expected, "naive_nondeterm on {:?}", word);
+            }
+        }
+    }
+}
@@ -4,8 +4,241 @@
 
 // Import grammar
 #[macro_use] extern crate lalrpop_util;
-lalrpop_mod!(pub grammar_rules, "/parser/grammar_rules.rs");
-lalrpop_mod!(pub sugar_grammar, "/parser/sugar/sugar_grammar.rs");
+lalrpop_mod!(#[allow(clippy::all)] pub grammar_rules, "/parser/grammar_rules.rs");
+
+// A global allocator that keeps track of how much memory the current thread has allocated
+// Tests run on their own threads, so each one only sees its own allocations
+mod counting_alloc {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    pub struct CountingAlloc;
+
+    thread_local! {
+        // The bytes currently allocated by this thread
+        static CURRENT : Cell<usize> = const { Cell::new(0) };
+
+        // The most bytes this thread has had allocated at once since the last reset
+        static PEAK : Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAlloc {
+        unsafe fn alloc(&self, layout : Layout) -> *mut u8 {
+            let ptr = unsafe { System.alloc(layout) };
+            if !ptr.is_null() { grow(layout.size()); }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout : Layout) -> *mut u8 {
+            let ptr = unsafe { System.alloc_zeroed(layout) };
+            if !ptr.is_null() { grow(layout.size()); }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr : *mut u8, layout : Layout) {
+            unsafe { System.dealloc(ptr, layout) };
+            shrink(layout.size());
+        }
+
+        unsafe fn realloc(&self, ptr : *mut u8, layout : Layout, new_size : usize) -> *mut u8 {
+            let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+            if !new_ptr.is_null() {
+                shrink(layout.size());
+                grow(new_size);
+            }
+            new_ptr
+        }
+    }
+
+    // The thread locals may already be gone while a thread is shutting down, so ignore those allocations
+    fn grow(bytes : usize) {
+        let _ = CURRENT.try_with(|current| {
+            current.set(current.get() + bytes);
+            let _ = PEAK.try_with(|peak| peak.set(peak.get().max(current.get())));
+        });
+    }
+
+    // Memory can be freed by a different thread to the one that allocated it, so don't go below zero
+    fn shrink(bytes : usize) {
+        let _ = CURRENT.try_with(|current| current.set(current.get().saturating_sub(bytes)));
+    }
+
+    // Run f and return its result along with the most memory it had allocated at once
+    pub fn peak_bytes<T>(f : impl FnOnce() -> T) -> (T, usize) {
+        let start = CURRENT.with(|current| current.get());
+        PEAK.with(|peak| peak.set(start));
+
+        let out = f();
+
+        (out, PEAK.with(|peak| peak.get()) - start)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR : counting_alloc::CountingAlloc = counting_alloc::CountingAlloc;
+
+// Rytter's algorithm with R stored as an Array2D<bool>, as rytter_matrix did before it moved to a BitMatrix
+// Everything apart from how R is stored and scanned is the same as rytter_matrix, so the two can be compared directly
+mod dense_rytter {
+    use std::collections::{VecDeque, HashMap};
+
+    use array2d::Array2D;
+
+    use twoc::automaton::compiled::{Action, CompiledAutom, Tape};
+    use twoc::automaton::generic_autom::State;
+    use twoc::simulation::config::{Config, StrippedConfig, get_transitions, strip_config, next_nondeterm, move_sources};
+    use twoc::simulation::rytter::zero_pops;
+
+    pub fn rytter_procedure(autom : &CompiledAutom, input : &str) -> bool {
+        let mut simulator = DenseRytterSimulator::new(autom, autom.encode(input));
+        simulator.simulate()
+    }
+
+    struct DenseRytterSimulator<'a> {
+        autom : &'a CompiledAutom,
+
+        input : Tape,
+
+        n : i32,
+
+        configs : Vec<StrippedConfig>,
+
+        num_configs : usize,
+
+        queue : VecDeque<(usize, usize)>,
+
+        conf_matrix : Array2D<bool>,
+
+        inverse_state_map : HashMap<State, Vec<(State, Action)>>,
+    }
+
+    impl<'a> DenseRytterSimulator<'a> {
+        fn new(autom : &'a CompiledAutom, input : Tape) -> Self {
+            let n = input.len() as i32;
+
+            // Initialise configs list
+            let mut configs = Vec::new();
+            for state in 0..autom.state_total {
+                for index in 0..n {
+                    for counter in [true, false] {
+                        configs.push((state, index, counter));
+                    }
+                }
+            }
+
+            let num_configs = configs.len();
+
+            // Fill the queue and matrix with the reflexive pairs
+            let mut queue = VecDeque::new();
+            let mut conf_matrix = Array2D::filled_with(false, num_configs, num_configs);
+            for cfg in 0..num_configs {
+                queue.push_back((cfg, cfg));
+                conf_matrix.set(cfg, cfg, true).unwrap();
+            }
+
+            // Construct inverse state map
+            let mut inverse_state_map = HashMap::new();
+            for state in 0..autom.state_total {
+                inverse_state_map.insert(state, Vec::new());
+            }
+
+            for from_state in 0..autom.state_total {
+                for action in autom.all_transitions(from_state) {
+                    inverse_state_map.get_mut(&action.goto).unwrap().push((from_state, action));
+                }
+            }
+
+            let mut simulator = Self { autom, input, n, configs, num_configs, queue, conf_matrix, inverse_state_map };
+
+            // Popping an empty counter relates configs without a matching push
+            for (from, to) in zero_pops(autom, &simulator.input) {
+                let (i, j) = (simulator.get_index(from), simulator.get_index(to));
+                simulator.relate(i, j);
+            }
+
+            simulator
+        }
+
+        fn simulate(&mut self) -> bool {
+            while let Some((i, j)) = self.queue.pop_front() {
+                for (k, l) in self.below(i, j) {
+                    self.relate(k, l);
+                }
+
+                // for each (k, i) in R such that (k, j) notin R do
+                for k in 0..self.num_configs {
+                    if self.conf_matrix[(k, i)] { self.relate(k, j); }
+                }
+
+                // for each (j, k) in R such that (i, k) notin R do
+                for k in 0..self.num_configs {
+                    if self.conf_matrix[(j, k)] { self.relate(i, k); }
+                }
+            }
+
+            // Accept if the start config is related to an accepting config
+            let start_conf = self.get_index((0, 0, true));
+            (0..self.num_configs).any(|conf| {
+                self.conf_matrix[(start_conf, conf)] && self.autom.check_if_halting(self.configs[conf].0) == Some(true)
+            })
+        }
+
+        // Add (i, j) to R, queueing it if it wasn't already there
+        fn relate(&mut self, i : usize, j : usize) {
+            if !self.conf_matrix[(i, j)] {
+                self.conf_matrix[(i, j)] = true;
+                self.queue.push_back((i, j));
+            }
+        }
+
+        fn get_index(&self, (state, index, counter_zero) : StrippedConfig) -> usize {
+            (if counter_zero {0} else {1}) + (index * 2) as usize + ((state as i32) * self.n * 2) as usize
+        }
+
+        // Find all the pairs of configurations below a given pair, as in rytter_matrix
+        fn below(&self, i : usize, j : usize) -> Vec<(usize, usize)> {
+            let (i_state, i_index, i_counter_zero) = self.configs[i];
+            let (j_state, j_index, j_counter_zero) = self.configs[j];
+
+            if i_counter_zero { return vec![]; }
+
+            // The configs that push onto i
+            let mut k_configs = Vec::new();
+            for (new_state, action) in &self.inverse_state_map[&i_state] {
+                if action.incr_by <= 0 { continue; }
+
+                for new_read in move_sources(i_index, action.move_by, self.n) {
+                    for counter_zero in [false, true] {
+                        if self.autom.transitions(*new_state, self.input[new_read as usize], counter_zero).contains(action) {
+                            k_configs.push((*new_state, new_read, counter_zero));
+                        }
+                    }
+                }
+            }
+
+            // The configs that j pops to
+            let j_conf = Config { state : j_state, read : j_index, counter : if j_counter_zero {0} else {1} };
+            let mut l_configs = Vec::new();
+            for trans in get_transitions(self.autom, j_conf, &self.input) {
+                if trans.incr_by >= 0 { continue; }
+
+                if let Some(conf) = next_nondeterm(j_conf, *trans, &self.input, self.autom.decr_zero) {
+                    let (state, read, _) = strip_config(conf);
+                    l_configs.push((state, read));
+                }
+            }
+
+            let mut out = Vec::new();
+            for k_conf in &k_configs {
+                for (l_state, l_read) in &l_configs {
+                    out.push((self.get_index(*k_conf), self.get_index((*l_state, *l_read, k_conf.2))));
+                }
+            }
+
+            out
+        }
+    }
+}
 
 #[cfg(test)]
 mod nondeterm_bench {
@@ -14,21 +247,33 @@ mod nondeterm_bench {
     use std::time::Instant;
 
     use crate::grammar_rules::TwocParser;
-    use crate::sugar_grammar::TwocParser as SugarTwocParser;
+    use twoc::parser::sugar::sugar_grammar::TwocParser as SugarTwocParser;
     use twoc::parser::sugar::convert_sugar::convert_sugar;
     use twoc::automaton::construction; 
+    use twoc::automaton::compiled::CompiledAutom;
     //use twoc::automaton::autom::Autom;
     use twoc::simulation::rytter;
     use twoc::simulation::rytter_matrix;
     use twoc::simulation::glueck_nondeterm;
+    use crate::dense_rytter;
+    use crate::counting_alloc::peak_bytes;
 
 
+    // The number of sizes to run a benchmark on
+    // The test suite only runs the first few, so set TWOC_BENCH_FULL to run the whole sweep and record its timings
+    fn sweep_length(tests : usize) -> usize {
+        match std::env::var_os("TWOC_BENCH_FULL") {
+            Some(_) => tests,
+            None => tests.min(3),
+        }
+    }
+
     #[test]
     pub fn string_length_performance_test() {
         // Loop params
         let start = 10000;
         let step = 500;
-        let tests = 100;
+        let tests = sweep_length(100);
 
         // Initialise last
         let mut last = 0.0f32;
@@ -56,6 +301,7 @@ mod nondeterm_bench {
 
         // Construct the automaton from the program
         let autom = construction::construct_from_prog(prog);
+        let compiled = CompiledAutom::new(&autom);
 
         for n in (start..(start + step*tests + 1)).step_by(step) {
             // Generate a string of n 0s and n 1s
@@ -67,7 +313,7 @@ mod nondeterm_bench {
             // Run test with a massive stack
             //thread::scope(|s| {
             //    thread::Builder::new().stack_size(0xFFFF * n)
-            //    .spawn_scoped(s, || thread_function(&autom, test_word))
+            //    .spawn_scoped(s, || thread_function(&compiled, test_word))
              //   .unwrap();
             //});
 
@@ -89,7 +335,7 @@ mod nondeterm_bench {
         // Loop params
         let start = 0;
         let step = 1;
-        let tests = 1000;
+        let tests = sweep_length(1000);
 
         // Initialise last
         let mut last = 0.0f32;
@@ -98,25 +344,29 @@ mod nondeterm_bench {
         let path = "./tests/bench_results/pl_2704_glueck_nondeterm.csv";
         let mut file = fs::File::create(path).expect("File creation failed");
 
-        // Declare parser for Twoc rule
-        let parser = TwocParser::new();
+        // Declare parser for Twoc rule (potential_loop uses sugar)
+        let parser = SugarTwocParser::new();
 
         // Load file
         let test_prog = fs::read_to_string("./twocprogs/nondeterm/potential_loop.twoc").expect("File not found");
 
         // Parse the file
         let test = parser.parse(&test_prog);
-        let mut prog = match test {
+        let sugar_prog = match test {
             // Output any parse errors
             Err(ref err) => panic!("Parse Error:\n{:?}", err),
             Ok(prog) => prog,
         };
 
+        // Desugar
+        let mut prog = convert_sugar(sugar_prog);
+
         // Contract the AST
         prog.contract();
 
         // Construct the automaton from the program
         let autom = construction::construct_from_prog(prog);
+        let compiled = CompiledAutom::new(&autom);
 
         for n in (start..(start + step*tests + 1)).step_by(step) {
             // Generate a string of n 0s and n 1s
@@ -128,7 +378,7 @@ mod nondeterm_bench {
             // Run test with a massive stack
             thread::scope(|s| {
                 thread::Builder::new().stack_size(0xFFFF * n)
-                .spawn_scoped(s, || glueck_nondeterm::glueck_procedure(&autom, &test_word))
+                .spawn_scoped(s, || glueck_nondeterm::glueck_procedure(&compiled, &test_word))
                 .unwrap();
             });
 
@@ -151,7 +401,7 @@ mod nondeterm_bench {
         // Loop params
         let start = 0;
         let step = 1;
-        let tests = 100;
+        let tests = sweep_length(100);
 
         // Initialise last
         let mut last_hashmap = 0.0f32;
@@ -183,6 +433,7 @@ mod nondeterm_bench {
 
         // Construct the automaton from the program
         let autom = construction::construct_from_prog(prog);
+        let compiled = CompiledAutom::new(&autom);
 
         for n in (start..(start + step*tests + 1)).step_by(step) {
             println!("n = {:?}", n);
@@ -192,14 +443,14 @@ mod nondeterm_bench {
 
             // HASHMAP TEST
             let now = Instant::now();
-            rytter::rytter_procedure(&autom, &test_word);
+            rytter::rytter_procedure(&compiled, &test_word);
             let time_taken_hashmap = now.elapsed().as_secs_f32();
             let delta_t_hashmap = time_taken_hashmap - last_hashmap;
 
 
             // MATRIX TEST
             let now = Instant::now();
-            rytter_matrix::rytter_procedure(&autom, &test_word);
+            rytter_matrix::rytter_procedure(&compiled, &test_word);
             let time_taken_matrix = now.elapsed().as_secs_f32();
             let delta_t_matrix = time_taken_matrix - last_matrix;
 
@@ -220,7 +471,7 @@ mod nondeterm_bench {
         // Loop params
         let start = 0;
         let step = 1;
-        let tests = 100;
+        let tests = sweep_length(100);
 
         // Initialise last
         let mut last_hashmap = 0.0f32;
@@ -255,6 +506,7 @@ mod nondeterm_bench {
 
         // Construct the automaton from the program
         let autom = construction::construct_from_prog(prog);
+        let compiled = CompiledAutom::new(&autom);
 
         for n in (start..(start + step*tests + 1)).step_by(step) {
             println!("n = {:?}", n);
@@ -264,14 +516,14 @@ mod nondeterm_bench {
 
             // HASHMAP TEST
             let now = Instant::now();
-            rytter::rytter_procedure(&autom, &test_word);
+            rytter::rytter_procedure(&compiled, &test_word);
             let time_taken_hashmap = now.elapsed().as_secs_f32();
             let delta_t_hashmap = time_taken_hashmap - last_hashmap;
 
 
             // MATRIX TEST
             let now = Instant::now();
-            rytter_matrix::rytter_procedure(&autom, &test_word);
+            rytter_matrix::rytter_procedure(&compiled, &test_word);
             let time_taken_matrix = now.elapsed().as_secs_f32();
             let delta_t_matrix = time_taken_matrix - last_matrix;
 
@@ -286,4 +538,64 @@ mod nondeterm_bench {
             last_matrix = time_taken_matrix;
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    pub fn bit_matrix_performance_test() {
+        // Loop params
+        let start = 0;
+        let step = 10;
+        let tests = sweep_length(10);
+
+        // Create output file
+        let path = "./tests/bench_results/bit_matrix_rytter.csv";
+        let mut file = fs::File::create(path).expect("File creation failed");
+
+        // Declare parser for Twoc rule
+        let parser = TwocParser::new();
+
+        // Load file
+        let test_prog = fs::read_to_string("./twocprogs/determ/very_long.twoc").expect("File not found");
+
+        // Parse the file
+        let test = parser.parse(&test_prog);
+        let mut prog = match test {
+            // Output any parse errors
+            Err(ref err) => panic!("Parse Error:\n{:?}", err),
+            Ok(prog) => prog,
+        };
+
+        // Contract the AST
+        prog.contract();
+
+        // Construct the automaton from the program
+        let autom = construction::construct_from_prog(prog);
+        let compiled = CompiledAutom::new(&autom);
+
+        for n in (start..(start + step*tests + 1)).step_by(step) {
+            // Generate a string of n 0s
+            let test_word = "0".repeat(n);
+
+            // ARRAY2D TEST
+            let now = Instant::now();
+            let (dense_result, dense_bytes) = peak_bytes(|| dense_rytter::rytter_procedure(&compiled, &test_word));
+            let time_taken_dense = now.elapsed().as_secs_f32();
+
+            // BIT MATRIX TEST
+            let now = Instant::now();
+            let (packed_result, packed_bytes) = peak_bytes(|| rytter_matrix::rytter_procedure(&compiled, &test_word));
+            let time_taken_packed = now.elapsed().as_secs_f32();
+
+            // Both matrices should give the same answer, and packing R into bits should take less memory
+            assert_eq!(dense_result, packed_result, "n = {}", n);
+            assert!(packed_bytes < dense_bytes, "n = {}: bit matrix used {}B, Array2D used {}B", n, packed_bytes, dense_bytes);
+
+            // Output and save times and peak memory usage
+            println!(
+                "n = {:?}, Array2D t = {:?}, bit matrix t = {:?}, Array2D peak = {:?}B, bit matrix peak = {:?}B",
+                n, time_taken_dense, time_taken_packed, dense_bytes, packed_bytes
+            );
+            let to_file = format!("{:?},{:?},{:?},{:?},{:?}\n", n, time_taken_dense, time_taken_packed, dense_bytes, packed_bytes);
+            file.write_all(to_file.as_bytes()).expect("File write failed");
+        }
+    }
+}
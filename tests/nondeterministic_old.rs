@@ -2,13 +2,14 @@
 
 // Import grammar
 #[macro_use] extern crate lalrpop_util;
-lalrpop_mod!(pub grammar_rules, "/parser/grammar_rules.rs");
+lalrpop_mod!(#[allow(clippy::all)] pub grammar_rules, "/parser/grammar_rules.rs");
 
 #[cfg(test)]
 mod determ_tests {
     use std::fs;
     use crate::grammar_rules::TwocParser;
     use twoc::automaton::construction; 
+    use twoc::automaton::compiled::CompiledAutom;
     use twoc::simulation::rytter::rytter_procedure;
 
     // Generic test function that runs a program on a single word and compares the outputs
@@ -32,10 +33,11 @@ mod determ_tests {
 
         // Construct the automaton from the program
         let autom = construction::construct_from_prog(prog);
+        let compiled = CompiledAutom::new(&autom);
 
         // Check that each of the words gives the correct answer
         for (word, expected) in examples {
-            let glueck_output = rytter_procedure(&autom, word);
+            let glueck_output = rytter_procedure(&compiled, word);
             assert_eq!(glueck_output, *expected);
         }
     }
@@ -1,17 +1,14 @@
 // Deprecated tests of the desugaring process
 
-// Import grammar
-#[macro_use] extern crate lalrpop_util;
-lalrpop_mod!(pub grammar_rules, "/parser/sugar/sugar_grammar.rs");
-
 #[cfg(test)]
 mod sugar_parse {
     use std::fs;
-    use crate::grammar_rules::TwocParser;
+    use twoc::parser::sugar::sugar_grammar::TwocParser;
     use twoc::parser::sugar::convert_sugar::convert_sugar;
     use twoc::automaton::determ_construction::construct_from_prog;
     //use twoc::simulation::glueck::glueck_procedure;
 
+    // Run with `cargo test -- --nocapture` to see the printed ASTs
     #[test]
     fn test() {
         // Declare parser for Twoc rule
@@ -68,7 +65,5 @@ mod sugar_parse {
             }
         }
         */
-
-        panic!("panic to show stdout; don't worry about me hoo hoo hee hee")
     }
 }
\ No newline at end of file
@@ -0,0 +1,67 @@
+// Tests of the word family generators
+
+#[cfg(test)]
+mod word_family_tests {
+    use twoc::words::pattern::{Pattern, Exponent};
+    use twoc::words::families::{Rng, pattern_family, random_words, all_words, parse_range};
+
+    // Instantiate a pattern that doesn't pick any random letters
+    fn instantiate(pattern : &str, n : usize) -> String {
+        Pattern::parse(pattern).unwrap().instantiate(n, &[], &mut Rng::new(0))
+    }
+
+    #[test]
+    pub fn patterns() {
+        assert_eq!(instantiate("0^{n}1^{n}", 3), "000111");
+        assert_eq!(instantiate("(01)^{n}", 3), "010101");
+        assert_eq!(instantiate("0^{n} 1^{2n+1}", 2), "0011111");
+        assert_eq!(instantiate("0^{3*n - 1}", 1), "00");
+        assert_eq!(instantiate("a(b^2c)^{n}", 2), "abbcbbc");
+        assert_eq!(instantiate("0^{n-1}", 0), "");
+        assert_eq!(instantiate("", 5), "");
+
+        assert_eq!(
+            Pattern::parse("(01)^{2n+1}").unwrap(),
+            Pattern::Repeat(
+                Box::new(Pattern::Concat(vec![Pattern::Letter('0'), Pattern::Letter('1')])),
+                Exponent { times_n : 2, plus : 1 },
+            )
+        );
+
+        for bad in ["(01", "01)", "0^", "0^{n", "0^{m}", "0^{}", "0+1"] {
+            assert!(Pattern::parse(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    pub fn families() {
+        let pattern = Pattern::parse("0^{n}1^{n}").unwrap();
+        let family = pattern_family(&pattern, &parse_range("1..10", 4).unwrap(), &[], 0);
+        assert_eq!(family, vec![(1, "01".to_string()), (5, "0000011111".to_string()), (9, "0".repeat(9) + &"1".repeat(9))]);
+
+        // Random letters come from the alphabet, and the same seed gives the same words
+        let pattern = Pattern::parse(".^{n}").unwrap();
+        let ns = parse_range("0..=20", 1).unwrap();
+        let family = pattern_family(&pattern, &ns, &['a', 'b'], 42);
+        assert_eq!(family, pattern_family(&pattern, &ns, &['a', 'b'], 42));
+        assert!(family.iter().all(|(n, word)| word.len() == *n && word.chars().all(|c| c == 'a' || c == 'b')));
+
+        let words = random_words(&['0', '1'], 16, 10, 7);
+        assert_eq!(words, random_words(&['0', '1'], 16, 10, 7));
+        assert_ne!(words, random_words(&['0', '1'], 16, 10, 8));
+        assert!(words.iter().all(|word| word.len() == 16));
+
+        // All words come shortest first
+        assert_eq!(all_words(&['1', '0'], 2), vec!["", "0", "1", "00", "01", "10", "11"]);
+        assert_eq!(all_words(&['a', 'b', 'c'], 4).len(), 1 + 3 + 9 + 27 + 81);
+    }
+
+    #[test]
+    pub fn ranges() {
+        assert_eq!(parse_range("1..1000", 250), Ok(vec![1, 251, 501, 751]));
+        assert_eq!(parse_range("0..=4", 2), Ok(vec![0, 2, 4]));
+        assert_eq!(parse_range("7", 1), Ok(vec![7]));
+        assert!(parse_range("1..x", 1).is_err());
+        assert!(parse_range("1..5", 0).is_err());
+    }
+}
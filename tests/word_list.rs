@@ -0,0 +1,25 @@
+// Tests of the word lists used by batch runs
+
+#[cfg(test)]
+mod word_list_tests {
+    use twoc::words::word_list::{ListedWord, parse_word_list};
+
+    #[test]
+    pub fn parse_words_and_verdicts() {
+        let text = "# comment\n\n\"\" accept\n0011\n  0101   reject  \n";
+
+        let expected = vec![
+            ListedWord { word : String::new(), expected : Some(true), line : 3 },
+            ListedWord { word : "0011".to_string(), expected : None, line : 4 },
+            ListedWord { word : "0101".to_string(), expected : Some(false), line : 5 },
+        ];
+
+        assert_eq!(parse_word_list(text), Ok(expected));
+    }
+
+    #[test]
+    pub fn bad_verdicts() {
+        assert!(parse_word_list("0011 maybe").is_err());
+        assert!(parse_word_list("0011 accept reject").is_err());
+    }
+}
@@ -0,0 +1,65 @@
+// Development tasks for twoc that don't belong in its build
+//
+// cargo run -p xtask -- header        regenerates include/twoc.h from src/ffi.rs
+// cargo run -p xtask -- check-header  fails if include/twoc.h is out of date with src/ffi.rs
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::{env, fs};
+
+// The root of the twoc repository, which this crate lives in
+fn root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).parent().unwrap().to_path_buf()
+}
+
+// The C header for the API in src/ffi.rs, as cbindgen.toml says to generate it
+fn generate_header() -> Result<Vec<u8>, String> {
+    let root = root();
+    let config = cbindgen::Config::from_file(root.join("cbindgen.toml"))
+        .map_err(|err| format!("Couldn't read cbindgen.toml: {}", err))?;
+
+    let bindings = cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(root.join("src/ffi.rs"))
+        .generate()
+        .map_err(|err| format!("Couldn't generate the C header: {}", err))?;
+
+    let mut header = Vec::new();
+    bindings.write(&mut header);
+    Ok(header)
+}
+
+fn run(task : &str) -> Result<(), String> {
+    let path = root().join("include/twoc.h");
+
+    match task {
+        "header" => {
+            fs::write(&path, generate_header()?).map_err(|err| format!("Couldn't write {}: {}", path.display(), err))?;
+            println!("Wrote {}", path.display());
+            Ok(())
+        },
+
+        "check-header" => {
+            let current = fs::read(&path).map_err(|err| format!("Couldn't read {}: {}", path.display(), err))?;
+
+            match current == generate_header()? {
+                true  => Ok(()),
+                false => Err("include/twoc.h is out of date with src/ffi.rs; run `cargo run -p xtask -- header`".to_string()),
+            }
+        },
+
+        _ => Err(format!("There's no task called {:?}; the tasks are header and check-header", task)),
+    }
+}
+
+fn main() -> ExitCode {
+    let task = env::args().nth(1).unwrap_or_default();
+
+    match run(&task) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        },
+    }
+}
@@ -0,0 +1,12 @@
+// Checks that the checked in C header matches the API in src/ffi.rs
+
+#[cfg(test)]
+mod header_tests {
+    use std::process::Command;
+
+    #[test]
+    pub fn header_is_up_to_date() {
+        let output = Command::new(env!("CARGO_BIN_EXE_xtask")).arg("check-header").output().expect("Couldn't run xtask");
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+}